@@ -1,10 +1,7 @@
+use stwo_examples::cli::log_size_arg;
 use stwo_prover::core::{
     backend::{
-        simd::{
-            column::BaseColumn,
-            m31::{LOG_N_LANES, N_LANES},
-            SimdBackend,
-        },
+        simd::{column::BaseColumn, SimdBackend},
         Column,
     },
     channel::{Blake2sChannel, Channel},
@@ -25,8 +22,8 @@ fn main() {
     // --snip--
 
     // ANCHOR_END: here_1
-    let num_rows = N_LANES;
-    let log_num_rows = LOG_N_LANES;
+    let log_num_rows = log_size_arg();
+    let num_rows = 1 << log_num_rows;
 
     // Create the table
     let mut col_1 = BaseColumn::zeros(num_rows);
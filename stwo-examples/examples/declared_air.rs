@@ -0,0 +1,70 @@
+//! Proves and verifies an AIR described in the format of `stwo_examples::declared`, over a trace
+//! imported from a spreadsheet, without any Rust specific to the AIR.
+//!
+//! Usage: `cargo run --example declared_air -- [--spec <file>] [--trace <file.csv|file.tsv>]
+//! [--log-size <n>]`
+
+use std::{env, fs, path::Path};
+
+use stwo_examples::{
+    cli::{log_size_arg, parse_flag},
+    declared::{parse_spec, verify_declared, DeclaredAir},
+    pipeline::prove_air,
+    spreadsheet::{Delimiter, ImportOptions},
+};
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
+
+const SPEC: &str = "\
+# The AIR of \"Proving an AIR\", where col_2 is also a permutation of col_1
+column col_1
+column col_2
+column col_3
+preprocessed is_first = is_first
+relation values 1
+
+constraint col_1 * col_2 + col_1 - col_3
+constraint is_first * (col_2[1] - col_1)
+lookup values 1 : col_1
+lookup values -1 : col_2
+";
+
+const TRACE: &str = "\
+col_1,col_2,col_3
+5,7,40
+7,5,42
+";
+
+fn read_flag_file(name: &str) -> Option<(String, String)> {
+    let path =
+        parse_flag::<String>(env::args().skip(1), name).unwrap_or_else(|err| panic!("{err}"))?;
+    let text = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{path}: {err}"));
+    Some((path, text))
+}
+
+fn main() {
+    let spec = match read_flag_file("spec") {
+        Some((path, text)) => parse_spec(&text).unwrap_or_else(|err| panic!("{path}: {err}")),
+        None => parse_spec(SPEC).unwrap(),
+    };
+    let (delimiter, trace) = match read_flag_file("trace") {
+        Some((path, text)) => (Delimiter::from_path(Path::new(&path)), text),
+        None => (Delimiter::Comma, TRACE.to_string()),
+    };
+    let options = ImportOptions {
+        delimiter,
+        min_log_size: log_size_arg(),
+        bit_reverse: false,
+    };
+    let air = DeclaredAir::from_spreadsheet(spec.clone(), &trace, options)
+        .unwrap_or_else(|err| panic!("{err}"));
+
+    let config = PcsConfig::default();
+    let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+    verify_declared::<Blake2sMerkleChannel>(proof, &spec, config).unwrap();
+    println!(
+        "proved and verified {} constraints and {} lookups over 2^{} rows",
+        spec.constraints.len(),
+        spec.lookups.len(),
+        air.log_size
+    );
+}
@@ -0,0 +1,29 @@
+use num_bigint::BigUint;
+use rand::Rng;
+use stwo_examples::{
+    cli::log_size_arg,
+    debug::constraints::{assert_constraints_hold, DebugTrace},
+    gadgets::felt252::{gen_add_trace, prime, Felt252AddEval},
+};
+
+// Adds random Felt252 values with the addition gadget and checks that the constraints hold on the
+// generated trace. The comparison with big-integer arithmetic and the rejection of tampered traces
+// are in `tests/gadgets.rs`.
+fn main() {
+    let log_size = log_size_arg();
+    let p = prime();
+    let mut rng = rand::thread_rng();
+
+    let operands = (0..1 << log_size)
+        .map(|_| [(); 2].map(|_| BigUint::from_slice(&rng.gen::<[u32; 8]>()) % &p))
+        .map(|[a, b]| (a, b))
+        .collect::<Vec<_>>();
+    let columns = gen_add_trace(log_size, &operands)
+        .iter()
+        .map(|column| column.as_slice().to_vec())
+        .collect();
+
+    let eval = Felt252AddEval { log_size };
+    assert_constraints_hold(&eval, &DebugTrace::from_row_order(vec![], columns));
+    println!("constraints hold on {} additions", operands.len());
+}
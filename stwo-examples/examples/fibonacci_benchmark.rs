@@ -0,0 +1,33 @@
+//! Runs the Fibonacci workload of the Benchmarks Report and prints its metrics as JSON.
+//!
+//! Usage: `cargo run --release --example fibonacci_benchmark -- [--log-size <n>]
+//! [--max-log-size <n>] [--instances <n>]`
+//!
+//! Each instance runs `2^log_size` steps, for every log size from `--log-size` to
+//! `--max-log-size`.
+
+use stwo_examples::{
+    airs::fibonacci::FibonacciAir,
+    bench::measure,
+    cli::{flag_arg, log_size_arg},
+};
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
+
+fn main() {
+    let min_log_size = log_size_arg();
+    let max_log_size = flag_arg("max-log-size", min_log_size);
+    let n_instances = flag_arg("instances", 1);
+
+    let metrics = (min_log_size..=max_log_size)
+        .map(|log_n_steps| {
+            let air = FibonacciAir {
+                log_n_steps,
+                n_instances,
+            };
+            measure::<_, Blake2sMerkleChannel>(&air, air.n_steps(), PcsConfig::default())
+                .unwrap_or_else(|err| panic!("log size {log_n_steps}: {err}"))
+        })
+        .collect::<Vec<_>>();
+
+    println!("{}", serde_json::to_string_pretty(&metrics).unwrap());
+}
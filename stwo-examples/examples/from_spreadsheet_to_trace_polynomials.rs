@@ -1,10 +1,7 @@
+use stwo_examples::cli::log_size_arg;
 use stwo_prover::core::{
     backend::{
-        simd::{
-            column::BaseColumn,
-            m31::{LOG_N_LANES, N_LANES},
-            SimdBackend,
-        },
+        simd::{column::BaseColumn, SimdBackend},
         Column,
     },
     fields::m31::M31,
@@ -18,8 +15,8 @@ use stwo_prover::core::{
 // ANCHOR: main_start
 fn main() {
     // ANCHOR_END: main_start
-    let num_rows = N_LANES;
-    let log_num_rows = LOG_N_LANES;
+    let log_num_rows = log_size_arg();
+    let num_rows = 1 << log_num_rows;
 
     // Create the table
     let mut col_1 = BaseColumn::zeros(num_rows);
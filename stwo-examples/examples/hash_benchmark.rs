@@ -0,0 +1,69 @@
+//! Runs the Sha2, Sha2-Chain, Sha3 and Sha3-Chain workloads of the Benchmarks Report and prints
+//! their metrics as JSON.
+//!
+//! Usage: `cargo run --release --example hash_benchmark -- [--messages <n>] [--chain-length <n>]`
+//!
+//! The single hashes take `--messages` messages of the largest single-block size, and `n` is the
+//! number of bytes hashed. The chains hash a 32-byte digest `--chain-length` times, and `n` is the
+//! number of hashes.
+
+use stwo_examples::{
+    airs::{keccak, keccak::Sha3Air, sha256, sha256::Sha256Air},
+    bench::{measure, BenchMetrics},
+    cli::flag_arg,
+};
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
+
+fn renamed(metrics: BenchMetrics, benchmark: &str) -> BenchMetrics {
+    BenchMetrics {
+        benchmark: benchmark.to_string(),
+        ..metrics
+    }
+}
+
+fn main() {
+    let n_messages = flag_arg("messages", 4);
+    let chain_length = flag_arg("chain-length", 16);
+    let config = PcsConfig::default();
+
+    let sha256 = Sha256Air {
+        messages: sha256::sample_messages(n_messages),
+        chain_length: 1,
+    };
+    let sha256_chain = Sha256Air {
+        messages: vec![vec![0; 32]],
+        chain_length,
+    };
+    let sha3 = Sha3Air {
+        messages: keccak::sample_messages(n_messages),
+        chain_length: 1,
+    };
+    let sha3_chain = Sha3Air {
+        messages: vec![vec![0; 32]],
+        chain_length,
+    };
+
+    let metrics = [
+        measure::<_, Blake2sMerkleChannel>(
+            &sha256,
+            n_messages * sha256::reference::MAX_SINGLE_BLOCK_LEN,
+            config,
+        )
+        .map(|metrics| renamed(metrics, "sha2")),
+        measure::<_, Blake2sMerkleChannel>(&sha256_chain, chain_length, config)
+            .map(|metrics| renamed(metrics, "sha2-chain")),
+        measure::<_, Blake2sMerkleChannel>(
+            &sha3,
+            n_messages * keccak::reference::MAX_SINGLE_BLOCK_LEN,
+            config,
+        )
+        .map(|metrics| renamed(metrics, "sha3")),
+        measure::<_, Blake2sMerkleChannel>(&sha3_chain, chain_length, config)
+            .map(|metrics| renamed(metrics, "sha3-chain")),
+    ]
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap_or_else(|err| panic!("{err}"));
+
+    println!("{}", serde_json::to_string_pretty(&metrics).unwrap());
+}
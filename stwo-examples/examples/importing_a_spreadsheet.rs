@@ -0,0 +1,31 @@
+//! Imports the table of "Writing a Spreadsheet" from CSV rather than writing it cell by cell.
+//!
+//! Usage: `cargo run --example importing_a_spreadsheet -- [<file.csv|file.tsv>]`
+
+use std::{env, path::Path};
+
+use stwo_examples::spreadsheet::{parse_spreadsheet, read_spreadsheet, ImportOptions};
+
+const TABLE: &str = "\
+col_1,col_2
+1,5
+7,11
+";
+
+fn main() {
+    let options = ImportOptions::default();
+    let trace = match env::args().nth(1) {
+        Some(path) => read_spreadsheet(Path::new(&path), options),
+        None => parse_spreadsheet(TABLE, options),
+    }
+    .unwrap_or_else(|err| panic!("{err}"));
+
+    let mut names = trace.indices.iter().collect::<Vec<_>>();
+    names.sort_by_key(|(_, &index)| index);
+    println!(
+        "imported {} rows padded to 2^{}: {:?}",
+        trace.n_rows,
+        trace.log_size,
+        names.into_iter().map(|(name, _)| name).collect::<Vec<_>>()
+    );
+}
@@ -1,230 +1,19 @@
-use num_traits::{identities::Zero, One};
-use rand::prelude::SliceRandom;
-use stwo_prover::{
-    constraint_framework::{
-        logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, EvalAtRow,
-        FrameworkComponent, FrameworkEval, Relation, RelationEntry, TraceLocationAllocator,
-        ORIGINAL_TRACE_IDX,
-    },
-    core::{
-        air::Component,
-        backend::{
-            simd::{column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend},
-            Column,
-        },
-        channel::Blake2sChannel,
-        fields::{m31::M31, qm31::SecureField},
-        pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig},
-        poly::{
-            circle::{CanonicCoset, CircleEvaluation, PolyOps},
-            BitReversedOrder,
-        },
-        prover::{prove, verify},
-        utils::bit_reverse_coset_to_circle_domain_order,
-        vcs::blake2_merkle::Blake2sMerkleChannel,
-    },
-    relation,
+use stwo_examples::{
+    airs::local_row_constraints::LocalRowConstraintsAir,
+    cli::log_size_arg,
+    pipeline::{prove_air, verify_air},
 };
-
-struct IsFirstColumn {
-    pub log_size: u32,
-}
-
-// ANCHOR: is_first_column_impl_start
-impl IsFirstColumn {
-    // ANCHOR_END: is_first_column_impl_start
-    pub fn new(log_size: u32) -> Self {
-        Self { log_size }
-    }
-
-    // ANCHOR: is_first_column
-    pub fn gen_column(&self) -> CircleEvaluation<SimdBackend, M31, BitReversedOrder> {
-        let mut col = BaseColumn::zeros(1 << self.log_size);
-        col.set(0, M31::from(1));
-
-        //////////////////////////////////////////////////////////////
-        // Convert the columns to bit-reversed circle domain order
-        bit_reverse_coset_to_circle_domain_order(col.as_mut_slice());
-        //////////////////////////////////////////////////////////////
-
-        CircleEvaluation::new(CanonicCoset::new(self.log_size).circle_domain(), col)
-    }
-    // ANCHOR_END: is_first_column
-
-    pub fn id(&self) -> PreProcessedColumnId {
-        PreProcessedColumnId {
-            id: format!("is_first_{}", self.log_size),
-        }
-    }
-    // ANCHOR: is_first_column_impl_end
-}
-// ANCHOR_END: is_first_column_impl_end
-
-struct TestEval {
-    is_first_id: PreProcessedColumnId,
-    log_size: u32,
-    lookup_elements: LookupElements,
-}
-
-impl FrameworkEval for TestEval {
-    fn log_size(&self) -> u32 {
-        self.log_size
-    }
-
-    fn max_constraint_log_degree_bound(&self) -> u32 {
-        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
-    }
-
-    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let unsorted_col = eval.next_trace_mask();
-        let [sorted_col_prev_row, sorted_col_curr_row] =
-            eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
-
-        let is_first_col = eval.get_preprocessed_column(self.is_first_id.clone());
-
-        eval.add_constraint(
-            (E::F::one() - is_first_col.clone())
-                * (E::F::one() - (sorted_col_curr_row.clone() - sorted_col_prev_row.clone())),
-        );
-
-        eval.add_to_relation(RelationEntry::new(
-            &self.lookup_elements,
-            E::EF::one(),
-            &[unsorted_col],
-        ));
-
-        eval.add_to_relation(RelationEntry::new(
-            &self.lookup_elements,
-            -E::EF::one(),
-            &[sorted_col_curr_row],
-        ));
-
-        eval.finalize_logup_in_pairs();
-
-        eval
-    }
-}
-
-const LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR: u32 = 1;
-
-relation!(LookupElements, 1);
-
-// ANCHOR: gen_trace
-fn gen_trace(log_size: u32) -> Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
-    // Create a table with random values
-    let mut rng = rand::thread_rng();
-    let sorted_values = (0..(1 << log_size)).map(|i| i).collect::<Vec<_>>();
-    let mut unsorted_values = sorted_values.clone();
-    unsorted_values.shuffle(&mut rng);
-
-    let mut unsorted_col = BaseColumn::from_iter(unsorted_values.iter().map(|v| M31::from(*v)));
-    let mut sorted_col = BaseColumn::from_iter(sorted_values.iter().map(|v| M31::from(*v)));
-
-    // Convert table to trace polynomials
-    let domain = CanonicCoset::new(log_size).circle_domain();
-
-    ////////////////////////////////////////////////////////////////////
-    // Convert the columns to bit-reversed circle domain order
-    bit_reverse_coset_to_circle_domain_order(unsorted_col.as_mut_slice());
-    bit_reverse_coset_to_circle_domain_order(sorted_col.as_mut_slice());
-    ////////////////////////////////////////////////////////////////////
-
-    vec![unsorted_col, sorted_col]
-        .into_iter()
-        .map(|col| CircleEvaluation::new(domain, col))
-        .collect()
-}
-// ANCHOR_END: gen_trace
-
-fn gen_logup_trace(
-    log_size: u32,
-    unsorted_col: &BaseColumn,
-    sorted_col: &BaseColumn,
-    lookup_elements: &LookupElements,
-) -> (
-    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
-    SecureField,
-) {
-    let mut logup_gen = LogupTraceGenerator::new(log_size);
-
-    let mut col_gen = logup_gen.new_col();
-    for row in 0..(1 << (log_size - LOG_N_LANES)) {
-        // 1 / unsorted - 1 / sorted = (sorted - unsorted) / (unsorted * sorted)
-        let unsorted_val: PackedSecureField = lookup_elements.combine(&[unsorted_col.data[row]]);
-        let sorted_val: PackedSecureField = lookup_elements.combine(&[sorted_col.data[row]]);
-        col_gen.write_frac(row, sorted_val - unsorted_val, unsorted_val * sorted_val);
-    }
-    col_gen.finalize_col();
-
-    logup_gen.finalize_last()
-}
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
 
 fn main() {
-    let log_size = LOG_N_LANES;
+    let air = LocalRowConstraintsAir {
+        log_size: log_size_arg(),
+    };
 
     // Config for FRI and PoW
     let config = PcsConfig::default();
 
-    // Precompute twiddles for evaluating and interpolating the trace
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(
-            log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR + config.fri_config.log_blowup_factor,
-        )
-        .circle_domain()
-        .half_coset,
-    );
-
-    // Create the channel and commitment scheme
-    let channel = &mut Blake2sChannel::default();
-    let mut commitment_scheme =
-        CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
-
-    // Create and commit to the preprocessed columns
-    let is_first_column = IsFirstColumn::new(log_size);
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(vec![is_first_column.gen_column()]);
-    tree_builder.commit(channel);
-
-    // Create and commit to the trace columns
-    let trace = gen_trace(log_size);
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(trace.clone());
-    tree_builder.commit(channel);
-
-    // Draw random elements to use when creating the random linear combination of lookup values in the LogUp columns
-    let lookup_elements = LookupElements::draw(channel);
-
-    // Create and commit to the LogUp columns
-    let (logup_cols, claimed_sum) =
-        gen_logup_trace(log_size, &trace[0], &trace[1], &lookup_elements);
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(logup_cols);
-    tree_builder.commit(channel);
-
-    // Create a component
-    let component = FrameworkComponent::<TestEval>::new(
-        &mut TraceLocationAllocator::default(),
-        TestEval {
-            is_first_id: is_first_column.id(),
-            log_size,
-            lookup_elements,
-        },
-        claimed_sum,
-    );
-
-    // Prove
-    let proof = prove(&[&component], channel, commitment_scheme).unwrap();
-
-    // Verify
-    assert_eq!(claimed_sum, SecureField::zero());
-
-    let channel = &mut Blake2sChannel::default();
-    let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
-    let sizes = component.trace_log_degree_bounds();
-
-    commitment_scheme.commit(proof.commitments[0], &sizes[0], channel);
-    commitment_scheme.commit(proof.commitments[1], &sizes[1], channel);
-    commitment_scheme.commit(proof.commitments[2], &sizes[2], channel);
-
-    verify(&[&component], channel, commitment_scheme, proof).unwrap();
+    let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+    verify_air::<LocalRowConstraintsAir, Blake2sMerkleChannel>(proof, config).unwrap();
+    println!("proof verified");
 }
@@ -0,0 +1,46 @@
+use stwo_examples::{
+    airs::local_row_constraints::{LocalRowConstraintsEval, LookupElements},
+    cli::log_size_arg,
+    debug::constraints::{find_constraint_failures, DebugTrace},
+    preprocessed::{IsFirst, PreprocessedColumn},
+};
+use stwo_prover::core::{
+    backend::{simd::column::BaseColumn, Column},
+    fields::m31::M31,
+    poly::circle::{CanonicCoset, CircleEvaluation},
+};
+
+// Builds the trace of `local_row_constraints_fails_2.rs`, where the columns are committed without
+// being converted to bit-reversed circle domain order, and reports the rows where it fails.
+fn main() {
+    let log_size = log_size_arg();
+    let domain = CanonicCoset::new(log_size).circle_domain();
+
+    let is_first_column = IsFirst::new(log_size);
+    let mut is_first = BaseColumn::zeros(1 << log_size);
+    is_first.set(0, M31::from(1));
+    let is_first = CircleEvaluation::new(domain, is_first);
+
+    let values = (0..(1 << log_size))
+        .map(|i| M31::from(i))
+        .collect::<Vec<_>>();
+    let trace = vec![
+        CircleEvaluation::new(domain, BaseColumn::from_iter(values.iter().rev().copied())),
+        CircleEvaluation::new(domain, BaseColumn::from_iter(values.iter().copied())),
+    ];
+
+    let eval = LocalRowConstraintsEval {
+        is_first_id: is_first_column.id(),
+        log_size,
+        lookup_elements: LookupElements::dummy(),
+    };
+    let failures = find_constraint_failures(
+        &eval,
+        &DebugTrace::new(&[(is_first_column.id(), &is_first)], &trace),
+    );
+
+    println!("{} constraint failures", failures.len());
+    for failure in failures.iter().take(3) {
+        println!("{failure}");
+    }
+}
@@ -0,0 +1,50 @@
+use stwo_examples::{
+    airs::static_lookups::{SmallerThan16Elements, StaticLookupsEval, RANGE_LOG_SIZE},
+    cli::log_size_arg,
+    debug::logup::{find_unbalanced_entries, relation_entries},
+    preprocessed::{PreprocessedColumn, RangeCheck},
+};
+use stwo_prover::core::{
+    backend::{simd::column::BaseColumn, Column},
+    fields::m31::M31,
+    poly::circle::{CanonicCoset, CircleEvaluation},
+    utils::bit_reverse_coset_to_circle_domain_order,
+};
+
+// Builds the trace of `static_lookups.rs` with a multiplicity column that is off by one for the
+// value 3, and reports the unbalanced tuples.
+fn main() {
+    let log_size = log_size_arg();
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let range_check_column = RangeCheck::repeated(RANGE_LOG_SIZE, log_size);
+
+    // Both lookup columns go through the table once per repetition, so every multiplicity should
+    // be twice the number of repetitions. The repeated rows of the table keep a multiplicity of 0.
+    let values = (0..(1 << log_size))
+        .map(|row| range_check_column.value_at_row(row))
+        .collect::<Vec<_>>();
+    let n_repetitions = 1 << (log_size - RANGE_LOG_SIZE);
+    let mut multiplicity_col = BaseColumn::zeros(1 << log_size);
+    for value in 0..(1 << RANGE_LOG_SIZE) {
+        multiplicity_col.set(value, M31::from(2 * n_repetitions));
+    }
+    multiplicity_col.set(3, M31::from(2 * n_repetitions - 1));
+    // The range-check table is in bit-reversed circle domain order
+    bit_reverse_coset_to_circle_domain_order(multiplicity_col.as_mut_slice());
+    let trace = vec![
+        CircleEvaluation::new(domain, BaseColumn::from_iter(values.iter().copied())),
+        CircleEvaluation::new(domain, BaseColumn::from_iter(values.iter().rev().copied())),
+        CircleEvaluation::new(domain, multiplicity_col),
+    ];
+
+    let eval = StaticLookupsEval {
+        range_check_id: range_check_column.id(),
+        log_size,
+        lookup_elements: SmallerThan16Elements::dummy(),
+    };
+    let entries = relation_entries(eval, &[range_check_column.gen_column()], &trace);
+
+    for imbalance in find_unbalanced_entries(&entries) {
+        println!("{imbalance}");
+    }
+}
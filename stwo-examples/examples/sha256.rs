@@ -0,0 +1,43 @@
+use stwo_examples::{
+    airs::sha256::{
+        reference::{to_hex, TEST_VECTORS},
+        Sha256Air,
+    },
+    cli::flag_arg,
+    pipeline::{prove_air, verify_air},
+};
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
+
+// Proves the test vectors as independent hashes and a chain of `--chain-length` hashes of the
+// first one, and prints the proven digests. They are checked against the reference in
+// `tests/sha256.rs`.
+fn main() {
+    let chain_length = flag_arg("chain-length", 8);
+    let messages = TEST_VECTORS
+        .iter()
+        .map(|(message, _)| message.as_bytes().to_vec())
+        .collect::<Vec<_>>();
+    for air in [
+        Sha256Air {
+            messages: messages.clone(),
+            chain_length: 1,
+        },
+        Sha256Air {
+            messages: messages[..1].to_vec(),
+            chain_length,
+        },
+    ] {
+        let config = PcsConfig::default();
+        let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+        for (message, digest) in air.messages.iter().zip(&proof.statement0.digests) {
+            println!(
+                "{} x sha256({:?}) = {}",
+                air.chain_length,
+                String::from_utf8_lossy(message),
+                to_hex(digest)
+            );
+        }
+        verify_air::<Sha256Air, Blake2sMerkleChannel>(proof, config).unwrap();
+        println!("proof verified");
+    }
+}
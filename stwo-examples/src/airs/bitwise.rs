@@ -0,0 +1,502 @@
+//! The XOR AIR from "Additional Examples", generalized to AND and OR: a words component that
+//! splits 32-bit words into limbs and looks up every limb-wise operation from a table component
+//! backed by the preprocessed truth table.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkComponent, FrameworkEval, Relation,
+        RelationEntry, PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::check_log_size;
+use crate::{
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::{registry::PreprocessedRegistry, BitwiseOp, BitwiseTable, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Combines `(a, b, a op b)`.
+relation!(BitwiseElements, 3);
+
+/// Checks that `n_bits`-bit limbs split a 32-bit word evenly and that the table of every pair of
+/// limbs has a log size the SIMD backend supports, which leaves limbs of 2, 4 or 8 bits.
+pub fn check_n_bits(n_bits: u32) -> Result<(), String> {
+    if n_bits == 0 || 32 % n_bits != 0 {
+        return Err(format!("limb size must divide 32, got {n_bits}"));
+    }
+    // The table holds every pair of limbs
+    check_log_size("table log size", 2 * n_bits)
+        .map_err(|err| format!("limb size {n_bits} is not supported: {err}"))
+}
+
+/// Number of `n_bits`-bit limbs in a 32-bit word. Panics if [`check_n_bits`] rejects `n_bits`.
+pub fn limbs_per_word(n_bits: u32) -> usize {
+    if let Err(err) = check_n_bits(n_bits) {
+        panic!("{err}");
+    }
+    (32 / n_bits) as usize
+}
+
+/// Splits `word` into `n_bits`-bit limbs, least significant first.
+pub fn decompose_u32(word: u32, n_bits: u32) -> Vec<u32> {
+    let mask = ((1u64 << n_bits) - 1) as u32;
+    (0..limbs_per_word(n_bits))
+        .map(|i| (word >> (i as u32 * n_bits)) & mask)
+        .collect()
+}
+
+pub fn preprocessed_registry(op: BitwiseOp, n_bits: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    for column in BitwiseTable::columns(op, n_bits) {
+        registry.add(column);
+    }
+    registry
+}
+
+/// Tallies how many times each row of the truth table of `op` is looked up.
+pub struct BitwiseMultiplicities {
+    pub op: BitwiseOp,
+    pub n_bits: u32,
+    /// Multiplicities in row order.
+    counts: Vec<u32>,
+}
+
+impl BitwiseMultiplicities {
+    pub fn new(op: BitwiseOp, n_bits: u32) -> Self {
+        if let Err(err) = check_n_bits(n_bits) {
+            panic!("{err}");
+        }
+        Self {
+            op,
+            n_bits,
+            counts: vec![0; 1 << (2 * n_bits)],
+        }
+    }
+
+    /// Records a lookup of `(a, b, a op b)` and returns `a op b`.
+    pub fn lookup(&mut self, a: u32, b: u32) -> u32 {
+        assert!(
+            a >> self.n_bits == 0 && b >> self.n_bits == 0,
+            "operands do not fit in {} bits",
+            self.n_bits
+        );
+        self.counts[((a << self.n_bits) | b) as usize] += 1;
+        self.op.apply(a, b)
+    }
+
+    /// Records a lookup for every limb of `a` and `b`, and returns the limbs of `a`, `b` and
+    /// `a op b`.
+    pub fn lookup_u32(&mut self, a: u32, b: u32) -> [Vec<u32>; 3] {
+        let (a_limbs, b_limbs) = (decompose_u32(a, self.n_bits), decompose_u32(b, self.n_bits));
+        let result_limbs = a_limbs
+            .iter()
+            .zip(&b_limbs)
+            .map(|(&a, &b)| self.lookup(a, b))
+            .collect();
+        [a_limbs, b_limbs, result_limbs]
+    }
+
+    /// Generates the multiplicity column of the table component, in the same bit-reversed circle
+    /// domain order as the preprocessed truth table.
+    pub fn gen_column(&self) -> BaseColumn {
+        let mut col = BaseColumn::from_iter(self.counts.iter().map(|&count| M31::from(count)));
+        bit_reverse_coset_to_circle_domain_order(col.as_mut_slice());
+        col
+    }
+}
+
+/// Looks up `(a_i, b_i, a_i op b_i)` for every limb of a pair of 32-bit words.
+pub struct WordsEval {
+    pub log_size: u32,
+    pub n_bits: u32,
+    pub lookup_elements: BitwiseElements,
+}
+
+impl FrameworkEval for WordsEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let n_limbs = limbs_per_word(self.n_bits);
+        let a_limbs = (0..n_limbs).map(|_| eval.next_trace_mask()).collect_vec();
+        let b_limbs = (0..n_limbs).map(|_| eval.next_trace_mask()).collect_vec();
+        let result_limbs = (0..n_limbs).map(|_| eval.next_trace_mask()).collect_vec();
+
+        for ((a, b), result) in a_limbs.into_iter().zip(b_limbs).zip(result_limbs) {
+            eval.add_to_relation(RelationEntry::new(
+                &self.lookup_elements,
+                E::EF::one(),
+                &[a, b, result],
+            ));
+        }
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Yields every row of the truth table with its multiplicity.
+pub struct BitwiseTableEval {
+    pub op: BitwiseOp,
+    pub n_bits: u32,
+    pub lookup_elements: BitwiseElements,
+}
+
+impl FrameworkEval for BitwiseTableEval {
+    fn log_size(&self) -> u32 {
+        2 * self.n_bits
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size() + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let [a, b, result] = BitwiseTable::columns(self.op, self.n_bits)
+            .map(|column| eval.get_preprocessed_column(column.id()));
+        let multiplicity = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::from(multiplicity),
+            &[a, b, result],
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitwiseStatement0 {
+    pub log_size: u32,
+    pub op: BitwiseOp,
+    pub n_bits: u32,
+}
+
+impl Statement for BitwiseStatement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_size as u64);
+        channel.mix_u64(self.op as u64);
+        channel.mix_u64(self.n_bits as u64);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitwiseStatement1 {
+    pub words_claimed_sum: SecureField,
+    pub table_claimed_sum: SecureField,
+}
+
+impl Statement for BitwiseStatement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[self.words_claimed_sum, self.table_claimed_sum]);
+    }
+}
+
+pub type WordsComponent = FrameworkComponent<WordsEval>;
+pub type BitwiseTableComponent = FrameworkComponent<BitwiseTableEval>;
+
+pub struct BitwiseComponents {
+    pub words_component: WordsComponent,
+    pub table_component: BitwiseTableComponent,
+}
+
+impl BitwiseComponents {
+    pub fn new(
+        statement0: &BitwiseStatement0,
+        lookup_elements: &BitwiseElements,
+        statement1: &BitwiseStatement1,
+    ) -> Self {
+        let builder =
+            &mut preprocessed_registry(statement0.op, statement0.n_bits).component_builder();
+
+        let words_component = builder.build(
+            WordsEval {
+                log_size: statement0.log_size,
+                n_bits: statement0.n_bits,
+                lookup_elements: lookup_elements.clone(),
+            },
+            statement1.words_claimed_sum,
+        );
+
+        let table_component = builder.build(
+            BitwiseTableEval {
+                op: statement0.op,
+                n_bits: statement0.n_bits,
+                lookup_elements: lookup_elements.clone(),
+            },
+            statement1.table_claimed_sum,
+        );
+
+        Self {
+            words_component,
+            table_component,
+        }
+    }
+}
+
+impl AirComponents for BitwiseComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        chain![[
+            &self.words_component as &dyn Component,
+            &self.table_component as &dyn Component
+        ]]
+        .collect()
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        chain![[
+            &self.words_component as &dyn ComponentProver<SimdBackend>,
+            &self.table_component as &dyn ComponentProver<SimdBackend>
+        ]]
+        .collect()
+    }
+}
+
+/// Applies `op` to `2^log_size` pairs of random 32-bit words, using `n_bits`-bit limbs, where
+/// `n_bits` is one of the sizes accepted by [`check_n_bits`].
+pub struct BitwiseAir {
+    pub op: BitwiseOp,
+    pub n_bits: u32,
+    pub log_size: u32,
+}
+
+impl Air for BitwiseAir {
+    const NAME: &'static str = "bitwise";
+    const COMPONENT_NAMES: &'static [&'static str] = &["words", "table"];
+
+    type Statement0 = BitwiseStatement0;
+    type Statement1 = BitwiseStatement1;
+    type LookupElements = BitwiseElements;
+    type Components = BitwiseComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        check_log_size("log size", statement0.log_size)?;
+        check_n_bits(statement0.n_bits)
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        BitwiseElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.op, statement0.n_bits)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let components = BitwiseComponents::new(
+            statement0,
+            &BitwiseElements::dummy(),
+            &BitwiseStatement1 {
+                words_claimed_sum: SecureField::one(),
+                table_claimed_sum: SecureField::one(),
+            },
+        );
+        let mut log_sizes = TreeVec::concat_cols(
+            [
+                components.words_component.trace_log_degree_bounds(),
+                components.table_component.trace_log_degree_bounds(),
+            ]
+            .into_iter(),
+        );
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] =
+            preprocessed_registry(statement0.op, statement0.n_bits).log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.words_claimed_sum + statement1.table_claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        BitwiseComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for BitwiseAir {
+    /// The columns of the words component followed by the multiplicity column of the table.
+    type Witness = Vec<BaseColumn>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let (words_columns, multiplicities) = gen_words_trace(self.log_size, self.n_bits, self.op);
+        let multiplicity_col = multiplicities.gen_column();
+
+        let words_domain = CanonicCoset::new(self.log_size).circle_domain();
+        let table_domain = CanonicCoset::new(2 * self.n_bits).circle_domain();
+        let trace = chain!(
+            words_columns
+                .iter()
+                .map(|col| CircleEvaluation::new(words_domain, col.clone())),
+            [CircleEvaluation::new(
+                table_domain,
+                multiplicity_col.clone()
+            )]
+        )
+        .collect();
+
+        (
+            BitwiseStatement0 {
+                log_size: self.log_size,
+                op: self.op,
+                n_bits: self.n_bits,
+            },
+            trace,
+            chain!(words_columns, [multiplicity_col]).collect(),
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let Some((multiplicity_col, words_columns)) = witness.split_last() else {
+            panic!("unexpected witness layout");
+        };
+        let (words_logup_cols, words_claimed_sum) =
+            gen_words_logup_trace(self.log_size, words_columns, lookup_elements);
+        let (table_logup_cols, table_claimed_sum) =
+            gen_table_logup_trace(self.op, self.n_bits, multiplicity_col, lookup_elements);
+
+        (
+            BitwiseStatement1 {
+                words_claimed_sum,
+                table_claimed_sum,
+            },
+            [words_logup_cols, table_logup_cols].concat(),
+        )
+    }
+}
+
+/// Generates the limbs of `a`, `b` and `a op b` for `2^log_size` pairs of random words, as the
+/// columns `a_0, .., a_n, b_0, .., b_n, r_0, .., r_n`, along with the multiplicities of the
+/// lookups.
+pub fn gen_words_trace(
+    log_size: u32,
+    n_bits: u32,
+    op: BitwiseOp,
+) -> (Vec<BaseColumn>, BitwiseMultiplicities) {
+    let n_limbs = limbs_per_word(n_bits);
+    let mut rng = rand::thread_rng();
+    let mut columns = vec![Vec::with_capacity(1 << log_size); 3 * n_limbs];
+    let mut multiplicities = BitwiseMultiplicities::new(op, n_bits);
+    for _ in 0..(1 << log_size) {
+        let limbs = multiplicities.lookup_u32(rng.gen(), rng.gen());
+        for (column, limb) in columns.iter_mut().zip(limbs.iter().flatten()) {
+            column.push(M31::from(*limb));
+        }
+    }
+
+    (
+        columns.into_iter().map(BaseColumn::from_iter).collect(),
+        multiplicities,
+    )
+}
+
+pub fn gen_words_logup_trace(
+    log_size: u32,
+    words_columns: &[BaseColumn],
+    lookup_elements: &BitwiseElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let n_limbs = words_columns.len() / 3;
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    // The lookups are batched in pairs, as in `finalize_logup_in_pairs`
+    for limbs in (0..n_limbs).collect_vec().chunks(2) {
+        let mut col_gen = logup_gen.new_col();
+        for row in 0..(1 << (log_size - LOG_N_LANES)) {
+            let denoms = limbs
+                .iter()
+                .map(|&limb| -> PackedSecureField {
+                    lookup_elements.combine(&[
+                        words_columns[limb].data[row],
+                        words_columns[n_limbs + limb].data[row],
+                        words_columns[2 * n_limbs + limb].data[row],
+                    ])
+                })
+                .collect_vec();
+            match denoms[..] {
+                // 1 / denom1 + 1 / denom2 = (denom1 + denom2) / (denom1 * denom2)
+                [denom1, denom2] => col_gen.write_frac(row, denom1 + denom2, denom1 * denom2),
+                [denom] => col_gen.write_frac(row, PackedSecureField::one(), denom),
+                _ => unreachable!(),
+            }
+        }
+        col_gen.finalize_col();
+    }
+
+    logup_gen.finalize_last()
+}
+
+pub fn gen_table_logup_trace(
+    op: BitwiseOp,
+    n_bits: u32,
+    multiplicity_col: &BaseColumn,
+    lookup_elements: &BitwiseElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let log_size = 2 * n_bits;
+    let [a_col, b_col, result_col] =
+        BitwiseTable::columns(op, n_bits).map(|column| column.gen_column().values);
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let numerator = PackedSecureField::from(multiplicity_col.data[row]);
+        let denom: PackedSecureField =
+            lookup_elements.combine(&[a_col.data[row], b_col.data[row], result_col.data[row]]);
+        col_gen.write_frac(row, -numerator, denom);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
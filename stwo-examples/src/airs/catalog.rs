@@ -0,0 +1,125 @@
+//! The example AIRs by name, each sized from a single log size, for the tools that pick the AIR
+//! at runtime.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{
+    bitwise::BitwiseAir,
+    components::ComponentsAir,
+    dynamic_lookups::DynamicLookupsAir,
+    fibonacci::FibonacciAir,
+    is_zero::IsZeroAir,
+    keccak::{self, reference::N_ROUNDS, Sha3Air},
+    local_row_constraints::LocalRowConstraintsAir,
+    memory::MemoryAir,
+    mini_cairo::{fibonacci_program, MiniCairoAir, MAX_FIBONACCI_STEPS},
+    preprocessed_trace::PreprocessedTraceAir,
+    public_permutation::PublicPermutationAir,
+    range_check::{RangeCheckAir, RangeCheckWidths},
+    sha256::{self, Sha256Air},
+    simple::SimpleAir,
+    static_lookups::StaticLookupsAir,
+};
+use crate::{
+    pipeline::{Air, AirProver},
+    preprocessed::BitwiseOp,
+};
+
+/// The names of the AIRs accepted by [`visit_air`].
+pub const AIR_NAMES: [&str; 15] = [
+    SimpleAir::NAME,
+    PreprocessedTraceAir::NAME,
+    StaticLookupsAir::NAME,
+    DynamicLookupsAir::NAME,
+    ComponentsAir::NAME,
+    LocalRowConstraintsAir::NAME,
+    PublicPermutationAir::NAME,
+    IsZeroAir::NAME,
+    BitwiseAir::NAME,
+    RangeCheckAir::NAME,
+    MemoryAir::NAME,
+    FibonacciAir::NAME,
+    MiniCairoAir::NAME,
+    Sha256Air::NAME,
+    Sha3Air::NAME,
+];
+
+/// An action on an AIR whose type is only known at runtime.
+pub trait AirVisitor {
+    type Output;
+
+    /// Runs the action on `air`, whose workload has size `n`, e.g. the number of Fibonacci steps
+    /// or of bytes hashed.
+    fn visit<A: AirProver>(self, air: &A, n: usize) -> Self::Output
+    where
+        A::Statement0: Serialize + DeserializeOwned,
+        A::Statement1: Serialize + DeserializeOwned;
+}
+
+/// Builds the AIR named `name` at about `2^log_size` rows and runs `visitor` on it.
+pub fn visit_air<V: AirVisitor>(
+    name: &str,
+    log_size: u32,
+    visitor: V,
+) -> Result<V::Output, String> {
+    let n_rows = 1 << log_size;
+    Ok(match name {
+        SimpleAir::NAME => visitor.visit(&SimpleAir { log_size }, n_rows),
+        PreprocessedTraceAir::NAME => visitor.visit(&PreprocessedTraceAir { log_size }, n_rows),
+        StaticLookupsAir::NAME => visitor.visit(&StaticLookupsAir { log_size }, n_rows),
+        DynamicLookupsAir::NAME => visitor.visit(&DynamicLookupsAir { log_size }, n_rows),
+        ComponentsAir::NAME => visitor.visit(&ComponentsAir { log_size }, n_rows),
+        LocalRowConstraintsAir::NAME => visitor.visit(&LocalRowConstraintsAir { log_size }, n_rows),
+        PublicPermutationAir::NAME => visitor.visit(&PublicPermutationAir { log_size }, n_rows),
+        IsZeroAir::NAME => visitor.visit(&IsZeroAir { log_size }, n_rows),
+        BitwiseAir::NAME => visitor.visit(
+            &BitwiseAir {
+                op: BitwiseOp::Xor,
+                n_bits: 8,
+                log_size,
+            },
+            n_rows,
+        ),
+        RangeCheckAir::NAME => visitor.visit(
+            &RangeCheckAir {
+                widths: RangeCheckWidths::new(&[4, 4]),
+                log_size,
+            },
+            n_rows,
+        ),
+        MemoryAir::NAME => visitor.visit(&MemoryAir { log_size }, n_rows),
+        FibonacciAir::NAME => {
+            let air = FibonacciAir {
+                log_n_steps: log_size,
+                n_instances: 1,
+            };
+            visitor.visit(&air, air.n_steps())
+        }
+        MiniCairoAir::NAME => {
+            // Runs `2^log_size` Fibonacci steps, as many as the offsets allow
+            let n_steps = n_rows.min(MAX_FIBONACCI_STEPS);
+            let air = MiniCairoAir::new(&fibonacci_program(n_steps))
+                .map_err(|err| format!("execution failed: {err}"))?;
+            visitor.visit(&air, n_steps)
+        }
+        Sha256Air::NAME => {
+            // Hashes as many messages as fill `2^log_size` round rows
+            let n_messages = 1 << log_size.saturating_sub(6);
+            let air = Sha256Air {
+                messages: sha256::sample_messages(n_messages),
+                chain_length: 1,
+            };
+            visitor.visit(&air, n_messages * sha256::reference::MAX_SINGLE_BLOCK_LEN)
+        }
+        Sha3Air::NAME => {
+            // Hashes as many messages as fill `2^log_size` round rows
+            let n_messages = (n_rows / N_ROUNDS).max(1);
+            let air = Sha3Air {
+                messages: keccak::sample_messages(n_messages),
+                chain_length: 1,
+            };
+            visitor.visit(&air, n_messages * keccak::reference::MAX_SINGLE_BLOCK_LEN)
+        }
+        _ => return Err(format!("unknown AIR `{name}`")),
+    })
+}
@@ -0,0 +1,378 @@
+//! The AIR from "Components": a scheduling component that looks up `x^5 + 1` from a computing
+//! component.
+
+use itertools::chain;
+use num_traits::One;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkComponent, FrameworkEval, InfoEvaluator,
+        Relation, RelationEntry,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend},
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField, FieldExpOps},
+        pcs::TreeVec,
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::LogSizeStatement;
+use crate::{
+    debug::to_row_order,
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::registry::PreprocessedRegistry,
+    witness::gen_parallel_trace,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+relation!(ComputationLookupElements, 2);
+
+pub type ComponentsStatement0 = LogSizeStatement;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentsStatement1 {
+    pub scheduling_claimed_sum: SecureField,
+    pub computing_claimed_sum: SecureField,
+}
+
+impl Statement for ComponentsStatement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[self.scheduling_claimed_sum, self.computing_claimed_sum]);
+    }
+}
+
+pub struct SchedulingEval {
+    pub log_size: u32,
+    pub lookup_elements: ComputationLookupElements,
+}
+
+// ANCHOR: scheduling_eval
+impl FrameworkEval for SchedulingEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let input_col = eval.next_trace_mask();
+        let output_col = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            E::EF::one(),
+            &[input_col, output_col],
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+// ANCHOR_END: scheduling_eval
+
+pub struct ComputingEval {
+    pub log_size: u32,
+    pub lookup_elements: ComputationLookupElements,
+}
+
+// ANCHOR: computing_eval
+impl FrameworkEval for ComputingEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let input_col = eval.next_trace_mask();
+        let intermediate_col = eval.next_trace_mask();
+        let output_col = eval.next_trace_mask();
+
+        eval.add_constraint(
+            intermediate_col.clone() - input_col.clone() * input_col.clone() * input_col.clone(),
+        );
+        eval.add_constraint(
+            output_col.clone()
+                - intermediate_col.clone() * input_col.clone() * input_col.clone()
+                - E::F::one(),
+        );
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::one(),
+            &[input_col, output_col],
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+// ANCHOR_END: computing_eval
+
+pub type SchedulingComponent = FrameworkComponent<SchedulingEval>;
+pub type ComputingComponent = FrameworkComponent<ComputingEval>;
+
+pub struct Components {
+    pub scheduling_component: SchedulingComponent,
+    pub computing_component: ComputingComponent,
+}
+
+impl Components {
+    pub fn new(
+        statement0: &ComponentsStatement0,
+        lookup_elements: &ComputationLookupElements,
+        statement1: &ComponentsStatement1,
+    ) -> Self {
+        let builder = &mut PreprocessedRegistry::new().component_builder();
+
+        let scheduling_component = builder.build(
+            SchedulingEval {
+                log_size: statement0.log_size,
+                lookup_elements: lookup_elements.clone(),
+            },
+            statement1.scheduling_claimed_sum,
+        );
+
+        let computing_component = builder.build(
+            ComputingEval {
+                log_size: statement0.log_size,
+                lookup_elements: lookup_elements.clone(),
+            },
+            statement1.computing_claimed_sum,
+        );
+
+        Self {
+            scheduling_component,
+            computing_component,
+        }
+    }
+}
+
+impl AirComponents for Components {
+    fn components(&self) -> Vec<&dyn Component> {
+        chain![[
+            &self.scheduling_component as &dyn Component,
+            &self.computing_component as &dyn Component
+        ]]
+        .collect()
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        chain![[
+            &self.scheduling_component as &dyn ComponentProver<SimdBackend>,
+            &self.computing_component as &dyn ComponentProver<SimdBackend>
+        ]]
+        .collect()
+    }
+}
+
+fn scheduling_info() -> InfoEvaluator {
+    let component = SchedulingEval {
+        log_size: 1,
+        lookup_elements: ComputationLookupElements::dummy(),
+    };
+
+    component.evaluate(InfoEvaluator::empty())
+}
+
+fn computing_info() -> InfoEvaluator {
+    let component = ComputingEval {
+        log_size: 1,
+        lookup_elements: ComputationLookupElements::dummy(),
+    };
+
+    component.evaluate(InfoEvaluator::empty())
+}
+
+pub struct ComponentsAir {
+    pub log_size: u32,
+}
+
+// ANCHOR: components_air
+impl Air for ComponentsAir {
+    const NAME: &'static str = "components";
+    const COMPONENT_NAMES: &'static [&'static str] = &["scheduling", "computing"];
+
+    type Statement0 = ComponentsStatement0;
+    type Statement1 = ComponentsStatement1;
+    type LookupElements = ComputationLookupElements;
+    type Components = Components;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        ComputationLookupElements::draw(channel)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let log_sizes = [scheduling_info(), computing_info()].map(|info| {
+            info.mask_offsets
+                .as_cols_ref()
+                .map_cols(|_| statement0.log_size)
+        });
+
+        TreeVec::concat_cols(log_sizes.into_iter())
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.scheduling_claimed_sum + statement1.computing_claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        Components::new(statement0, lookup_elements, statement1)
+    }
+}
+// ANCHOR_END: components_air
+
+// ANCHOR: components_air_prover
+impl AirProver for ComponentsAir {
+    /// The scheduling trace followed by the computing trace.
+    type Witness = Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let scheduling_trace = gen_scheduling_trace(self.log_size, &mut rand::thread_rng());
+        let computing_trace =
+            gen_computing_trace(self.log_size, &scheduling_trace[0], &scheduling_trace[1]);
+        let trace = [scheduling_trace, computing_trace].concat();
+
+        (
+            LogSizeStatement {
+                log_size: self.log_size,
+            },
+            trace.clone(),
+            trace,
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let (scheduling_trace, computing_trace) = witness.split_at(2);
+        let (scheduling_logup_cols, scheduling_claimed_sum) = gen_scheduling_logup_trace(
+            self.log_size,
+            &scheduling_trace[0],
+            &scheduling_trace[1],
+            lookup_elements,
+        );
+        let (computing_logup_cols, computing_claimed_sum) = gen_computing_logup_trace(
+            self.log_size,
+            &computing_trace[0],
+            &computing_trace[2],
+            lookup_elements,
+        );
+
+        (
+            ComponentsStatement1 {
+                scheduling_claimed_sum,
+                computing_claimed_sum,
+            },
+            [scheduling_logup_cols, computing_logup_cols].concat(),
+        )
+    }
+}
+// ANCHOR_END: components_air_prover
+
+pub fn gen_scheduling_trace(
+    log_size: u32,
+    rng: &mut impl Rng,
+) -> Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+    // Create a table with random values
+    let inputs = (0..1 << log_size)
+        .map(|_| M31::from(rng.gen_range(0..16)))
+        .collect::<Vec<_>>();
+
+    gen_parallel_trace(log_size, |row| {
+        let x = inputs[row];
+        [x, x.pow(5) + M31::from(1)]
+    })
+}
+
+pub fn gen_computing_trace(
+    log_size: u32,
+    scheduling_col_1: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+    scheduling_col_2: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+) -> Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+    let [inputs, outputs] = [scheduling_col_1, scheduling_col_2].map(to_row_order);
+
+    gen_parallel_trace(log_size, |row| {
+        let x = inputs[row];
+        [x, x.pow(3), outputs[row]]
+    })
+}
+
+// ANCHOR: gen_scheduling_logup_trace
+pub fn gen_scheduling_logup_trace(
+    log_size: u32,
+    scheduling_col_1: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+    scheduling_col_2: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+    lookup_elements: &ComputationLookupElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let scheduling_input_output: PackedSecureField =
+            lookup_elements.combine(&[scheduling_col_1.data[row], scheduling_col_2.data[row]]);
+        col_gen.write_frac(row, PackedSecureField::one(), scheduling_input_output);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+// ANCHOR_END: gen_scheduling_logup_trace
+
+// ANCHOR: gen_computing_logup_trace
+pub fn gen_computing_logup_trace(
+    log_size: u32,
+    computing_col_1: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+    computing_col_3: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+    lookup_elements: &ComputationLookupElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let computing_input_output: PackedSecureField =
+            lookup_elements.combine(&[computing_col_1.data[row], computing_col_3.data[row]]);
+        col_gen.write_frac(row, -PackedSecureField::one(), computing_input_output);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+// ANCHOR_END: gen_computing_logup_trace
@@ -0,0 +1,221 @@
+//! The AIR from "Dynamic Lookups": a permutation check between two columns.
+
+use num_traits::One;
+use rand::prelude::SliceRandom;
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkComponent, FrameworkEval, Relation,
+        RelationEntry,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::{ClaimedSumStatement, LogSizeStatement};
+use crate::{
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::registry::PreprocessedRegistry,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+relation!(LookupElements, 1);
+
+// ANCHOR: dynamic_lookups_eval
+pub struct DynamicLookupsEval {
+    pub log_size: u32,
+    pub lookup_elements: LookupElements,
+}
+
+impl FrameworkEval for DynamicLookupsEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let random_col = eval.next_trace_mask();
+        let ordered_col = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            E::EF::one(),
+            &[random_col],
+        ));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::one(),
+            &[ordered_col],
+        ));
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+// ANCHOR_END: dynamic_lookups_eval
+
+pub type DynamicLookupsComponent = FrameworkComponent<DynamicLookupsEval>;
+
+pub struct DynamicLookupsComponents {
+    pub component: DynamicLookupsComponent,
+}
+
+impl DynamicLookupsComponents {
+    pub fn new(
+        statement0: &LogSizeStatement,
+        lookup_elements: &LookupElements,
+        statement1: &ClaimedSumStatement,
+    ) -> Self {
+        Self {
+            component: PreprocessedRegistry::new().component_builder().build(
+                DynamicLookupsEval {
+                    log_size: statement0.log_size,
+                    lookup_elements: lookup_elements.clone(),
+                },
+                statement1.claimed_sum,
+            ),
+        }
+    }
+}
+
+impl AirComponents for DynamicLookupsComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct DynamicLookupsAir {
+    pub log_size: u32,
+}
+
+impl Air for DynamicLookupsAir {
+    const NAME: &'static str = "dynamic_lookups";
+    const COMPONENT_NAMES: &'static [&'static str] = &["dynamic_lookups"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = LookupElements;
+    type Components = DynamicLookupsComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        LookupElements::draw(channel)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        DynamicLookupsComponents::new(
+            statement0,
+            &LookupElements::dummy(),
+            &ClaimedSumStatement {
+                claimed_sum: SecureField::one(),
+            },
+        )
+        .component
+        .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        DynamicLookupsComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+// ANCHOR: dynamic_lookups_air_prover
+impl AirProver for DynamicLookupsAir {
+    type Witness = Vec<BaseColumn>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let mut rng = rand::thread_rng();
+        let values = (0..(1 << self.log_size)).collect::<Vec<_>>();
+
+        // Create a random permutation of the values
+        let mut random_values = values.clone();
+        random_values.shuffle(&mut rng);
+        let random_col_1 = BaseColumn::from_iter(random_values.iter().map(|v| M31::from(*v)));
+
+        // Create another random permutation of the values
+        let mut random_values = random_values.clone();
+        random_values.shuffle(&mut rng);
+        let random_col_2 = BaseColumn::from_iter(random_values.iter().map(|v| M31::from(*v)));
+
+        // Convert table to trace polynomials
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        let columns = vec![random_col_1, random_col_2];
+        let trace = columns
+            .iter()
+            .map(|col| CircleEvaluation::new(domain, col.clone()))
+            .collect();
+
+        (
+            LogSizeStatement {
+                log_size: self.log_size,
+            },
+            trace,
+            columns,
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let [random_col_1, random_col_2] = &witness[..] else {
+            panic!("unexpected witness layout");
+        };
+        let mut logup_gen = LogupTraceGenerator::new(self.log_size);
+
+        let mut col_gen = logup_gen.new_col();
+        for row in 0..(1 << (self.log_size - LOG_N_LANES)) {
+            // 1 / random - 1 / ordered = (ordered - random) / (random * ordered)
+            let random_val: PackedSecureField = lookup_elements.combine(&[random_col_1.data[row]]);
+            let ordered_val: PackedSecureField = lookup_elements.combine(&[random_col_2.data[row]]);
+            col_gen.write_frac(row, ordered_val - random_val, random_val * ordered_val);
+        }
+        col_gen.finalize_col();
+
+        let (logup_cols, claimed_sum) = logup_gen.finalize_last();
+        (ClaimedSumStatement { claimed_sum }, logup_cols)
+    }
+}
+// ANCHOR_END: dynamic_lookups_air_prover
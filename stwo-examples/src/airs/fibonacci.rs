@@ -0,0 +1,257 @@
+//! The Fibonacci workload of the Benchmarks Report: `n_instances` independent Fibonacci sequences
+//! of `2^log_n_steps` steps, proven side by side in one component.
+//!
+//! Each instance has two columns `a, b`, with `(a, b) = (F(i), F(i + 1))` on row `i`. The first
+//! row is selected by `IsFirst` and fixed to `(1, 1)`, every other row is computed from the
+//! previous one through `[-1, 0]` masks, and the last row, selected by `IsLast`, holds the public
+//! result.
+
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        EvalAtRow, FrameworkComponent, FrameworkEval, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::SimdBackend,
+        channel::Channel,
+        fields::{
+            m31::{M31, P},
+            qm31::SecureField,
+        },
+        pcs::TreeVec,
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        ColumnVec,
+    },
+};
+
+use super::check_log_size;
+use crate::{
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::{registry::PreprocessedRegistry, IsFirst, IsLast, PreprocessedColumn},
+    witness::gen_sequential_trace,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// `F(n)` modulo `P`, with `F(0) = F(1) = 1`.
+pub fn fibonacci(n: usize) -> M31 {
+    let (mut a, mut b) = (M31::one(), M31::one());
+    for _ in 0..n {
+        (a, b) = (b, a + b);
+    }
+    a
+}
+
+pub fn preprocessed_registry(log_n_steps: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(log_n_steps));
+    registry.add(IsLast::new(log_n_steps));
+    registry
+}
+
+pub struct FibonacciEval {
+    pub log_n_steps: u32,
+    pub n_instances: usize,
+    /// `F(2^log_n_steps)`, the value of `b` on the last row.
+    pub result: M31,
+}
+
+impl FrameworkEval for FibonacciEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_steps
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_n_steps + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let is_first = eval.get_preprocessed_column(IsFirst::new(self.log_n_steps).id());
+        let is_last = eval.get_preprocessed_column(IsLast::new(self.log_n_steps).id());
+        let not_first = E::F::one() - is_first.clone();
+
+        for _ in 0..self.n_instances {
+            let [a_prev_row, a] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+            let [b_prev_row, b] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+
+            // The sequence starts from (1, 1)
+            eval.add_constraint(is_first.clone() * (a.clone() - E::F::one()));
+            eval.add_constraint(is_first.clone() * (b.clone() - E::F::one()));
+            // (a, b) = (b_prev, a_prev + b_prev)
+            eval.add_constraint(not_first.clone() * (a - b_prev_row.clone()));
+            eval.add_constraint(not_first.clone() * (b.clone() - (a_prev_row + b_prev_row)));
+            // The last row holds the result
+            eval.add_constraint(is_last.clone() * (b - E::F::from(self.result)));
+        }
+
+        eval
+    }
+}
+
+/// Generates the columns `a, b` of every instance.
+pub fn gen_fibonacci_trace(
+    log_n_steps: u32,
+    n_instances: usize,
+) -> ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+    let instance = gen_sequential_trace(log_n_steps, |row, &[a, b]| match row {
+        0 => [M31::one(), M31::one()],
+        _ => [b, a + b],
+    });
+    (0..n_instances)
+        .flat_map(|_| instance.iter().cloned())
+        .collect()
+}
+
+/// The largest number of instances accepted in a statement, two columns each.
+pub const MAX_INSTANCES: u32 = 1 << 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FibonacciStatement0 {
+    pub log_n_steps: u32,
+    pub n_instances: u32,
+    /// `F(2^log_n_steps)` modulo `P`.
+    pub result: u32,
+}
+
+impl Statement for FibonacciStatement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_n_steps as u64);
+        channel.mix_u64(self.n_instances as u64);
+        channel.mix_u64(self.result as u64);
+    }
+}
+
+pub type FibonacciComponent = FrameworkComponent<FibonacciEval>;
+
+pub struct FibonacciComponents {
+    pub component: FibonacciComponent,
+}
+
+impl FibonacciComponents {
+    pub fn new(statement0: &FibonacciStatement0) -> Self {
+        Self {
+            component: preprocessed_registry(statement0.log_n_steps)
+                .component_builder()
+                .build(
+                    FibonacciEval {
+                        log_n_steps: statement0.log_n_steps,
+                        n_instances: statement0.n_instances as usize,
+                        result: M31::from(statement0.result),
+                    },
+                    SecureField::zero(),
+                ),
+        }
+    }
+}
+
+impl AirComponents for FibonacciComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+/// Proves `n_instances` Fibonacci sequences of `2^log_n_steps` steps.
+pub struct FibonacciAir {
+    pub log_n_steps: u32,
+    pub n_instances: usize,
+}
+
+impl FibonacciAir {
+    /// The number of Fibonacci steps proven, `n` in the Benchmarks Report.
+    pub fn n_steps(&self) -> usize {
+        self.n_instances << self.log_n_steps
+    }
+}
+
+impl Air for FibonacciAir {
+    const NAME: &'static str = "fibonacci";
+    const COMPONENT_NAMES: &'static [&'static str] = &["fibonacci"];
+
+    type Statement0 = FibonacciStatement0;
+    type Statement1 = ();
+    type LookupElements = ();
+    type Components = FibonacciComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        check_log_size("log number of steps", statement0.log_n_steps)?;
+        if !(1..=MAX_INSTANCES).contains(&statement0.n_instances) {
+            return Err(format!(
+                "the number of instances must be between 1 and {MAX_INSTANCES}, got {}",
+                statement0.n_instances
+            ));
+        }
+        if statement0.result >= P {
+            return Err(format!("result {} is not reduced", statement0.result));
+        }
+        Ok(())
+    }
+
+    fn draw_lookup_elements(_channel: &mut impl Channel) -> Self::LookupElements {}
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let mut log_sizes = FibonacciComponents::new(statement0)
+            .component
+            .trace_log_degree_bounds();
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] =
+            preprocessed_registry(statement0.log_n_steps).log_sizes();
+        log_sizes
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.log_n_steps)
+    }
+
+    fn claimed_sum(_statement1: &Self::Statement1) -> SecureField {
+        SecureField::zero()
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        _lookup_elements: &Self::LookupElements,
+        _statement1: &Self::Statement1,
+    ) -> Self::Components {
+        FibonacciComponents::new(statement0)
+    }
+}
+
+impl AirProver for FibonacciAir {
+    type Witness = ();
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let trace = gen_fibonacci_trace(self.log_n_steps, self.n_instances);
+
+        (
+            FibonacciStatement0 {
+                log_n_steps: self.log_n_steps,
+                n_instances: self.n_instances as u32,
+                result: fibonacci(1 << self.log_n_steps).0,
+            },
+            trace,
+            (),
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        _witness: &Self::Witness,
+        _lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        ((), vec![])
+    }
+}
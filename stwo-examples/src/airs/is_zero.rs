@@ -0,0 +1,179 @@
+//! The IsZero example from "Additional Examples": a column of values, some of them zero, and a
+//! flag column constrained through the [`is_zero`] gadget to be 1 exactly where the value is zero.
+
+use num_traits::Zero;
+use rand::Rng;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval},
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{column::BaseColumn, SimdBackend},
+        channel::Channel,
+        fields::{
+            m31::{M31, P},
+            qm31::SecureField,
+        },
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+};
+
+use super::LogSizeStatement;
+use crate::{
+    gadgets::is_zero::{gen_inverse_column, is_zero},
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::registry::PreprocessedRegistry,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Constrains the columns `value, inv, flag` so that `flag` is 1 if `value` is zero and 0
+/// otherwise.
+pub struct IsZeroEval {
+    pub log_size: u32,
+}
+
+impl FrameworkEval for IsZeroEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let value = eval.next_trace_mask();
+        let value_is_zero = is_zero(&mut eval, value);
+        let flag = eval.next_trace_mask();
+        eval.add_constraint(flag - value_is_zero);
+        eval
+    }
+}
+
+/// Generates the columns `value, inv, flag` of [`IsZeroEval`] from `values`.
+pub fn gen_is_zero_trace(values: &[M31]) -> Vec<BaseColumn> {
+    let value = BaseColumn::from_iter(values.iter().copied());
+    let inv = gen_inverse_column(&value);
+    let flag = BaseColumn::from_iter(values.iter().map(|value| M31::from(value.is_zero() as u32)));
+    vec![value, inv, flag]
+}
+
+pub type IsZeroComponent = FrameworkComponent<IsZeroEval>;
+
+pub struct IsZeroComponents {
+    pub component: IsZeroComponent,
+}
+
+impl IsZeroComponents {
+    pub fn new(statement0: &LogSizeStatement) -> Self {
+        Self {
+            component: PreprocessedRegistry::new().component_builder().build(
+                IsZeroEval {
+                    log_size: statement0.log_size,
+                },
+                SecureField::zero(),
+            ),
+        }
+    }
+}
+
+impl AirComponents for IsZeroComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct IsZeroAir {
+    pub log_size: u32,
+}
+
+impl Air for IsZeroAir {
+    const NAME: &'static str = "is_zero";
+    const COMPONENT_NAMES: &'static [&'static str] = &["is_zero"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ();
+    type LookupElements = ();
+    type Components = IsZeroComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(_channel: &mut impl Channel) -> Self::LookupElements {}
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        IsZeroComponents::new(statement0)
+            .component
+            .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(_statement1: &Self::Statement1) -> SecureField {
+        SecureField::zero()
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        _lookup_elements: &Self::LookupElements,
+        _statement1: &Self::Statement1,
+    ) -> Self::Components {
+        IsZeroComponents::new(statement0)
+    }
+}
+
+impl AirProver for IsZeroAir {
+    type Witness = ();
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        // About half of the values are zero
+        let mut rng = rand::thread_rng();
+        let values = (0..1 << self.log_size)
+            .map(|_| {
+                if rng.gen_bool(0.5) {
+                    M31::zero()
+                } else {
+                    M31::from(rng.gen_range(1..P))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        let trace = gen_is_zero_trace(&values)
+            .into_iter()
+            .map(|col| CircleEvaluation::new(domain, col))
+            .collect();
+
+        (
+            LogSizeStatement {
+                log_size: self.log_size,
+            },
+            trace,
+            (),
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        _witness: &Self::Witness,
+        _lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        ((), vec![])
+    }
+}
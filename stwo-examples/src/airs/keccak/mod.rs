@@ -0,0 +1,752 @@
+//! SHA3-256 over single-block messages, hashed once or chained, as in the Sha3 and Sha3-Chain
+//! workloads of the Benchmarks Report.
+//!
+//! Every permutation is an instance, and lanes move between components as four 16-bit limbs:
+//! - [`round`] has a row per round of Keccak-f[1600], 24 per instance, which consumes the state
+//!   before the round and yields the state after it.
+//! - [`sponge`] has a row per instance, which absorbs the block and squeezes the digest, either
+//!   into the block of the next instance of its chain or into the output of the chain.
+//! - The round constant table yields `(t, RC[t])` to the rounds, and the byte tables of
+//!   [`crate::gadgets::bytes`] check the bitwise operations.
+//!
+//! The verifier closes the relations with the first block and the output of every chain.
+
+pub mod reference;
+pub mod round;
+pub mod sponge;
+
+use itertools::chain;
+use num_traits::One;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, EvalAtRow,
+        FrameworkComponent, FrameworkEval, Relation, RelationEntry, PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+
+use self::{
+    reference::{
+        absorb, digest_block, pad_single_block, Block, Digest, MAX_SINGLE_BLOCK_LEN, N_ROUNDS,
+        ROUND_CONSTANTS,
+    },
+    round::{gen_round_row, RoundEval},
+    sponge::{gen_sponge_row, SpongeEval},
+};
+use super::{
+    bitwise::{gen_table_logup_trace, BitwiseElements, BitwiseTableEval},
+    check_log_size,
+};
+use crate::{
+    gadgets::{
+        bytes::{
+            gen_byte_split_table_logup_trace, to_bytes, ByteRelation, ByteSplitElements,
+            ByteSplitTableEval, ByteTables, TraceRow,
+        },
+        enabler::padded_log_size,
+    },
+    pipeline::{Air, AirComponents, AirProver, Statement, MAX_COLUMN_LOG_SIZE},
+    preprocessed::{
+        registry::PreprocessedRegistry, BitwiseOp, BitwiseTable, ByteSplit, IsFirst,
+        PreprocessedColumn, Seq,
+    },
+    public_inputs::PublicEntries,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Combines `(instance, t, A[0, 0] limbs, .., A[4, 4] limbs)` for the state before round `t`.
+relation!(StateElements, 102);
+/// Combines `(t, RC[t] bytes)`.
+relation!(RoundConstantElements, 9);
+/// Combines `(instance, j, limbs)` for lane `j` of the block of an instance.
+relation!(MessageElements, 6);
+/// Combines `(instance, digest limbs)` for the output of a chain.
+relation!(OutputElements, 17);
+
+#[derive(Clone)]
+pub struct Sha3Elements {
+    pub xor: BitwiseElements,
+    pub and: BitwiseElements,
+    pub byte_split: ByteSplitElements,
+    pub state: StateElements,
+    pub round_constant: RoundConstantElements,
+    pub message: MessageElements,
+    pub output: OutputElements,
+}
+
+impl Sha3Elements {
+    pub fn draw(channel: &mut impl Channel) -> Self {
+        Self {
+            xor: BitwiseElements::draw(channel),
+            and: BitwiseElements::draw(channel),
+            byte_split: ByteSplitElements::draw(channel),
+            state: StateElements::draw(channel),
+            round_constant: RoundConstantElements::draw(channel),
+            message: MessageElements::draw(channel),
+            output: OutputElements::draw(channel),
+        }
+    }
+
+    pub fn dummy() -> Self {
+        Self {
+            xor: BitwiseElements::dummy(),
+            and: BitwiseElements::dummy(),
+            byte_split: ByteSplitElements::dummy(),
+            state: StateElements::dummy(),
+            round_constant: RoundConstantElements::dummy(),
+            message: MessageElements::dummy(),
+            output: OutputElements::dummy(),
+        }
+    }
+
+    pub fn combine(&self, relation: Sha3Relation, values: &[M31]) -> SecureField {
+        match relation {
+            Sha3Relation::Xor => self.xor.combine(values),
+            Sha3Relation::And => self.and.combine(values),
+            Sha3Relation::ByteSplit => self.byte_split.combine(values),
+            Sha3Relation::State => self.state.combine(values),
+            Sha3Relation::RoundConstant => self.round_constant.combine(values),
+            Sha3Relation::Message => self.message.combine(values),
+            Sha3Relation::Output => self.output.combine(values),
+        }
+    }
+}
+
+/// The relations of the AIR, to tag the entries of the generated rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sha3Relation {
+    Xor,
+    And,
+    ByteSplit,
+    State,
+    RoundConstant,
+    Message,
+    Output,
+}
+
+impl From<ByteRelation> for Sha3Relation {
+    fn from(relation: ByteRelation) -> Self {
+        match relation {
+            ByteRelation::Xor => Self::Xor,
+            ByteRelation::And => Self::And,
+            ByteRelation::Split => Self::ByteSplit,
+        }
+    }
+}
+
+pub type Sha3Row = TraceRow<Sha3Relation>;
+
+/// The 8 bytes of `lane`, least significant first.
+pub fn lane_bytes(lane: u64) -> Vec<u32> {
+    to_bytes(lane, 8)
+}
+
+/// The 4 16-bit limbs of `lane`, least significant first.
+pub fn lane_limbs(lane: u64) -> [M31; 4] {
+    std::array::from_fn(|i| M31::from(((lane >> (16 * i)) & 0xffff) as u32))
+}
+
+/// The round constants, one round per row: `t` and the bytes of `RC[t]`. The rows past the last
+/// round hold their index and 0, and are never looked up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundConstantColumn {
+    Round,
+    Byte(usize),
+}
+
+impl RoundConstantColumn {
+    pub const LOG_SIZE: u32 = 5;
+
+    pub fn columns() -> [Self; 9] {
+        std::array::from_fn(|i| match i {
+            0 => Self::Round,
+            _ => Self::Byte(i - 1),
+        })
+    }
+}
+
+impl PreprocessedColumn for RoundConstantColumn {
+    fn log_size(&self) -> u32 {
+        Self::LOG_SIZE
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        let id = match self {
+            Self::Round => "keccak_round".to_string(),
+            Self::Byte(i) => format!("keccak_round_constant_byte_{i}"),
+        };
+        PreProcessedColumnId { id }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        match *self {
+            Self::Round => M31::from(row),
+            Self::Byte(i) => {
+                M31::from(ROUND_CONSTANTS.get(row).map_or(0, |&rc| to_bytes(rc, 8)[i]))
+            }
+        }
+    }
+}
+
+/// Yields every round constant with its multiplicity.
+pub struct RoundConstantTableEval {
+    pub lookup_elements: RoundConstantElements,
+}
+
+impl FrameworkEval for RoundConstantTableEval {
+    fn log_size(&self) -> u32 {
+        RoundConstantColumn::LOG_SIZE
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size() + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let values =
+            RoundConstantColumn::columns().map(|column| eval.get_preprocessed_column(column.id()));
+        let multiplicity = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::from(multiplicity),
+            &values,
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+/// The multiplicity column of the round constant table: every round is looked up once per
+/// instance.
+pub fn gen_round_constant_multiplicities(n_instances: usize) -> BaseColumn {
+    let mut col = BaseColumn::from_iter(
+        (0..1 << RoundConstantColumn::LOG_SIZE)
+            .map(|t| M31::from(if t < N_ROUNDS { n_instances } else { 0 })),
+    );
+    bit_reverse_coset_to_circle_domain_order(col.as_mut_slice());
+    col
+}
+
+pub fn gen_round_constant_table_logup_trace(
+    multiplicity_col: &BaseColumn,
+    lookup_elements: &RoundConstantElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let columns = RoundConstantColumn::columns().map(|column| column.gen_column().values);
+    let mut logup_gen = LogupTraceGenerator::new(RoundConstantColumn::LOG_SIZE);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (RoundConstantColumn::LOG_SIZE - LOG_N_LANES)) {
+        let numerator = PackedSecureField::from(multiplicity_col.data[row]);
+        let denom: PackedSecureField =
+            lookup_elements.combine(&columns.each_ref().map(|column| column.data[row]));
+        col_gen.write_frac(row, -numerator, denom);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+/// The log size of the [`round`] component: 24 rows per instance.
+pub fn rounds_log_size(n_instances: usize) -> u32 {
+    padded_log_size(N_ROUNDS * n_instances)
+}
+
+/// The log size of the [`sponge`] component: one row per instance.
+pub fn instances_log_size(n_instances: usize) -> u32 {
+    padded_log_size(n_instances)
+}
+
+pub fn preprocessed_registry(n_instances: usize) -> PreprocessedRegistry {
+    let instances_log_size = instances_log_size(n_instances);
+
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(rounds_log_size(n_instances)));
+    registry.add(IsFirst::new(instances_log_size));
+    registry.add(Seq::new(instances_log_size));
+    for column in RoundConstantColumn::columns() {
+        registry.add(column);
+    }
+    for column in chain!(
+        BitwiseTable::columns(BitwiseOp::Xor, 8),
+        BitwiseTable::columns(BitwiseOp::And, 8)
+    ) {
+        registry.add(column);
+    }
+    for column in ByteSplit::columns() {
+        registry.add(column);
+    }
+    registry
+}
+
+/// `n_messages` sample messages of [`MAX_SINGLE_BLOCK_LEN`] bytes, the most a block holds.
+pub fn sample_messages(n_messages: usize) -> Vec<Vec<u8>> {
+    (0..n_messages)
+        .map(|i| {
+            (0..MAX_SINGLE_BLOCK_LEN)
+                .map(|j| ((i * MAX_SINGLE_BLOCK_LEN + j) % 251) as u8)
+                .collect()
+        })
+        .collect()
+}
+
+/// The first block and the output of every chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sha3Statement0 {
+    pub blocks: Vec<Block>,
+    /// The number of hashes of every chain.
+    pub chain_length: u32,
+    pub digests: Vec<Digest>,
+}
+
+impl Sha3Statement0 {
+    pub fn n_instances(&self) -> usize {
+        self.blocks.len() * self.chain_length as usize
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.blocks.is_empty() || self.chain_length == 0 {
+            return Err("nothing is hashed".to_string());
+        }
+        if self.digests.len() != self.blocks.len() {
+            return Err(format!(
+                "{} chains but {} digests",
+                self.blocks.len(),
+                self.digests.len()
+            ));
+        }
+        // Bounds the number of instances before it is padded
+        if self.n_instances() > 1 << MAX_COLUMN_LOG_SIZE {
+            return Err(format!("too many instances: {}", self.n_instances()));
+        }
+        check_log_size("rounds log size", rounds_log_size(self.n_instances()))
+    }
+
+    /// The blocks yielded to the first instance of every chain, and the outputs consumed from
+    /// the last one.
+    pub fn public_entries(&self) -> (PublicEntries, PublicEntries) {
+        let chain_length = self.chain_length as usize;
+        let mut messages = PublicEntries::new();
+        let mut outputs = PublicEntries::new();
+        for (i, (block, digest)) in self.blocks.iter().zip(&self.digests).enumerate() {
+            let first = M31::from(i * chain_length);
+            for (j, &lane) in block.iter().enumerate() {
+                messages.add(
+                    -M31::one(),
+                    chain!([first, M31::from(j)], lane_limbs(lane)).collect(),
+                );
+            }
+            let last = M31::from((i + 1) * chain_length - 1);
+            outputs.add(
+                M31::one(),
+                chain!([last], digest.iter().flat_map(|&lane| lane_limbs(lane))).collect(),
+            );
+        }
+        (messages, outputs)
+    }
+}
+
+impl Statement for Sha3Statement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.blocks.len() as u64);
+        channel.mix_u64(self.chain_length as u64);
+        for lane in chain!(self.blocks.iter().flatten(), self.digests.iter().flatten()) {
+            channel.mix_u64(*lane);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sha3Statement1 {
+    pub round_claimed_sum: SecureField,
+    pub sponge_claimed_sum: SecureField,
+    pub round_constant_table_claimed_sum: SecureField,
+    pub xor_table_claimed_sum: SecureField,
+    pub and_table_claimed_sum: SecureField,
+    pub byte_split_table_claimed_sum: SecureField,
+}
+
+impl Statement for Sha3Statement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[
+            self.round_claimed_sum,
+            self.sponge_claimed_sum,
+            self.round_constant_table_claimed_sum,
+            self.xor_table_claimed_sum,
+            self.and_table_claimed_sum,
+            self.byte_split_table_claimed_sum,
+        ]);
+    }
+}
+
+pub type RoundComponent = FrameworkComponent<RoundEval>;
+pub type SpongeComponent = FrameworkComponent<SpongeEval>;
+pub type RoundConstantTableComponent = FrameworkComponent<RoundConstantTableEval>;
+pub type BitwiseTableComponent = FrameworkComponent<BitwiseTableEval>;
+pub type ByteSplitTableComponent = FrameworkComponent<ByteSplitTableEval>;
+
+pub struct Sha3Components {
+    pub round: RoundComponent,
+    pub sponge: SpongeComponent,
+    pub round_constant_table: RoundConstantTableComponent,
+    pub xor_table: BitwiseTableComponent,
+    pub and_table: BitwiseTableComponent,
+    pub byte_split_table: ByteSplitTableComponent,
+}
+
+impl Sha3Components {
+    pub fn new(
+        statement0: &Sha3Statement0,
+        elements: &Sha3Elements,
+        statement1: &Sha3Statement1,
+    ) -> Self {
+        let n_instances = statement0.n_instances();
+        let builder = &mut preprocessed_registry(n_instances).component_builder();
+
+        Self {
+            round: builder.build(
+                RoundEval {
+                    log_size: rounds_log_size(n_instances),
+                    elements: elements.clone(),
+                },
+                statement1.round_claimed_sum,
+            ),
+            sponge: builder.build(
+                SpongeEval {
+                    log_size: instances_log_size(n_instances),
+                    elements: elements.clone(),
+                },
+                statement1.sponge_claimed_sum,
+            ),
+            round_constant_table: builder.build(
+                RoundConstantTableEval {
+                    lookup_elements: elements.round_constant.clone(),
+                },
+                statement1.round_constant_table_claimed_sum,
+            ),
+            xor_table: builder.build(
+                BitwiseTableEval {
+                    op: BitwiseOp::Xor,
+                    n_bits: 8,
+                    lookup_elements: elements.xor.clone(),
+                },
+                statement1.xor_table_claimed_sum,
+            ),
+            and_table: builder.build(
+                BitwiseTableEval {
+                    op: BitwiseOp::And,
+                    n_bits: 8,
+                    lookup_elements: elements.and.clone(),
+                },
+                statement1.and_table_claimed_sum,
+            ),
+            byte_split_table: builder.build(
+                ByteSplitTableEval {
+                    lookup_elements: elements.byte_split.clone(),
+                },
+                statement1.byte_split_table_claimed_sum,
+            ),
+        }
+    }
+}
+
+impl AirComponents for Sha3Components {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![
+            &self.round as &dyn Component,
+            &self.sponge,
+            &self.round_constant_table,
+            &self.xor_table,
+            &self.and_table,
+            &self.byte_split_table,
+        ]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![
+            &self.round as &dyn ComponentProver<SimdBackend>,
+            &self.sponge,
+            &self.round_constant_table,
+            &self.xor_table,
+            &self.and_table,
+            &self.byte_split_table,
+        ]
+    }
+}
+
+/// Hashes every message, which must fit in a single block, `chain_length` times with SHA3-256:
+/// every hash but the first of a chain takes the previous digest as its message.
+pub struct Sha3Air {
+    pub messages: Vec<Vec<u8>>,
+    pub chain_length: usize,
+}
+
+impl Air for Sha3Air {
+    const NAME: &'static str = "sha3";
+    const COMPONENT_NAMES: &'static [&'static str] = &[
+        "round",
+        "sponge",
+        "round_constant_table",
+        "xor_table",
+        "and_table",
+        "byte_split_table",
+    ];
+
+    type Statement0 = Sha3Statement0;
+    type Statement1 = Sha3Statement1;
+    type LookupElements = Sha3Elements;
+    type Components = Sha3Components;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        Sha3Elements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.n_instances())
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let components = Sha3Components::new(
+            statement0,
+            &Sha3Elements::dummy(),
+            &Sha3Statement1 {
+                round_claimed_sum: SecureField::one(),
+                sponge_claimed_sum: SecureField::one(),
+                round_constant_table_claimed_sum: SecureField::one(),
+                xor_table_claimed_sum: SecureField::one(),
+                and_table_claimed_sum: SecureField::one(),
+                byte_split_table_claimed_sum: SecureField::one(),
+            },
+        );
+        let mut log_sizes = TreeVec::concat_cols(
+            components
+                .components()
+                .into_iter()
+                .map(|component| component.trace_log_degree_bounds()),
+        );
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] =
+            preprocessed_registry(statement0.n_instances()).log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.round_claimed_sum
+            + statement1.sponge_claimed_sum
+            + statement1.round_constant_table_claimed_sum
+            + statement1.xor_table_claimed_sum
+            + statement1.and_table_claimed_sum
+            + statement1.byte_split_table_claimed_sum
+    }
+
+    fn public_sum(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+    ) -> SecureField {
+        let (messages, outputs) = statement0.public_entries();
+        messages.logup_sum(&lookup_elements.message) + outputs.logup_sum(&lookup_elements.output)
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        Sha3Components::new(statement0, lookup_elements, statement1)
+    }
+}
+
+/// The rows of the components and the multiplicities of the byte tables.
+pub struct Sha3Witness {
+    pub round: Vec<Sha3Row>,
+    pub sponge: Vec<Sha3Row>,
+    pub tables: ByteTables,
+}
+
+impl Sha3Air {
+    pub fn n_instances(&self) -> usize {
+        self.messages.len() * self.chain_length
+    }
+
+    /// Runs every chain, generating the rows of its instances, and returns the outputs.
+    pub fn gen_witness(&self) -> (Vec<Digest>, Sha3Witness) {
+        assert!(
+            !self.messages.is_empty() && self.chain_length > 0,
+            "nothing to hash"
+        );
+        let mut witness = Sha3Witness {
+            round: vec![],
+            sponge: vec![],
+            tables: ByteTables::new(),
+        };
+
+        let mut outputs = vec![];
+        for (i, message) in self.messages.iter().enumerate() {
+            let mut block = pad_single_block(message);
+            for k in 0..self.chain_length {
+                let instance = i * self.chain_length + k;
+                let mut state = absorb(&block);
+                for t in 0..N_ROUNDS {
+                    let (row, next_state) = gen_round_row(&mut witness.tables, instance, t, &state);
+                    witness.round.push(row);
+                    state = next_state;
+                }
+
+                let is_link = k + 1 < self.chain_length;
+                let (row, digest) = gen_sponge_row(instance, &block, &state, is_link);
+                witness.sponge.push(row);
+
+                if is_link {
+                    block = digest_block(&digest);
+                } else {
+                    outputs.push(digest);
+                }
+            }
+        }
+
+        (outputs, witness)
+    }
+}
+
+impl AirProver for Sha3Air {
+    type Witness = Sha3Witness;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let (digests, witness) = self.gen_witness();
+        let rounds_log_size = rounds_log_size(self.n_instances());
+        let instances_log_size = instances_log_size(self.n_instances());
+
+        let columns = [
+            (
+                rounds_log_size,
+                Sha3Row::gen_trace(rounds_log_size, &witness.round),
+            ),
+            (
+                instances_log_size,
+                Sha3Row::gen_trace(instances_log_size, &witness.sponge),
+            ),
+            (
+                RoundConstantColumn::LOG_SIZE,
+                vec![gen_round_constant_multiplicities(self.n_instances())],
+            ),
+            (2 * 8, vec![witness.tables.xor.gen_column()]),
+            (2 * 8, vec![witness.tables.and.gen_column()]),
+            (ByteSplit::LOG_SIZE, vec![witness.tables.split.gen_column()]),
+        ];
+        let trace = columns
+            .into_iter()
+            .flat_map(|(log_size, columns)| {
+                let domain = CanonicCoset::new(log_size).circle_domain();
+                columns
+                    .into_iter()
+                    .map(move |column| CircleEvaluation::new(domain, column))
+            })
+            .collect();
+
+        (
+            Sha3Statement0 {
+                blocks: self
+                    .messages
+                    .iter()
+                    .map(|message| pad_single_block(message))
+                    .collect(),
+                chain_length: self.chain_length as u32,
+                digests,
+            },
+            trace,
+            witness,
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let combine = |relation, values: &[M31]| lookup_elements.combine(relation, values);
+        let (round_cols, round_claimed_sum) =
+            Sha3Row::gen_logup_trace(rounds_log_size(self.n_instances()), &witness.round, combine);
+        let (sponge_cols, sponge_claimed_sum) = Sha3Row::gen_logup_trace(
+            instances_log_size(self.n_instances()),
+            &witness.sponge,
+            combine,
+        );
+        let (round_constant_table_cols, round_constant_table_claimed_sum) =
+            gen_round_constant_table_logup_trace(
+                &gen_round_constant_multiplicities(self.n_instances()),
+                &lookup_elements.round_constant,
+            );
+        let (xor_table_cols, xor_table_claimed_sum) = gen_table_logup_trace(
+            BitwiseOp::Xor,
+            8,
+            &witness.tables.xor.gen_column(),
+            &lookup_elements.xor,
+        );
+        let (and_table_cols, and_table_claimed_sum) = gen_table_logup_trace(
+            BitwiseOp::And,
+            8,
+            &witness.tables.and.gen_column(),
+            &lookup_elements.and,
+        );
+        let (byte_split_table_cols, byte_split_table_claimed_sum) =
+            gen_byte_split_table_logup_trace(
+                &witness.tables.split.gen_column(),
+                &lookup_elements.byte_split,
+            );
+
+        (
+            Sha3Statement1 {
+                round_claimed_sum,
+                sponge_claimed_sum,
+                round_constant_table_claimed_sum,
+                xor_table_claimed_sum,
+                and_table_claimed_sum,
+                byte_split_table_claimed_sum,
+            },
+            [
+                round_cols,
+                sponge_cols,
+                round_constant_table_cols,
+                xor_table_cols,
+                and_table_cols,
+                byte_split_table_cols,
+            ]
+            .concat(),
+        )
+    }
+}
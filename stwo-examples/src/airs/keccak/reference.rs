@@ -0,0 +1,156 @@
+//! A plain implementation of Keccak-f[1600] and SHA3-256 (FIPS 202), which the trace generators
+//! follow and the AIR is checked against.
+
+/// The lanes `A[x, y]` of the state, at index `x + 5 * y`.
+pub type State = [u64; 25];
+/// The first 17 lanes of the state, the rate of SHA3-256.
+pub type Block = [u64; RATE_LANES];
+/// The first 4 lanes of the state.
+pub type Digest = [u64; 4];
+
+pub const N_ROUNDS: usize = 24;
+pub const RATE_LANES: usize = 17;
+
+/// The largest message that fits in a single block, with its padding.
+pub const MAX_SINGLE_BLOCK_LEN: usize = 8 * RATE_LANES - 1;
+
+pub const ROUND_CONSTANTS: [u64; N_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The left rotation of lane `A[x, y]` in ρ, at `[x][y]`.
+pub const ROTATION_OFFSETS: [[u32; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// The parities `C[x]` of the columns in θ.
+pub fn column_parities(state: &State) -> [u64; 5] {
+    std::array::from_fn(|x| (0..5).fold(0, |c, y| c ^ state[x + 5 * y]))
+}
+
+/// The state after round `t`.
+pub fn round(state: &State, t: usize) -> State {
+    // θ
+    let c = column_parities(state);
+    let d: [u64; 5] = std::array::from_fn(|x| c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1));
+    let a: State = std::array::from_fn(|i| state[i] ^ d[i % 5]);
+
+    // ρ and π: B[y, 2x + 3y] = A[x, y] <<< r[x, y]
+    let mut b = [0; 25];
+    for x in 0..5 {
+        for y in 0..5 {
+            b[y + 5 * ((2 * x + 3 * y) % 5)] = a[x + 5 * y].rotate_left(ROTATION_OFFSETS[x][y]);
+        }
+    }
+
+    // χ and ι
+    let mut out: State = std::array::from_fn(|i| {
+        let (x, y) = (i % 5, i / 5);
+        b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y])
+    });
+    out[0] ^= ROUND_CONSTANTS[t];
+    out
+}
+
+pub fn keccak_f(state: &State) -> State {
+    (0..N_ROUNDS).fold(*state, |state, t| round(&state, t))
+}
+
+/// Pads a message of at most [`MAX_SINGLE_BLOCK_LEN`] bytes into a single block, with the SHA-3
+/// domain bits.
+pub fn pad_single_block(message: &[u8]) -> Block {
+    assert!(
+        message.len() <= MAX_SINGLE_BLOCK_LEN,
+        "a {}-byte message does not fit in a single block",
+        message.len()
+    );
+    let mut bytes = [0u8; 8 * RATE_LANES];
+    bytes[..message.len()].copy_from_slice(message);
+    bytes[message.len()] ^= 0x06;
+    bytes[8 * RATE_LANES - 1] ^= 0x80;
+    std::array::from_fn(|i| u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap()))
+}
+
+/// The state absorbing a block into the zero state.
+pub fn absorb(block: &Block) -> State {
+    let mut state = [0; 25];
+    state[..RATE_LANES].copy_from_slice(block);
+    state
+}
+
+pub fn squeeze(state: &State) -> Digest {
+    state[..4].try_into().unwrap()
+}
+
+/// SHA3-256 of a message of at most [`MAX_SINGLE_BLOCK_LEN`] bytes.
+pub fn sha3_256_single_block(message: &[u8]) -> Digest {
+    squeeze(&keccak_f(&absorb(&pad_single_block(message))))
+}
+
+/// The block hashing a previous digest in [`chain`] mode.
+pub fn digest_block(digest: &Digest) -> Block {
+    let bytes = digest
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .collect::<Vec<_>>();
+    pad_single_block(&bytes)
+}
+
+/// Hashes `input` `n` times: every hash but the first takes the previous digest as its message.
+pub fn chain(input: &[u8], n: usize) -> Digest {
+    assert!(n > 0, "a chain has at least one hash");
+    (1..n).fold(sha3_256_single_block(input), |digest, _| {
+        squeeze(&keccak_f(&absorb(&digest_block(&digest))))
+    })
+}
+
+pub fn to_hex(digest: &Digest) -> String {
+    digest
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// `(message, SHA3-256 digest)` test vectors from NIST's examples.
+pub const TEST_VECTORS: [(&str, &str); 3] = [
+    (
+        "",
+        "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+    ),
+    (
+        "abc",
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+    ),
+    (
+        "The quick brown fox jumps over the lazy dog",
+        "69070dda01975c8c120c3aada1b282394e7f032fa9cf32f4cb2259a0897dfc04",
+    ),
+];
@@ -0,0 +1,271 @@
+//! The rounds of Keccak-f[1600]: every row consumes the state of an instance before round `t`
+//! and the round constant `RC[t]`, and yields the state after the round.
+//!
+//! Lanes are stored as 8 little-endian bytes. θ and χ are computed byte by byte in the XOR and
+//! AND tables, the rotations of θ and ρ split the bytes when they do not move whole bytes, and π
+//! only renames lanes.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval},
+    core::fields::m31::M31,
+};
+
+use super::{
+    lane_bytes, lane_limbs,
+    reference::{self, State, ROTATION_OFFSETS, ROUND_CONSTANTS},
+    Sha3Elements, Sha3Relation, Sha3Row,
+};
+use crate::{
+    gadgets::{
+        bytes::{bitwise_bytes, bytes_to_limbs, rotate_bytes_right, ByteTables},
+        enabler::Enabler,
+    },
+    preprocessed::BitwiseOp,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// The lane `A[x, y]` that π moves to `B[y, 2x + 3y]`, with the rotation of ρ: `(index of A,
+/// index of B, right rotation)`.
+fn rho_pi() -> impl Iterator<Item = (usize, usize, u32)> {
+    (0..5).cartesian_product(0..5).map(|(x, y)| {
+        (
+            x + 5 * y,
+            y + 5 * ((2 * x + 3 * y) % 5),
+            (64 - ROTATION_OFFSETS[x][y]) % 64,
+        )
+    })
+}
+
+pub struct RoundEval {
+    pub log_size: u32,
+    pub elements: Sha3Elements,
+}
+
+impl FrameworkEval for RoundEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let elements = &self.elements;
+        let enabled = Enabler::monotone(self.log_size).evaluate(&mut eval);
+        let numerator = E::EF::from(enabled.enabler.clone());
+
+        let [instance, t] = std::array::from_fn(|_| eval.next_trace_mask());
+        let state = (0..25)
+            .map(|_| (0..8).map(|_| eval.next_trace_mask()).collect_vec())
+            .collect_vec();
+        let round_constant = (0..8).map(|_| eval.next_trace_mask()).collect_vec();
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.state,
+            E::EF::one(),
+            &chain!(
+                [instance.clone(), t.clone()],
+                state.iter().flat_map(|lane| bytes_to_limbs(lane))
+            )
+            .collect_vec(),
+        );
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.round_constant,
+            E::EF::one(),
+            &chain!([t.clone()], round_constant.clone()).collect_vec(),
+        );
+
+        // θ: C[x] = A[x, 0] ^ .. ^ A[x, 4], D[x] = C[x - 1] ^ (C[x + 1] <<< 1), A[x, y] ^= D[x]
+        let xor = |eval: &mut E, a: &[E::F], b: &[E::F]| {
+            bitwise_bytes(eval, &elements.xor, numerator.clone(), a, b)
+        };
+        let c = (0..5)
+            .map(|x| {
+                (1..5).fold(state[x].clone(), |c, y| {
+                    xor(&mut eval, &c, &state[x + 5 * y])
+                })
+            })
+            .collect_vec();
+        let d = (0..5)
+            .map(|x| {
+                let rotated = rotate_bytes_right(
+                    &mut eval,
+                    &elements.byte_split,
+                    numerator.clone(),
+                    &c[(x + 1) % 5],
+                    63,
+                );
+                xor(&mut eval, &c[(x + 4) % 5], &rotated)
+            })
+            .collect_vec();
+        let a = (0..25)
+            .map(|i| xor(&mut eval, &state[i], &d[i % 5]))
+            .collect_vec();
+
+        // ρ and π: B[y, 2x + 3y] = A[x, y] <<< r[x, y]
+        let mut b = vec![vec![]; 25];
+        for (i, j, amount) in rho_pi() {
+            b[j] = rotate_bytes_right(
+                &mut eval,
+                &elements.byte_split,
+                numerator.clone(),
+                &a[i],
+                amount,
+            );
+        }
+
+        // χ: A[x, y] = B[x, y] ^ (!B[x + 1, y] & B[x + 2, y])
+        let mut out = (0..25)
+            .map(|i| {
+                let (x, y) = (i % 5, i / 5);
+                let not_b = b[(x + 1) % 5 + 5 * y]
+                    .iter()
+                    .map(|byte| E::F::from(M31::from(255)) - byte.clone())
+                    .collect_vec();
+                let and = bitwise_bytes(
+                    &mut eval,
+                    &elements.and,
+                    numerator.clone(),
+                    &not_b,
+                    &b[(x + 2) % 5 + 5 * y],
+                );
+                xor(&mut eval, &b[i], &and)
+            })
+            .collect_vec();
+
+        // ι: A[0, 0] ^= RC[t]
+        out[0] = xor(&mut eval, &out[0], &round_constant);
+
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.state,
+            -E::EF::one(),
+            &chain!(
+                [instance, t + E::F::one()],
+                out.iter().flat_map(|lane| bytes_to_limbs(lane))
+            )
+            .collect_vec(),
+        );
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates round `t` of instance `instance` from the state before the round, and returns it
+/// with the state after the round.
+pub fn gen_round_row(
+    tables: &mut ByteTables,
+    instance: usize,
+    t: usize,
+    state: &State,
+) -> (Sha3Row, State) {
+    let mut row = Sha3Row::new();
+    let [instance_value, t_value] = [instance, t].map(M31::from);
+
+    row.columns.extend([instance_value, t_value]);
+    for &lane in state {
+        row.push(&lane_bytes(lane));
+    }
+    row.push(&lane_bytes(ROUND_CONSTANTS[t]));
+    row.entry(
+        Sha3Relation::State,
+        M31::one(),
+        chain!(
+            [instance_value, t_value],
+            state.iter().flat_map(|&lane| lane_limbs(lane))
+        )
+        .collect(),
+    );
+    row.entry(
+        Sha3Relation::RoundConstant,
+        M31::one(),
+        chain!(
+            [t_value],
+            lane_bytes(ROUND_CONSTANTS[t]).into_iter().map(M31::from)
+        )
+        .collect(),
+    );
+
+    // θ
+    let c = reference::column_parities(state);
+    for x in 0..5 {
+        let mut parity = state[x];
+        for y in 1..5 {
+            row.bitwise(
+                tables,
+                BitwiseOp::Xor,
+                &lane_bytes(parity),
+                &lane_bytes(state[x + 5 * y]),
+            );
+            parity ^= state[x + 5 * y];
+        }
+    }
+    let d: [u64; 5] = std::array::from_fn(|x| {
+        row.rotate_right(tables, &lane_bytes(c[(x + 1) % 5]), 63);
+        let rotated = c[(x + 1) % 5].rotate_right(63);
+        row.bitwise(
+            tables,
+            BitwiseOp::Xor,
+            &lane_bytes(c[(x + 4) % 5]),
+            &lane_bytes(rotated),
+        );
+        c[(x + 4) % 5] ^ rotated
+    });
+    let a: State = std::array::from_fn(|i| {
+        row.bitwise(
+            tables,
+            BitwiseOp::Xor,
+            &lane_bytes(state[i]),
+            &lane_bytes(d[i % 5]),
+        );
+        state[i] ^ d[i % 5]
+    });
+
+    // ρ and π
+    let mut b = [0; 25];
+    for (i, j, amount) in rho_pi() {
+        row.rotate_right(tables, &lane_bytes(a[i]), amount);
+        b[j] = a[i].rotate_right(amount);
+    }
+
+    // χ
+    let mut out: State = std::array::from_fn(|i| {
+        let (x, y) = (i % 5, i / 5);
+        let [b1, b2] = [b[(x + 1) % 5 + 5 * y], b[(x + 2) % 5 + 5 * y]];
+        row.bitwise(tables, BitwiseOp::And, &lane_bytes(!b1), &lane_bytes(b2));
+        row.bitwise(
+            tables,
+            BitwiseOp::Xor,
+            &lane_bytes(b[i]),
+            &lane_bytes(!b1 & b2),
+        );
+        b[i] ^ (!b1 & b2)
+    });
+
+    // ι
+    row.bitwise(
+        tables,
+        BitwiseOp::Xor,
+        &lane_bytes(out[0]),
+        &lane_bytes(ROUND_CONSTANTS[t]),
+    );
+    out[0] ^= ROUND_CONSTANTS[t];
+    debug_assert_eq!(out, reference::round(state, t));
+
+    row.entry(
+        Sha3Relation::State,
+        -M31::one(),
+        chain!(
+            [instance_value, t_value + M31::one()],
+            out.iter().flat_map(|&lane| lane_limbs(lane))
+        )
+        .collect(),
+    );
+    (row, out)
+}
@@ -0,0 +1,176 @@
+//! The SHA3-256 sponge around the permutation, for single-block messages: row `i` absorbs the
+//! block of instance `i` into the zero state, consumes the state after the last round, and
+//! squeezes the digest either into the block of instance `i + 1`, which hashes it, or into the
+//! output of the chain.
+//!
+//! As in the SHA-256 chain, whether an instance is linked is a witness bit, which the verifier
+//! checks through the first block and the output of every chain.
+
+use itertools::{chain, Itertools};
+use num_traits::{One, Zero};
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval},
+    core::fields::m31::M31,
+};
+
+use super::{
+    lane_limbs,
+    reference::{absorb, digest_block, squeeze, Block, Digest, State, N_ROUNDS, RATE_LANES},
+    Sha3Elements, Sha3Relation, Sha3Row,
+};
+use crate::{
+    gadgets::enabler::Enabler,
+    preprocessed::{PreprocessedColumn, Seq},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+pub struct SpongeEval {
+    pub log_size: u32,
+    pub elements: Sha3Elements,
+}
+
+impl FrameworkEval for SpongeEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let elements = &self.elements;
+        let enabled = Enabler::monotone(self.log_size).evaluate(&mut eval);
+        let instance = eval.get_preprocessed_column(Seq::new(self.log_size).id());
+
+        let block = (0..4 * RATE_LANES)
+            .map(|_| eval.next_trace_mask())
+            .collect_vec();
+        let state = (0..4 * 25).map(|_| eval.next_trace_mask()).collect_vec();
+        let is_link = eval.next_trace_mask();
+        eval.add_constraint(is_link.clone() * (is_link.clone() - E::F::one()));
+
+        for (j, lane) in block.chunks(4).enumerate() {
+            enabled.add_to_relation(
+                &mut eval,
+                &elements.message,
+                E::EF::one(),
+                &chain!(
+                    [instance.clone(), E::F::from(M31::from(j))],
+                    lane.iter().cloned()
+                )
+                .collect_vec(),
+            );
+        }
+        // The capacity lanes start at 0
+        let absorbed = chain!(block, (0..4 * (25 - RATE_LANES)).map(|_| E::F::zero()));
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.state,
+            -E::EF::one(),
+            &chain!([instance.clone(), E::F::zero()], absorbed).collect_vec(),
+        );
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.state,
+            E::EF::one(),
+            &chain!(
+                [instance.clone(), E::F::from(M31::from(N_ROUNDS))],
+                state.iter().cloned()
+            )
+            .collect_vec(),
+        );
+
+        // The block hashing the digest: its 4 lanes followed by constant padding
+        let digest = &state[..16];
+        let padding = digest_block(&[0; 4])[4..]
+            .iter()
+            .flat_map(|&lane| lane_limbs(lane))
+            .map(E::F::from)
+            .collect_vec();
+        let next_block = chain!(digest.iter().cloned(), padding).collect_vec();
+        let next_instance = instance.clone() + E::F::one();
+        for (j, lane) in next_block.chunks(4).enumerate() {
+            enabled.add_to_relation(
+                &mut eval,
+                &elements.message,
+                -E::EF::from(is_link.clone()),
+                &chain!(
+                    [next_instance.clone(), E::F::from(M31::from(j))],
+                    lane.iter().cloned()
+                )
+                .collect_vec(),
+            );
+        }
+
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.output,
+            -E::EF::from(E::F::one() - is_link),
+            &chain!([instance], digest.iter().cloned()).collect_vec(),
+        );
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the sponge of instance `instance`, from its block and the state after the last
+/// round, and returns it with the digest.
+pub fn gen_sponge_row(
+    instance: usize,
+    block: &Block,
+    state: &State,
+    is_link: bool,
+) -> (Sha3Row, Digest) {
+    let mut row = Sha3Row::new();
+    let instance = M31::from(instance);
+    let is_link = M31::from(is_link as u32);
+    let digest = squeeze(state);
+
+    row.columns
+        .extend(block.iter().flat_map(|&lane| lane_limbs(lane)));
+    row.columns
+        .extend(state.iter().flat_map(|&lane| lane_limbs(lane)));
+    row.columns.push(is_link);
+
+    for (j, &lane) in block.iter().enumerate() {
+        row.entry(
+            Sha3Relation::Message,
+            M31::one(),
+            chain!([instance, M31::from(j)], lane_limbs(lane)).collect(),
+        );
+    }
+    row.entry(
+        Sha3Relation::State,
+        -M31::one(),
+        chain!(
+            [instance, M31::zero()],
+            absorb(block).iter().flat_map(|&lane| lane_limbs(lane))
+        )
+        .collect(),
+    );
+    row.entry(
+        Sha3Relation::State,
+        M31::one(),
+        chain!(
+            [instance, M31::from(N_ROUNDS)],
+            state.iter().flat_map(|&lane| lane_limbs(lane))
+        )
+        .collect(),
+    );
+    for (j, &lane) in digest_block(&digest).iter().enumerate() {
+        row.entry(
+            Sha3Relation::Message,
+            -is_link,
+            chain!([instance + M31::one(), M31::from(j)], lane_limbs(lane)).collect(),
+        );
+    }
+    row.entry(
+        Sha3Relation::Output,
+        -(M31::one() - is_link),
+        chain!([instance], digest.iter().flat_map(|&lane| lane_limbs(lane))).collect(),
+    );
+    (row, digest)
+}
@@ -0,0 +1,264 @@
+//! The AIR from "Local Row Constraints": a permutation into a sorted column whose consecutive rows
+//! increase by one.
+
+use num_traits::One;
+use rand::{prelude::SliceRandom, Rng};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, EvalAtRow,
+        FrameworkComponent, FrameworkEval, Relation, RelationEntry, ORIGINAL_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::{ClaimedSumStatement, LogSizeStatement};
+use crate::{
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::{registry::PreprocessedRegistry, IsFirst, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+relation!(LookupElements, 1);
+
+pub fn preprocessed_registry(log_size: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(log_size));
+    registry
+}
+
+pub struct LocalRowConstraintsEval {
+    pub is_first_id: PreProcessedColumnId,
+    pub log_size: u32,
+    pub lookup_elements: LookupElements,
+}
+
+impl FrameworkEval for LocalRowConstraintsEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let unsorted_col = eval.next_trace_mask();
+        let [sorted_col_prev_row, sorted_col_curr_row] =
+            eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+
+        // ANCHOR: constraint
+        let is_first_col = eval.get_preprocessed_column(self.is_first_id.clone());
+
+        eval.add_constraint(
+            (E::F::one() - is_first_col.clone())
+                * (E::F::one() - (sorted_col_curr_row.clone() - sorted_col_prev_row.clone())),
+        );
+        // ANCHOR_END: constraint
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            E::EF::one(),
+            &[unsorted_col],
+        ));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::one(),
+            &[sorted_col_curr_row],
+        ));
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+pub type LocalRowConstraintsComponent = FrameworkComponent<LocalRowConstraintsEval>;
+
+pub struct LocalRowConstraintsComponents {
+    pub component: LocalRowConstraintsComponent,
+}
+
+impl LocalRowConstraintsComponents {
+    pub fn new(
+        statement0: &LogSizeStatement,
+        lookup_elements: &LookupElements,
+        statement1: &ClaimedSumStatement,
+    ) -> Self {
+        Self {
+            component: preprocessed_registry(statement0.log_size)
+                .component_builder()
+                .build(
+                    LocalRowConstraintsEval {
+                        is_first_id: IsFirst::new(statement0.log_size).id(),
+                        log_size: statement0.log_size,
+                        lookup_elements: lookup_elements.clone(),
+                    },
+                    statement1.claimed_sum,
+                ),
+        }
+    }
+}
+
+impl AirComponents for LocalRowConstraintsComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct LocalRowConstraintsAir {
+    pub log_size: u32,
+}
+
+impl Air for LocalRowConstraintsAir {
+    const NAME: &'static str = "local_row_constraints";
+    const COMPONENT_NAMES: &'static [&'static str] = &["local_row_constraints"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = LookupElements;
+    type Components = LocalRowConstraintsComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        LookupElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.log_size)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        LocalRowConstraintsComponents::new(
+            statement0,
+            &LookupElements::dummy(),
+            &ClaimedSumStatement {
+                claimed_sum: SecureField::one(),
+            },
+        )
+        .component
+        .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        LocalRowConstraintsComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for LocalRowConstraintsAir {
+    type Witness = Vec<BaseColumn>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let columns = gen_columns(self.log_size, &mut rand::thread_rng(), true);
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        let trace = columns
+            .iter()
+            .map(|col| CircleEvaluation::new(domain, col.clone()))
+            .collect();
+
+        (
+            LogSizeStatement {
+                log_size: self.log_size,
+            },
+            trace,
+            columns,
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let [unsorted_col, sorted_col] = &witness[..] else {
+            panic!("unexpected witness layout");
+        };
+        let (logup_cols, claimed_sum) =
+            gen_logup_trace(self.log_size, unsorted_col, sorted_col, lookup_elements);
+        (ClaimedSumStatement { claimed_sum }, logup_cols)
+    }
+}
+
+// ANCHOR: gen_columns
+/// Generates the unsorted and sorted columns: a random permutation of `0..2^log_size` and the
+/// values in order. Unless `bit_reverse` is set, the columns are left in row order, which is not
+/// the order the committed columns are read in.
+pub fn gen_columns(log_size: u32, rng: &mut impl Rng, bit_reverse: bool) -> Vec<BaseColumn> {
+    let sorted_values = (0u32..1 << log_size).map(M31::from).collect::<Vec<_>>();
+    let mut unsorted_values = sorted_values.clone();
+    unsorted_values.shuffle(rng);
+
+    [unsorted_values, sorted_values]
+        .into_iter()
+        .map(|mut values| {
+            if bit_reverse {
+                bit_reverse_coset_to_circle_domain_order(&mut values);
+            }
+            BaseColumn::from_iter(values)
+        })
+        .collect()
+}
+// ANCHOR_END: gen_columns
+
+/// Generates the LogUp column that consumes the unsorted values and yields the sorted ones.
+pub fn gen_logup_trace(
+    log_size: u32,
+    unsorted_col: &BaseColumn,
+    sorted_col: &BaseColumn,
+    lookup_elements: &LookupElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        // 1 / unsorted - 1 / sorted = (sorted - unsorted) / (unsorted * sorted)
+        let unsorted_val: PackedSecureField = lookup_elements.combine(&[unsorted_col.data[row]]);
+        let sorted_val: PackedSecureField = lookup_elements.combine(&[sorted_col.data[row]]);
+        col_gen.write_frac(row, sorted_val - unsorted_val, unsorted_val * sorted_val);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
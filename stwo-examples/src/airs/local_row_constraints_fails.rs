@@ -0,0 +1,291 @@
+//! The two failing attempts from "Local Row Constraints", kept to show that the prover rejects
+//! them: proving either one fails with `ConstraintsNotSatisfied`.
+//!
+//! Both commit to the columns in row order rather than in bit-reversed circle domain order, and
+//! the first one also checks the consecutive rows constraint on the first row, against the last
+//! one.
+
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{
+        EvalAtRow, FrameworkComponent, FrameworkEval, RelationEntry, ORIGINAL_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{column::BaseColumn, SimdBackend},
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+};
+
+use super::{
+    local_row_constraints::{
+        gen_columns, gen_logup_trace, LocalRowConstraintsAir, LocalRowConstraintsComponents,
+        LookupElements,
+    },
+    ClaimedSumStatement, LogSizeStatement,
+};
+use crate::{
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::registry::PreprocessedRegistry,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Generates the trace of both attempts, leaving the columns in row order.
+fn gen_trace(
+    log_size: u32,
+) -> (
+    LogSizeStatement,
+    ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    Vec<BaseColumn>,
+) {
+    let columns = gen_columns(log_size, &mut rand::thread_rng(), false);
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let trace = columns
+        .iter()
+        .map(|col| CircleEvaluation::new(domain, col.clone()))
+        .collect();
+    (LogSizeStatement { log_size }, trace, columns)
+}
+
+fn gen_interaction_trace(
+    log_size: u32,
+    witness: &[BaseColumn],
+    lookup_elements: &LookupElements,
+) -> (
+    ClaimedSumStatement,
+    ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+) {
+    let [unsorted_col, sorted_col] = witness else {
+        panic!("unexpected witness layout");
+    };
+    let (logup_cols, claimed_sum) =
+        gen_logup_trace(log_size, unsorted_col, sorted_col, lookup_elements);
+    (ClaimedSumStatement { claimed_sum }, logup_cols)
+}
+
+/// The constraints of the first attempt, where every row, the first one included, is one more
+/// than the previous row.
+pub struct UngatedEval {
+    pub log_size: u32,
+    pub lookup_elements: LookupElements,
+}
+
+impl FrameworkEval for UngatedEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    // ANCHOR: evaluate
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let unsorted_col = eval.next_trace_mask();
+        let [sorted_col_prev_row, sorted_col_curr_row] =
+            eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+
+        eval.add_constraint(
+            E::F::one() - (sorted_col_curr_row.clone() - sorted_col_prev_row.clone()),
+        );
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            E::EF::one(),
+            &[unsorted_col],
+        ));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::one(),
+            &[sorted_col_curr_row],
+        ));
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+    // ANCHOR_END: evaluate
+}
+
+pub type UngatedComponent = FrameworkComponent<UngatedEval>;
+
+pub struct UngatedComponents {
+    pub component: UngatedComponent,
+}
+
+impl UngatedComponents {
+    pub fn new(
+        statement0: &LogSizeStatement,
+        lookup_elements: &LookupElements,
+        statement1: &ClaimedSumStatement,
+    ) -> Self {
+        Self {
+            component: PreprocessedRegistry::new().component_builder().build(
+                UngatedEval {
+                    log_size: statement0.log_size,
+                    lookup_elements: lookup_elements.clone(),
+                },
+                statement1.claimed_sum,
+            ),
+        }
+    }
+}
+
+impl AirComponents for UngatedComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+/// The first attempt, from `local_row_constraints_fails_1.rs`.
+pub struct LocalRowConstraintsFails1Air {
+    pub log_size: u32,
+}
+
+impl Air for LocalRowConstraintsFails1Air {
+    const NAME: &'static str = "local_row_constraints_fails_1";
+    const COMPONENT_NAMES: &'static [&'static str] = &["local_row_constraints"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = LookupElements;
+    type Components = UngatedComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        LookupElements::draw(channel)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        UngatedComponents::new(
+            statement0,
+            &LookupElements::dummy(),
+            &ClaimedSumStatement {
+                claimed_sum: SecureField::one(),
+            },
+        )
+        .component
+        .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        UngatedComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for LocalRowConstraintsFails1Air {
+    type Witness = Vec<BaseColumn>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        gen_trace(self.log_size)
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        gen_interaction_trace(self.log_size, witness, lookup_elements)
+    }
+}
+
+/// The second attempt, from `local_row_constraints_fails_2.rs`: the constraints of
+/// [`LocalRowConstraintsAir`] on columns committed in row order.
+pub struct LocalRowConstraintsFails2Air {
+    pub log_size: u32,
+}
+
+impl Air for LocalRowConstraintsFails2Air {
+    const NAME: &'static str = "local_row_constraints_fails_2";
+    const COMPONENT_NAMES: &'static [&'static str] = LocalRowConstraintsAir::COMPONENT_NAMES;
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = LookupElements;
+    type Components = LocalRowConstraintsComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        LookupElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        LocalRowConstraintsAir::preprocessed_registry(statement0)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        LocalRowConstraintsAir::log_sizes(statement0)
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        LocalRowConstraintsComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for LocalRowConstraintsFails2Air {
+    type Witness = Vec<BaseColumn>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        gen_trace(self.log_size)
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        gen_interaction_trace(self.log_size, witness, lookup_elements)
+    }
+}
@@ -0,0 +1,502 @@
+//! Non-deterministic read-only memory, as described in "Cairo Overview": a memory component holds
+//! every accessed cell once, sorted by address, and yields `(address, value)` as many times as the
+//! cell is read. Any component reads memory by consuming the tuple with [`read_memory`].
+//!
+//! On the sorted rows, consecutive addresses increase by 0 or 1, so the memory is continuous, and
+//! rows with the same address hold the same value, so the memory is single-valued.
+
+use std::collections::BTreeMap;
+
+use itertools::chain;
+use num_traits::One;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, EvalAtRow,
+        FrameworkComponent, FrameworkEval, Relation, RelationEntry, ORIGINAL_TRACE_IDX,
+        PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{
+            m31::{M31, P},
+            qm31::SecureField,
+        },
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::check_log_size;
+use crate::{
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::{registry::PreprocessedRegistry, IsFirst, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Combines `(address, value)`.
+relation!(MemoryElements, 2);
+
+/// Reads `value` at `address`: consumes the tuple yielded by the memory component. `numerator` is
+/// the number of reads, usually 1 or an enabler column.
+pub fn read_memory<E: EvalAtRow>(
+    eval: &mut E,
+    memory_elements: &MemoryElements,
+    numerator: E::EF,
+    address: E::F,
+    value: E::F,
+) {
+    eval.add_to_relation(RelationEntry::new(
+        memory_elements,
+        numerator,
+        &[address, value],
+    ));
+}
+
+/// The memory cells and the number of times each is read, filled while generating the traces of
+/// the consumer components.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryTable {
+    cells: BTreeMap<u32, (M31, u32)>,
+}
+
+impl MemoryTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns `value` to the cell at `address`, which must be empty or hold the same value.
+    pub fn insert(&mut self, address: u32, value: M31) {
+        let (current, _) = self.cells.entry(address).or_insert((value, 0));
+        assert_eq!(*current, value, "memory cell {address} is already assigned");
+    }
+
+    /// Returns the value at `address` and counts the read.
+    pub fn read(&mut self, address: u32) -> M31 {
+        let Some((value, mult)) = self.cells.get_mut(&address) else {
+            panic!("memory cell {address} is not assigned");
+        };
+        *mult += 1;
+        *value
+    }
+
+    /// The log size of the memory component: the range of addresses, holes included, padded to a
+    /// power of two and at least a SIMD vector.
+    pub fn log_size(&self) -> u32 {
+        let n_rows = match (self.cells.first_key_value(), self.cells.last_key_value()) {
+            (Some((first, _)), Some((last, _))) => (last - first + 1) as usize,
+            _ => 1,
+        };
+        n_rows.next_power_of_two().ilog2().max(LOG_N_LANES)
+    }
+
+    /// Generates the columns `address, value, mult` of the memory component, sorted by address.
+    /// The holes between the assigned addresses are filled with zero-valued cells that are never
+    /// read, and the padding rows repeat the last cell with a multiplicity of 0.
+    pub fn gen_trace(&self, log_size: u32) -> Vec<BaseColumn> {
+        assert!(log_size >= self.log_size());
+        let (first, last) = match (self.cells.first_key_value(), self.cells.last_key_value()) {
+            (Some((first, _)), Some((last, _))) => (*first, *last),
+            _ => (0, 0),
+        };
+
+        let mut columns = [vec![], vec![], vec![]];
+        for address in first..=last {
+            let (value, mult) = self
+                .cells
+                .get(&address)
+                .copied()
+                .unwrap_or((M31::from(0), 0));
+            columns[0].push(M31::from(address));
+            columns[1].push(value);
+            columns[2].push(M31::from(mult));
+        }
+        let n_padding_rows = (1 << log_size) - columns[0].len();
+        for column in &mut columns[..2] {
+            let last_value = *column.last().unwrap();
+            column.extend(std::iter::repeat(last_value).take(n_padding_rows));
+        }
+        columns[2].extend(std::iter::repeat(M31::from(0)).take(n_padding_rows));
+
+        // The rows are compared to the previous ones, so they are in circle domain order
+        columns
+            .into_iter()
+            .map(|column| {
+                let mut column = BaseColumn::from_iter(column);
+                bit_reverse_coset_to_circle_domain_order(column.as_mut_slice());
+                column
+            })
+            .collect()
+    }
+}
+
+pub fn preprocessed_registry(memory_log_size: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(memory_log_size));
+    registry
+}
+
+/// The sorted memory: yields `(address, value)` with multiplicity `mult`.
+pub struct MemoryEval {
+    pub is_first_id: PreProcessedColumnId,
+    pub log_size: u32,
+    pub memory_elements: MemoryElements,
+}
+
+impl FrameworkEval for MemoryEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let [address_prev_row, address] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+        let [value_prev_row, value] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+        let mult = eval.next_trace_mask();
+
+        let is_first_col = eval.get_preprocessed_column(self.is_first_id.clone());
+        let not_first = E::F::one() - is_first_col;
+        let address_diff = address.clone() - address_prev_row;
+
+        // Continuity: the address increases by 0 or 1
+        eval.add_constraint(
+            not_first.clone() * address_diff.clone() * (address_diff.clone() - E::F::one()),
+        );
+        // Single-valuedness: the value only changes along with the address
+        eval.add_constraint(
+            not_first * (value.clone() - value_prev_row) * (address_diff - E::F::one()),
+        );
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.memory_elements,
+            -E::EF::from(mult),
+            &[address, value],
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+pub fn gen_memory_logup_trace(
+    log_size: u32,
+    memory_columns: &[BaseColumn],
+    memory_elements: &MemoryElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [address_col, value_col, mult_col] = memory_columns else {
+        panic!("unexpected memory trace layout");
+    };
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let denom: PackedSecureField =
+            memory_elements.combine(&[address_col.data[row], value_col.data[row]]);
+        col_gen.write_frac(row, -PackedSecureField::from(mult_col.data[row]), denom);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+/// An example consumer: reads one memory cell per row.
+pub struct ReadsEval {
+    pub log_size: u32,
+    pub memory_elements: MemoryElements,
+}
+
+impl FrameworkEval for ReadsEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let address = eval.next_trace_mask();
+        let value = eval.next_trace_mask();
+
+        read_memory(
+            &mut eval,
+            &self.memory_elements,
+            E::EF::one(),
+            address,
+            value,
+        );
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+pub fn gen_reads_logup_trace(
+    log_size: u32,
+    reads_columns: &[BaseColumn],
+    memory_elements: &MemoryElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [address_col, value_col] = reads_columns else {
+        panic!("unexpected reads trace layout");
+    };
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let denom: PackedSecureField =
+            memory_elements.combine(&[address_col.data[row], value_col.data[row]]);
+        col_gen.write_frac(row, PackedSecureField::one(), denom);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+/// The log sizes of the reads and memory components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStatement0 {
+    pub reads_log_size: u32,
+    pub memory_log_size: u32,
+}
+
+impl Statement for MemoryStatement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.reads_log_size as u64);
+        channel.mix_u64(self.memory_log_size as u64);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStatement1 {
+    pub reads_claimed_sum: SecureField,
+    pub memory_claimed_sum: SecureField,
+}
+
+impl Statement for MemoryStatement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[self.reads_claimed_sum, self.memory_claimed_sum]);
+    }
+}
+
+pub type ReadsComponent = FrameworkComponent<ReadsEval>;
+pub type MemoryComponent = FrameworkComponent<MemoryEval>;
+
+pub struct MemoryComponents {
+    pub reads_component: ReadsComponent,
+    pub memory_component: MemoryComponent,
+}
+
+impl MemoryComponents {
+    pub fn new(
+        statement0: &MemoryStatement0,
+        lookup_elements: &MemoryElements,
+        statement1: &MemoryStatement1,
+    ) -> Self {
+        let builder = &mut preprocessed_registry(statement0.memory_log_size).component_builder();
+
+        let reads_component = builder.build(
+            ReadsEval {
+                log_size: statement0.reads_log_size,
+                memory_elements: lookup_elements.clone(),
+            },
+            statement1.reads_claimed_sum,
+        );
+
+        let memory_component = builder.build(
+            MemoryEval {
+                is_first_id: IsFirst::new(statement0.memory_log_size).id(),
+                log_size: statement0.memory_log_size,
+                memory_elements: lookup_elements.clone(),
+            },
+            statement1.memory_claimed_sum,
+        );
+
+        Self {
+            reads_component,
+            memory_component,
+        }
+    }
+}
+
+impl AirComponents for MemoryComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        chain![[
+            &self.reads_component as &dyn Component,
+            &self.memory_component as &dyn Component
+        ]]
+        .collect()
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        chain![[
+            &self.reads_component as &dyn ComponentProver<SimdBackend>,
+            &self.memory_component as &dyn ComponentProver<SimdBackend>
+        ]]
+        .collect()
+    }
+}
+
+/// Reads `2^(log_size + 1)` random cells of a memory of `2^log_size` random values.
+pub struct MemoryAir {
+    pub log_size: u32,
+}
+
+impl Air for MemoryAir {
+    const NAME: &'static str = "memory";
+    const COMPONENT_NAMES: &'static [&'static str] = &["reads", "memory"];
+
+    type Statement0 = MemoryStatement0;
+    type Statement1 = MemoryStatement1;
+    type LookupElements = MemoryElements;
+    type Components = MemoryComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        check_log_size("reads log size", statement0.reads_log_size)?;
+        check_log_size("memory log size", statement0.memory_log_size)
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        MemoryElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.memory_log_size)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let components = MemoryComponents::new(
+            statement0,
+            &MemoryElements::dummy(),
+            &MemoryStatement1 {
+                reads_claimed_sum: SecureField::one(),
+                memory_claimed_sum: SecureField::one(),
+            },
+        );
+        let mut log_sizes = TreeVec::concat_cols(
+            [
+                components.reads_component.trace_log_degree_bounds(),
+                components.memory_component.trace_log_degree_bounds(),
+            ]
+            .into_iter(),
+        );
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] =
+            preprocessed_registry(statement0.memory_log_size).log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.reads_claimed_sum + statement1.memory_claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        MemoryComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for MemoryAir {
+    /// The columns of the reads component followed by the columns of the memory component.
+    type Witness = Vec<BaseColumn>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        // The memory does not have to start at address 0
+        const BASE_ADDRESS: u32 = 100;
+        let mut rng = rand::thread_rng();
+        let mut memory = MemoryTable::new();
+        for address in BASE_ADDRESS..BASE_ADDRESS + (1 << self.log_size) {
+            memory.insert(address, M31::from(rng.gen_range(0..P)));
+        }
+
+        let reads_log_size = self.log_size + 1;
+        let (addresses, values): (Vec<_>, Vec<_>) = (0..1 << reads_log_size)
+            .map(|_| {
+                let address = BASE_ADDRESS + rng.gen_range(0..1 << self.log_size);
+                (M31::from(address), memory.read(address))
+            })
+            .unzip();
+        let reads_columns = vec![
+            BaseColumn::from_iter(addresses),
+            BaseColumn::from_iter(values),
+        ];
+
+        let memory_log_size = memory.log_size();
+        let memory_columns = memory.gen_trace(memory_log_size);
+
+        let reads_domain = CanonicCoset::new(reads_log_size).circle_domain();
+        let memory_domain = CanonicCoset::new(memory_log_size).circle_domain();
+        let trace = chain!(
+            reads_columns
+                .iter()
+                .map(|col| CircleEvaluation::new(reads_domain, col.clone())),
+            memory_columns
+                .iter()
+                .map(|col| CircleEvaluation::new(memory_domain, col.clone()))
+        )
+        .collect();
+
+        (
+            MemoryStatement0 {
+                reads_log_size,
+                memory_log_size,
+            },
+            trace,
+            chain!(reads_columns, memory_columns).collect(),
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let (reads_columns, memory_columns) = witness.split_at(2);
+        let (reads_logup_cols, reads_claimed_sum) =
+            gen_reads_logup_trace(self.log_size + 1, reads_columns, lookup_elements);
+        let (memory_logup_cols, memory_claimed_sum) =
+            gen_memory_logup_trace(self.log_size, memory_columns, lookup_elements);
+
+        (
+            MemoryStatement1 {
+                reads_claimed_sum,
+                memory_claimed_sum,
+            },
+            [reads_logup_cols, memory_logup_cols].concat(),
+        )
+    }
+}
@@ -0,0 +1,99 @@
+//! The read-only memory: one row per address, holding the value at that address and the number of
+//! times it is accessed.
+
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval, Relation, RelationEntry},
+    core::{
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        fields::{m31::M31, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        utils::bit_reverse_coset_to_circle_domain_order,
+    },
+};
+
+use super::{gen_logup_trace_in_pairs, MemoryElements};
+use crate::{
+    preprocessed::{PreprocessedColumn, Seq},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Yields `(address, value)` with multiplicity `mult`. The addresses come from a preprocessed
+/// sequence, so every address appears exactly once.
+pub struct MemoryEval {
+    pub log_size: u32,
+    pub memory_elements: MemoryElements,
+}
+
+impl FrameworkEval for MemoryEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let address = eval.get_preprocessed_column(Seq::new(self.log_size).id());
+        let value = eval.next_trace_mask();
+        let mult = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.memory_elements,
+            -E::EF::from(mult),
+            &[address, value],
+        ));
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the `value` and `mult` columns, in the bit-reversed order of the address column.
+/// Unassigned cells hold 0.
+pub fn gen_memory_trace(
+    log_size: u32,
+    memory: &[Option<M31>],
+    multiplicities: &[u32],
+) -> Vec<BaseColumn> {
+    let n_rows = 1 << log_size;
+    assert!(memory.len() <= n_rows && multiplicities.len() <= n_rows);
+
+    let mut value_col = BaseColumn::from_iter(
+        (0..n_rows).map(|address| memory.get(address).copied().flatten().unwrap_or_default()),
+    );
+    let mut mult_col = BaseColumn::from_iter(
+        (0..n_rows).map(|address| M31::from(multiplicities.get(address).copied().unwrap_or(0))),
+    );
+    bit_reverse_coset_to_circle_domain_order(value_col.as_mut_slice());
+    bit_reverse_coset_to_circle_domain_order(mult_col.as_mut_slice());
+
+    vec![value_col, mult_col]
+}
+
+pub fn gen_memory_interaction_trace(
+    log_size: u32,
+    trace: &[BaseColumn],
+    memory_elements: &MemoryElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [value_col, mult_col] = trace else {
+        panic!("unexpected memory trace layout");
+    };
+    let address_col = Seq::new(log_size).gen_column().values;
+
+    let entry = (0..1 << (log_size - LOG_N_LANES))
+        .map(|row| {
+            let denom: PackedSecureField =
+                memory_elements.combine(&[address_col.data[row], value_col.data[row]]);
+            (-PackedSecureField::from(mult_col.data[row]), denom)
+        })
+        .collect();
+
+    gen_logup_trace_in_pairs(log_size, vec![entry])
+}
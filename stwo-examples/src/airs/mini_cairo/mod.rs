@@ -0,0 +1,586 @@
+//! A mini Cairo VM: a register machine with `pc`, `ap` and `fp`, a write-once memory and four
+//! opcodes, proven with the component layout of the Cairo AIR.
+//!
+//! - [`memory`] holds the value of every address and yields `(address, value)` as many times as
+//!   the address is accessed.
+//! - [`range_check`] yields the `OFFSET_BITS`-bit values.
+//! - [`verify_instruction`] decodes each instruction reached by the execution from its encoding in
+//!   memory, and yields the decoded instruction.
+//! - [`opcode`] has one component per opcode, which consumes the decoded instruction and its
+//!   operands, and turns the registers before each step into the registers after it.
+//!
+//! The verifier closes the relations with the public data: it yields the initial registers,
+//! consumes the final ones, and consumes the program and its inputs from memory (see
+//! [`public_inputs`](crate::public_inputs)).
+
+pub mod memory;
+pub mod opcode;
+pub mod range_check;
+pub mod verify_instruction;
+pub mod vm;
+
+use std::collections::BTreeMap;
+
+use itertools::{chain, Itertools};
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{FrameworkComponent, PREPROCESSED_TRACE_IDX},
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{column::BaseColumn, SimdBackend},
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+    relation,
+};
+
+use self::{
+    memory::{gen_memory_interaction_trace, gen_memory_trace, MemoryEval},
+    opcode::{gen_opcode_interaction_trace, gen_opcode_trace, OpcodeEval},
+    range_check::{
+        gen_range_check_interaction_trace, gen_range_check_trace, RangeCheckEval,
+        RANGE_CHECK_LOG_SIZE,
+    },
+    verify_instruction::{
+        gen_verify_instruction_interaction_trace, gen_verify_instruction_trace, InstructionRow,
+        VerifyInstructionEval,
+    },
+    vm::{run, Execution, Instruction, Opcode, Program, Registers, VmError, OFFSET_BITS},
+};
+use super::{check_log_size, gen_logup_trace_in_pairs};
+use crate::{
+    gadgets::enabler::padded_log_size,
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::{registry::PreprocessedRegistry, RangeCheck, Seq},
+    public_inputs::PublicEntries,
+};
+
+/// Combines `(address, value)`.
+relation!(MemoryElements, 2);
+/// Combines `(pc, opcode, dst_off, op0_off, op1_off)`.
+relation!(InstructionElements, 5);
+/// Combines `(pc, ap, fp)`.
+relation!(RegistersElements, 3);
+/// Combines an `OFFSET_BITS`-bit value.
+relation!(OffsetRangeCheckElements, 1);
+
+#[derive(Clone)]
+pub struct MiniCairoElements {
+    pub memory: MemoryElements,
+    pub instruction: InstructionElements,
+    pub registers: RegistersElements,
+    pub range_check: OffsetRangeCheckElements,
+}
+
+impl MiniCairoElements {
+    pub fn draw(channel: &mut impl Channel) -> Self {
+        Self {
+            memory: MemoryElements::draw(channel),
+            instruction: InstructionElements::draw(channel),
+            registers: RegistersElements::draw(channel),
+            range_check: OffsetRangeCheckElements::draw(channel),
+        }
+    }
+
+    pub fn dummy() -> Self {
+        Self {
+            memory: MemoryElements::dummy(),
+            instruction: InstructionElements::dummy(),
+            registers: RegistersElements::dummy(),
+            range_check: OffsetRangeCheckElements::dummy(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MiniCairoStatement0 {
+    pub memory_log_size: u32,
+    pub verify_instruction_log_size: u32,
+    /// The log sizes of the opcode components, in the order of [`Opcode::ALL`].
+    pub opcode_log_sizes: [u32; 4],
+    pub initial_registers: Registers,
+    pub final_registers: Registers,
+    /// The program and its inputs, as `(address, value)` tuples consumed once from memory.
+    pub public_memory: PublicEntries,
+}
+
+impl Statement for MiniCairoStatement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.memory_log_size as u64);
+        channel.mix_u64(self.verify_instruction_log_size as u64);
+        for log_size in self.opcode_log_sizes {
+            channel.mix_u64(log_size as u64);
+        }
+        for registers in [self.initial_registers, self.final_registers] {
+            for value in registers.to_m31s() {
+                channel.mix_u64(value.0 as u64);
+            }
+        }
+        self.public_memory.mix_into(channel);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MiniCairoStatement1 {
+    pub memory_claimed_sum: SecureField,
+    pub range_check_claimed_sum: SecureField,
+    pub verify_instruction_claimed_sum: SecureField,
+    /// The claimed sums of the opcode components, in the order of [`Opcode::ALL`].
+    pub opcode_claimed_sums: [SecureField; 4],
+}
+
+impl Statement for MiniCairoStatement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[
+            self.memory_claimed_sum,
+            self.range_check_claimed_sum,
+            self.verify_instruction_claimed_sum,
+        ]);
+        channel.mix_felts(&self.opcode_claimed_sums);
+    }
+}
+
+pub fn preprocessed_registry(memory_log_size: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(Seq::new(memory_log_size));
+    registry.add(RangeCheck::new(OFFSET_BITS));
+    registry
+}
+
+pub type MemoryComponent = FrameworkComponent<MemoryEval>;
+pub type RangeCheckComponent = FrameworkComponent<RangeCheckEval>;
+pub type VerifyInstructionComponent = FrameworkComponent<VerifyInstructionEval>;
+pub type OpcodeComponent = FrameworkComponent<OpcodeEval>;
+
+pub struct MiniCairoComponents {
+    pub memory_component: MemoryComponent,
+    pub range_check_component: RangeCheckComponent,
+    pub verify_instruction_component: VerifyInstructionComponent,
+    /// In the order of [`Opcode::ALL`].
+    pub opcode_components: Vec<OpcodeComponent>,
+}
+
+impl MiniCairoComponents {
+    pub fn new(
+        statement0: &MiniCairoStatement0,
+        lookup_elements: &MiniCairoElements,
+        statement1: &MiniCairoStatement1,
+    ) -> Self {
+        let builder = &mut preprocessed_registry(statement0.memory_log_size).component_builder();
+
+        let memory_component = builder.build(
+            MemoryEval {
+                log_size: statement0.memory_log_size,
+                memory_elements: lookup_elements.memory.clone(),
+            },
+            statement1.memory_claimed_sum,
+        );
+        let range_check_component = builder.build(
+            RangeCheckEval {
+                range_check_elements: lookup_elements.range_check.clone(),
+            },
+            statement1.range_check_claimed_sum,
+        );
+        let verify_instruction_component = builder.build(
+            VerifyInstructionEval {
+                log_size: statement0.verify_instruction_log_size,
+                memory_elements: lookup_elements.memory.clone(),
+                instruction_elements: lookup_elements.instruction.clone(),
+                range_check_elements: lookup_elements.range_check.clone(),
+            },
+            statement1.verify_instruction_claimed_sum,
+        );
+        let opcode_components = Opcode::ALL
+            .into_iter()
+            .zip(statement0.opcode_log_sizes)
+            .zip(statement1.opcode_claimed_sums)
+            .map(|((opcode, log_size), claimed_sum)| {
+                builder.build(
+                    OpcodeEval {
+                        opcode,
+                        log_size,
+                        memory_elements: lookup_elements.memory.clone(),
+                        instruction_elements: lookup_elements.instruction.clone(),
+                        registers_elements: lookup_elements.registers.clone(),
+                    },
+                    claimed_sum,
+                )
+            })
+            .collect();
+
+        Self {
+            memory_component,
+            range_check_component,
+            verify_instruction_component,
+            opcode_components,
+        }
+    }
+}
+
+impl AirComponents for MiniCairoComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        chain!(
+            [
+                &self.memory_component as &dyn Component,
+                &self.range_check_component as &dyn Component,
+                &self.verify_instruction_component as &dyn Component,
+            ],
+            self.opcode_components
+                .iter()
+                .map(|component| component as &dyn Component)
+        )
+        .collect()
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        chain!(
+            [
+                &self.memory_component as &dyn ComponentProver<SimdBackend>,
+                &self.range_check_component as &dyn ComponentProver<SimdBackend>,
+                &self.verify_instruction_component as &dyn ComponentProver<SimdBackend>,
+            ],
+            self.opcode_components
+                .iter()
+                .map(|component| component as &dyn ComponentProver<SimdBackend>)
+        )
+        .collect()
+    }
+}
+
+/// Proves the execution of a program on the mini Cairo VM.
+pub struct MiniCairoAir {
+    execution: Execution,
+}
+
+impl MiniCairoAir {
+    /// Runs the program, which must execute at least one instruction.
+    pub fn new(program: &Program) -> Result<Self, VmError> {
+        let execution = run(program)?;
+        assert!(
+            !execution.steps.is_empty(),
+            "the program must execute at least one instruction"
+        );
+        Ok(Self { execution })
+    }
+
+    pub fn execution(&self) -> &Execution {
+        &self.execution
+    }
+}
+
+impl Air for MiniCairoAir {
+    const NAME: &'static str = "mini_cairo";
+    const COMPONENT_NAMES: &'static [&'static str] = &[
+        "memory",
+        "range_check",
+        "verify_instruction",
+        "add",
+        "mul",
+        "assert_eq",
+        "jmp",
+    ];
+
+    type Statement0 = MiniCairoStatement0;
+    type Statement1 = MiniCairoStatement1;
+    type LookupElements = MiniCairoElements;
+    type Components = MiniCairoComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        check_log_size("memory log size", statement0.memory_log_size)?;
+        check_log_size(
+            "verify instruction log size",
+            statement0.verify_instruction_log_size,
+        )?;
+        for log_size in statement0.opcode_log_sizes {
+            check_log_size("opcode log size", log_size)?;
+        }
+        statement0.public_memory.check_arity(2)
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        MiniCairoElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.memory_log_size)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let components = MiniCairoComponents::new(
+            statement0,
+            &MiniCairoElements::dummy(),
+            &MiniCairoStatement1 {
+                memory_claimed_sum: SecureField::one(),
+                range_check_claimed_sum: SecureField::one(),
+                verify_instruction_claimed_sum: SecureField::one(),
+                opcode_claimed_sums: [SecureField::one(); 4],
+            },
+        );
+        let mut log_sizes = TreeVec::concat_cols(
+            components
+                .components()
+                .into_iter()
+                .map(|component| component.trace_log_degree_bounds()),
+        );
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] =
+            preprocessed_registry(statement0.memory_log_size).log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.memory_claimed_sum
+            + statement1.range_check_claimed_sum
+            + statement1.verify_instruction_claimed_sum
+            + statement1
+                .opcode_claimed_sums
+                .into_iter()
+                .fold(SecureField::zero(), |sum, claimed_sum| sum + claimed_sum)
+    }
+
+    fn public_sum(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+    ) -> SecureField {
+        // The opcode components consume the initial registers and yield the final ones
+        let mut registers = PublicEntries::new();
+        registers.add(-M31::from(1), statement0.initial_registers.to_m31s());
+        registers.add(M31::from(1), statement0.final_registers.to_m31s());
+
+        statement0.public_memory.logup_sum(&lookup_elements.memory)
+            + registers.logup_sum(&lookup_elements.registers)
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        MiniCairoComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+/// The trace columns of each component.
+pub struct MiniCairoWitness {
+    pub statement0: MiniCairoStatement0,
+    pub memory: Vec<BaseColumn>,
+    pub range_check: Vec<BaseColumn>,
+    pub verify_instruction: Vec<BaseColumn>,
+    /// In the order of [`Opcode::ALL`].
+    pub opcodes: Vec<Vec<BaseColumn>>,
+}
+
+impl AirProver for MiniCairoAir {
+    type Witness = MiniCairoWitness;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let execution = &self.execution;
+        let memory_log_size = memory_log_size(execution);
+        let mut memory_mults = vec![0; 1 << memory_log_size];
+        let mut range_check_mults = vec![0; 1 << RANGE_CHECK_LOG_SIZE];
+
+        // The public memory is consumed once by the verifier
+        let mut public_memory = PublicEntries::new();
+        for &(address, value) in &execution.public_memory {
+            public_memory.add(M31::from(1), vec![M31::from(address), value]);
+            memory_mults[address as usize] += 1;
+        }
+
+        // Every step accesses its operands, and is counted in its decoded instruction
+        let mut instructions = BTreeMap::<u32, (Instruction, u32)>::new();
+        let mut opcode_steps = vec![vec![]; Opcode::ALL.len()];
+        for step in &execution.steps {
+            let opcode = step.instruction.opcode;
+            if opcode.uses_dst() {
+                memory_mults[step.dst_address() as usize] += 1;
+            }
+            memory_mults[step.op0_address() as usize] += 1;
+            if opcode.uses_op1() {
+                memory_mults[step.op1_address() as usize] += 1;
+            }
+            instructions
+                .entry(step.registers.pc)
+                .or_insert((step.instruction, 0))
+                .1 += 1;
+            opcode_steps[opcode as usize].push(*step);
+        }
+
+        // Every row of the verify instruction component, padding included, fetches an
+        // instruction and range checks its offsets
+        let instruction_rows = instructions
+            .into_iter()
+            .map(|(pc, (instruction, mult))| InstructionRow {
+                pc,
+                instruction,
+                mult,
+            })
+            .collect_vec();
+        let verify_instruction_log_size = padded_log_size(instruction_rows.len());
+        let n_padding_rows = (1 << verify_instruction_log_size) - instruction_rows.len();
+        for (i, row) in instruction_rows.iter().enumerate() {
+            let n_fetches = if i == 0 { 1 + n_padding_rows as u32 } else { 1 };
+            memory_mults[row.pc as usize] += n_fetches;
+            for offset in [
+                row.instruction.dst_off,
+                row.instruction.op0_off,
+                row.instruction.op1_off,
+            ] {
+                range_check_mults[offset as usize] += n_fetches;
+            }
+        }
+
+        let statement0 = MiniCairoStatement0 {
+            memory_log_size,
+            verify_instruction_log_size,
+            opcode_log_sizes: opcode_steps
+                .iter()
+                .map(|steps| padded_log_size(steps.len()))
+                .collect_vec()
+                .try_into()
+                .unwrap(),
+            initial_registers: execution.initial_registers,
+            final_registers: execution.final_registers,
+            public_memory,
+        };
+
+        let witness = MiniCairoWitness {
+            memory: gen_memory_trace(memory_log_size, &execution.memory, &memory_mults),
+            range_check: gen_range_check_trace(&range_check_mults),
+            verify_instruction: gen_verify_instruction_trace(
+                verify_instruction_log_size,
+                &instruction_rows,
+            ),
+            opcodes: statement0
+                .opcode_log_sizes
+                .iter()
+                .zip(&opcode_steps)
+                .map(|(&log_size, steps)| gen_opcode_trace(log_size, steps))
+                .collect(),
+            statement0: statement0.clone(),
+        };
+
+        let to_evaluations = |log_size: u32, columns: &[BaseColumn]| {
+            let domain = CanonicCoset::new(log_size).circle_domain();
+            columns
+                .iter()
+                .map(|col| CircleEvaluation::new(domain, col.clone()))
+                .collect_vec()
+        };
+        let trace = chain!(
+            to_evaluations(memory_log_size, &witness.memory),
+            to_evaluations(RANGE_CHECK_LOG_SIZE, &witness.range_check),
+            to_evaluations(verify_instruction_log_size, &witness.verify_instruction),
+            statement0
+                .opcode_log_sizes
+                .iter()
+                .zip(&witness.opcodes)
+                .flat_map(|(&log_size, columns)| to_evaluations(log_size, columns))
+        )
+        .collect();
+
+        (statement0, trace, witness)
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let statement0 = &witness.statement0;
+        let (memory_cols, memory_claimed_sum) = gen_memory_interaction_trace(
+            statement0.memory_log_size,
+            &witness.memory,
+            &lookup_elements.memory,
+        );
+        let (range_check_cols, range_check_claimed_sum) =
+            gen_range_check_interaction_trace(&witness.range_check, &lookup_elements.range_check);
+        let (verify_instruction_cols, verify_instruction_claimed_sum) =
+            gen_verify_instruction_interaction_trace(
+                statement0.verify_instruction_log_size,
+                &witness.verify_instruction,
+                &lookup_elements.memory,
+                &lookup_elements.instruction,
+                &lookup_elements.range_check,
+            );
+        let (opcode_cols, opcode_claimed_sums): (Vec<_>, Vec<_>) = Opcode::ALL
+            .into_iter()
+            .zip(statement0.opcode_log_sizes)
+            .zip(&witness.opcodes)
+            .map(|((opcode, log_size), trace)| {
+                gen_opcode_interaction_trace(
+                    opcode,
+                    log_size,
+                    trace,
+                    &lookup_elements.memory,
+                    &lookup_elements.instruction,
+                    &lookup_elements.registers,
+                )
+            })
+            .unzip();
+
+        (
+            MiniCairoStatement1 {
+                memory_claimed_sum,
+                range_check_claimed_sum,
+                verify_instruction_claimed_sum,
+                opcode_claimed_sums: opcode_claimed_sums.try_into().unwrap(),
+            },
+            chain!(
+                memory_cols,
+                range_check_cols,
+                verify_instruction_cols,
+                opcode_cols.into_iter().flatten()
+            )
+            .collect(),
+        )
+    }
+}
+
+fn memory_log_size(execution: &Execution) -> u32 {
+    padded_log_size(execution.memory.len())
+}
+
+/// The largest `n` for which the operands of [`fibonacci_program`] fit in the offsets.
+pub const MAX_FIBONACCI_STEPS: usize = (1 << OFFSET_BITS) - 3;
+
+/// A program running `n` steps of the Fibonacci sequence from the inputs `1, 1`, and then using
+/// the other opcodes once: it squares the first input, copies the last Fibonacci number, and
+/// jumps to the end of the code through an input holding its address.
+pub fn fibonacci_program(n: usize) -> Program {
+    assert!(
+        n <= MAX_FIBONACCI_STEPS,
+        "{n} steps do not fit in the offsets"
+    );
+    let n = n as u32;
+    // Memory from `fp`: the halting address, the two first Fibonacci numbers, then the results
+    let mut code = (0..n)
+        .map(|k| Instruction::new(Opcode::Add, 0, k + 1, k + 2))
+        .collect_vec();
+    code.push(Instruction::new(Opcode::Mul, 0, 1, 1));
+    code.push(Instruction::new(Opcode::AssertEq, 0, n + 2, 0));
+    code.push(Instruction::new(Opcode::Jmp, 0, 0, 0));
+
+    let code_len = code.len();
+    Program {
+        code,
+        inputs: vec![M31::from(code_len), M31::from(1), M31::from(1)],
+        max_steps: code_len,
+    }
+}
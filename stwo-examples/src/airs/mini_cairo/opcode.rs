@@ -0,0 +1,219 @@
+//! The opcode components: one component per opcode, with one row per executed instruction of
+//! that opcode. Each row consumes the registers before the step and yields the registers after
+//! it, so the rows of all the opcode components chain into the execution.
+
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval, Relation},
+    core::{
+        backend::simd::{
+            column::BaseColumn,
+            m31::{PackedM31, LOG_N_LANES},
+            qm31::PackedSecureField,
+            SimdBackend,
+        },
+        fields::{m31::M31, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+
+use super::{
+    gen_logup_trace_in_pairs,
+    vm::{Opcode, Step},
+    InstructionElements, MemoryElements, RegistersElements,
+};
+use crate::{gadgets::enabler::Enabler, LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR};
+
+/// Number of columns of an opcode component.
+pub const N_OPCODE_COLUMNS: usize = 10;
+
+/// Executes `opcode` on the rows where `enabler` is set. The padding rows have `enabler = 0`, take
+/// no part in the lookups and are not constrained by the opcode.
+pub struct OpcodeEval {
+    pub opcode: Opcode,
+    pub log_size: u32,
+    pub memory_elements: MemoryElements,
+    pub instruction_elements: InstructionElements,
+    pub registers_elements: RegistersElements,
+}
+
+impl FrameworkEval for OpcodeEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let enabled = Enabler::boolean().evaluate(&mut eval);
+        let pc = eval.next_trace_mask();
+        let ap = eval.next_trace_mask();
+        let fp = eval.next_trace_mask();
+        let dst_off = eval.next_trace_mask();
+        let op0_off = eval.next_trace_mask();
+        let op1_off = eval.next_trace_mask();
+        let dst = eval.next_trace_mask();
+        let op0 = eval.next_trace_mask();
+        let op1 = eval.next_trace_mask();
+
+        // The padding rows are not executed, so their operands need not satisfy the opcode
+        let enabler = enabled.enabler.clone();
+        match self.opcode {
+            Opcode::Add => {
+                eval.add_constraint(enabler * (dst.clone() - (op0.clone() + op1.clone())))
+            }
+            Opcode::Mul => eval.add_constraint(enabler * (dst.clone() - op0.clone() * op1.clone())),
+            Opcode::AssertEq => eval.add_constraint(enabler * (dst.clone() - op0.clone())),
+            Opcode::Jmp => {}
+        }
+
+        enabled.add_to_relation(
+            &mut eval,
+            &self.instruction_elements,
+            E::EF::one(),
+            &[
+                pc.clone(),
+                E::F::from(M31::from(self.opcode as u32)),
+                dst_off.clone(),
+                op0_off.clone(),
+                op1_off.clone(),
+            ],
+        );
+        if self.opcode.uses_dst() {
+            enabled.add_to_relation(
+                &mut eval,
+                &self.memory_elements,
+                E::EF::one(),
+                &[ap.clone() + dst_off, dst],
+            );
+        }
+        enabled.add_to_relation(
+            &mut eval,
+            &self.memory_elements,
+            E::EF::one(),
+            &[fp.clone() + op0_off, op0.clone()],
+        );
+        if self.opcode.uses_op1() {
+            enabled.add_to_relation(
+                &mut eval,
+                &self.memory_elements,
+                E::EF::one(),
+                &[fp.clone() + op1_off, op1],
+            );
+        }
+
+        // Consume the registers before the step and yield the registers after it
+        enabled.add_to_relation(
+            &mut eval,
+            &self.registers_elements,
+            E::EF::one(),
+            &[pc.clone(), ap.clone(), fp.clone()],
+        );
+        let next_registers = match self.opcode {
+            Opcode::Jmp => [op0, ap, fp],
+            _ => [pc + E::F::one(), ap + E::F::one(), fp],
+        };
+        enabled.add_to_relation(
+            &mut eval,
+            &self.registers_elements,
+            -E::EF::one(),
+            &next_registers,
+        );
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the columns `enabler, pc, ap, fp, dst_off, op0_off, op1_off, dst, op0, op1` of the
+/// given steps, padded with zero rows.
+pub fn gen_opcode_trace(log_size: u32, steps: &[Step]) -> Vec<BaseColumn> {
+    let mut columns = vec![Vec::with_capacity(steps.len()); N_OPCODE_COLUMNS - 1];
+    for step in steps {
+        let values = [
+            M31::from(step.registers.pc),
+            M31::from(step.registers.ap),
+            M31::from(step.registers.fp),
+            M31::from(step.instruction.dst_off),
+            M31::from(step.instruction.op0_off),
+            M31::from(step.instruction.op1_off),
+            step.dst,
+            step.op0,
+            step.op1,
+        ];
+        for (column, value) in columns.iter_mut().zip(values) {
+            column.push(value);
+        }
+    }
+    Enabler::boolean().gen_trace(log_size, columns)
+}
+
+pub fn gen_opcode_interaction_trace(
+    opcode: Opcode,
+    log_size: u32,
+    trace: &[BaseColumn],
+    memory_elements: &MemoryElements,
+    instruction_elements: &InstructionElements,
+    registers_elements: &RegistersElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [enabler, pc, ap, fp, dst_off, op0_off, op1_off, dst, op0, op1] = trace else {
+        panic!("unexpected opcode trace layout");
+    };
+
+    // The entries in the order of `OpcodeEval::evaluate`
+    let n_packed_rows = 1 << (log_size - LOG_N_LANES);
+    let one = PackedM31::broadcast(M31::from(1));
+    let mut entries = vec![];
+    for row in 0..n_packed_rows {
+        let numerator = PackedSecureField::from(enabler.data[row]);
+        let next_registers = match opcode {
+            Opcode::Jmp => [op0.data[row], ap.data[row], fp.data[row]],
+            _ => [pc.data[row] + one, ap.data[row] + one, fp.data[row]],
+        };
+
+        let mut row_entries = vec![(
+            numerator,
+            instruction_elements.combine(&[
+                pc.data[row],
+                PackedM31::broadcast(M31::from(opcode as u32)),
+                dst_off.data[row],
+                op0_off.data[row],
+                op1_off.data[row],
+            ]),
+        )];
+        if opcode.uses_dst() {
+            row_entries.push((
+                numerator,
+                memory_elements.combine(&[ap.data[row] + dst_off.data[row], dst.data[row]]),
+            ));
+        }
+        row_entries.push((
+            numerator,
+            memory_elements.combine(&[fp.data[row] + op0_off.data[row], op0.data[row]]),
+        ));
+        if opcode.uses_op1() {
+            row_entries.push((
+                numerator,
+                memory_elements.combine(&[fp.data[row] + op1_off.data[row], op1.data[row]]),
+            ));
+        }
+        row_entries.push((
+            numerator,
+            registers_elements.combine(&[pc.data[row], ap.data[row], fp.data[row]]),
+        ));
+        row_entries.push((-numerator, registers_elements.combine(&next_registers)));
+
+        entries.resize(row_entries.len(), Vec::with_capacity(n_packed_rows));
+        for (entry, fraction) in entries.iter_mut().zip(row_entries) {
+            entry.push(fraction);
+        }
+    }
+
+    gen_logup_trace_in_pairs(log_size, entries)
+}
@@ -0,0 +1,82 @@
+//! The range check of the instruction offsets against a preprocessed table of all the
+//! `OFFSET_BITS`-bit values.
+
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval, Relation, RelationEntry},
+    core::{
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        fields::{m31::M31, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        utils::bit_reverse_coset_to_circle_domain_order,
+    },
+};
+
+use super::{gen_logup_trace_in_pairs, vm::OFFSET_BITS, OffsetRangeCheckElements};
+use crate::{
+    preprocessed::{PreprocessedColumn, RangeCheck},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+pub const RANGE_CHECK_LOG_SIZE: u32 = OFFSET_BITS;
+
+/// Yields every `OFFSET_BITS`-bit value with multiplicity `mult`.
+pub struct RangeCheckEval {
+    pub range_check_elements: OffsetRangeCheckElements,
+}
+
+impl FrameworkEval for RangeCheckEval {
+    fn log_size(&self) -> u32 {
+        RANGE_CHECK_LOG_SIZE
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        RANGE_CHECK_LOG_SIZE + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let value = eval.get_preprocessed_column(RangeCheck::new(OFFSET_BITS).id());
+        let mult = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.range_check_elements,
+            -E::EF::from(mult),
+            &[value],
+        ));
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the `mult` column, in the bit-reversed order of the range-check table.
+pub fn gen_range_check_trace(multiplicities: &[u32]) -> Vec<BaseColumn> {
+    assert_eq!(multiplicities.len(), 1 << RANGE_CHECK_LOG_SIZE);
+    let mut mult_col = BaseColumn::from_iter(multiplicities.iter().map(|&mult| M31::from(mult)));
+    bit_reverse_coset_to_circle_domain_order(mult_col.as_mut_slice());
+    vec![mult_col]
+}
+
+pub fn gen_range_check_interaction_trace(
+    trace: &[BaseColumn],
+    range_check_elements: &OffsetRangeCheckElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [mult_col] = trace else {
+        panic!("unexpected range-check trace layout");
+    };
+    let value_col = RangeCheck::new(OFFSET_BITS).gen_column().values;
+
+    let entry = (0..1 << (RANGE_CHECK_LOG_SIZE - LOG_N_LANES))
+        .map(|row| {
+            let denom: PackedSecureField = range_check_elements.combine(&[value_col.data[row]]);
+            (-PackedSecureField::from(mult_col.data[row]), denom)
+        })
+        .collect();
+
+    gen_logup_trace_in_pairs(RANGE_CHECK_LOG_SIZE, vec![entry])
+}
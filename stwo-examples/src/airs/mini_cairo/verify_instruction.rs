@@ -0,0 +1,168 @@
+//! Decodes the instructions of the program: one row per distinct `pc` reached by the execution,
+//! which yields the decoded instruction as many times as it is executed.
+
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval, Relation, RelationEntry},
+    core::{
+        backend::simd::{
+            column::BaseColumn,
+            m31::{PackedM31, LOG_N_LANES},
+            qm31::PackedSecureField,
+            SimdBackend,
+        },
+        fields::{m31::M31, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+
+use super::{
+    gen_logup_trace_in_pairs,
+    vm::{Instruction, OFFSET_BITS},
+    InstructionElements, MemoryElements, OffsetRangeCheckElements,
+};
+use crate::LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR;
+
+/// A row of the component: an instruction, its address and the number of times it is executed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InstructionRow {
+    pub pc: u32,
+    pub instruction: Instruction,
+    pub mult: u32,
+}
+
+/// Consumes the encoded instruction from memory and its offsets from the range check, and yields
+/// `(pc, opcode, dst_off, op0_off, op1_off)` with multiplicity `mult`.
+pub struct VerifyInstructionEval {
+    pub log_size: u32,
+    pub memory_elements: MemoryElements,
+    pub instruction_elements: InstructionElements,
+    pub range_check_elements: OffsetRangeCheckElements,
+}
+
+impl FrameworkEval for VerifyInstructionEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let pc = eval.next_trace_mask();
+        let opcode_bit_0 = eval.next_trace_mask();
+        let opcode_bit_1 = eval.next_trace_mask();
+        let dst_off = eval.next_trace_mask();
+        let op0_off = eval.next_trace_mask();
+        let op1_off = eval.next_trace_mask();
+        let mult = eval.next_trace_mask();
+
+        let one = E::F::one();
+        eval.add_constraint(opcode_bit_0.clone() * (opcode_bit_0.clone() - one.clone()));
+        eval.add_constraint(opcode_bit_1.clone() * (opcode_bit_1.clone() - one));
+
+        // The offsets fit in `OFFSET_BITS` bits and the opcode in 2 bits, so the packing is
+        // injective
+        let opcode = opcode_bit_0 + opcode_bit_1 * M31::from(2);
+        let encoded = opcode.clone()
+            + dst_off.clone() * M31::from(1 << 2)
+            + op0_off.clone() * M31::from(1 << (2 + OFFSET_BITS))
+            + op1_off.clone() * M31::from(1 << (2 + 2 * OFFSET_BITS));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.memory_elements,
+            E::EF::one(),
+            &[pc.clone(), encoded],
+        ));
+        for offset in [&dst_off, &op0_off, &op1_off] {
+            eval.add_to_relation(RelationEntry::new(
+                &self.range_check_elements,
+                E::EF::one(),
+                &[offset.clone()],
+            ));
+        }
+        eval.add_to_relation(RelationEntry::new(
+            &self.instruction_elements,
+            -E::EF::from(mult),
+            &[pc, opcode, dst_off, op0_off, op1_off],
+        ));
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the columns `pc, opcode_bit_0, opcode_bit_1, dst_off, op0_off, op1_off, mult`. The
+/// padding rows repeat the first row with a multiplicity of 0, so `rows` must not be empty.
+pub fn gen_verify_instruction_trace(log_size: u32, rows: &[InstructionRow]) -> Vec<BaseColumn> {
+    assert!(!rows.is_empty() && rows.len() <= 1 << log_size);
+    let padding = InstructionRow { mult: 0, ..rows[0] };
+    let rows = rows
+        .iter()
+        .chain(std::iter::repeat(&padding))
+        .take(1 << log_size);
+
+    let mut columns = vec![Vec::with_capacity(1 << log_size); 7];
+    for row in rows {
+        let opcode = row.instruction.opcode as u32;
+        let values = [
+            row.pc,
+            opcode & 1,
+            opcode >> 1,
+            row.instruction.dst_off,
+            row.instruction.op0_off,
+            row.instruction.op1_off,
+            row.mult,
+        ];
+        for (column, value) in columns.iter_mut().zip(values) {
+            column.push(M31::from(value));
+        }
+    }
+    columns.into_iter().map(BaseColumn::from_iter).collect()
+}
+
+pub fn gen_verify_instruction_interaction_trace(
+    log_size: u32,
+    trace: &[BaseColumn],
+    memory_elements: &MemoryElements,
+    instruction_elements: &InstructionElements,
+    range_check_elements: &OffsetRangeCheckElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [pc, bit_0, bit_1, dst_off, op0_off, op1_off, mult] = trace else {
+        panic!("unexpected verify instruction trace layout");
+    };
+
+    let n_packed_rows = 1 << (log_size - LOG_N_LANES);
+    let one = PackedSecureField::one();
+    let broadcast = |value: u32| PackedM31::broadcast(M31::from(value));
+    let mut entries = vec![Vec::with_capacity(n_packed_rows); 5];
+    for row in 0..n_packed_rows {
+        let opcode = bit_0.data[row] + bit_1.data[row] * broadcast(2);
+        let encoded = opcode
+            + dst_off.data[row] * broadcast(1 << 2)
+            + op0_off.data[row] * broadcast(1 << (2 + OFFSET_BITS))
+            + op1_off.data[row] * broadcast(1 << (2 + 2 * OFFSET_BITS));
+
+        entries[0].push((one, memory_elements.combine(&[pc.data[row], encoded])));
+        for (entry, offset) in entries[1..4].iter_mut().zip([dst_off, op0_off, op1_off]) {
+            entry.push((one, range_check_elements.combine(&[offset.data[row]])));
+        }
+        entries[4].push((
+            -PackedSecureField::from(mult.data[row]),
+            instruction_elements.combine(&[
+                pc.data[row],
+                opcode,
+                dst_off.data[row],
+                op0_off.data[row],
+                op1_off.data[row],
+            ]),
+        ));
+    }
+
+    gen_logup_trace_in_pairs(log_size, entries)
+}
@@ -0,0 +1,275 @@
+//! The interpreter of the mini Cairo VM, which produces the execution trace proven by the AIR.
+
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::fields::m31::M31;
+use thiserror::Error;
+
+/// Number of bits of each instruction offset.
+pub const OFFSET_BITS: u32 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Opcode {
+    /// `[ap + dst_off] = [fp + op0_off] + [fp + op1_off]; ap += 1`
+    Add = 0,
+    /// `[ap + dst_off] = [fp + op0_off] * [fp + op1_off]; ap += 1`
+    Mul = 1,
+    /// `[ap + dst_off] = [fp + op0_off]; ap += 1`
+    AssertEq = 2,
+    /// `pc = [fp + op0_off]`
+    Jmp = 3,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 4] = [Opcode::Add, Opcode::Mul, Opcode::AssertEq, Opcode::Jmp];
+
+    pub fn uses_dst(&self) -> bool {
+        !matches!(self, Self::Jmp)
+    }
+
+    pub fn uses_op1(&self) -> bool {
+        matches!(self, Self::Add | Self::Mul)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub dst_off: u32,
+    pub op0_off: u32,
+    pub op1_off: u32,
+}
+
+impl Instruction {
+    pub fn new(opcode: Opcode, dst_off: u32, op0_off: u32, op1_off: u32) -> Self {
+        for offset in [dst_off, op0_off, op1_off] {
+            assert!(
+                offset >> OFFSET_BITS == 0,
+                "offset {offset} does not fit in {OFFSET_BITS} bits"
+            );
+        }
+        Self {
+            opcode,
+            dst_off,
+            op0_off,
+            op1_off,
+        }
+    }
+
+    /// Packs the instruction as `opcode + 2^2 * dst_off + 2^10 * op0_off + 2^18 * op1_off`.
+    pub fn encode(&self) -> u32 {
+        self.opcode as u32
+            + (self.dst_off << 2)
+            + (self.op0_off << (2 + OFFSET_BITS))
+            + (self.op1_off << (2 + 2 * OFFSET_BITS))
+    }
+
+    pub fn decode(word: u32) -> Option<Self> {
+        if word >> (2 + 3 * OFFSET_BITS) != 0 {
+            return None;
+        }
+        let mask = (1 << OFFSET_BITS) - 1;
+        Some(Self {
+            opcode: Opcode::ALL[(word & 3) as usize],
+            dst_off: (word >> 2) & mask,
+            op0_off: (word >> (2 + OFFSET_BITS)) & mask,
+            op1_off: (word >> (2 + 2 * OFFSET_BITS)) & mask,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registers {
+    pub pc: u32,
+    pub ap: u32,
+    pub fp: u32,
+}
+
+impl Registers {
+    pub fn to_m31s(&self) -> Vec<M31> {
+        vec![M31::from(self.pc), M31::from(self.ap), M31::from(self.fp)]
+    }
+}
+
+/// A program and its inputs. The code is loaded at address 0 and the inputs right after it, where
+/// `fp` points; `ap` starts after the inputs. The program halts when `pc` reaches the end of the
+/// code.
+#[derive(Clone, Debug, Default)]
+pub struct Program {
+    pub code: Vec<Instruction>,
+    pub inputs: Vec<M31>,
+    pub max_steps: usize,
+}
+
+/// An executed instruction, with the registers before the step and the operand values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub registers: Registers,
+    pub instruction: Instruction,
+    /// 0 if the opcode does not use it.
+    pub dst: M31,
+    pub op0: M31,
+    /// 0 if the opcode does not use it.
+    pub op1: M31,
+}
+
+impl Step {
+    pub fn dst_address(&self) -> u32 {
+        self.registers.ap + self.instruction.dst_off
+    }
+
+    pub fn op0_address(&self) -> u32 {
+        self.registers.fp + self.instruction.op0_off
+    }
+
+    pub fn op1_address(&self) -> u32 {
+        self.registers.fp + self.instruction.op1_off
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Execution {
+    pub steps: Vec<Step>,
+    /// The memory at the end of the execution. Cells that were never assigned are `None`.
+    pub memory: Vec<Option<M31>>,
+    /// The code and the inputs, as `(address, value)` pairs.
+    pub public_memory: Vec<(u32, M31)>,
+    pub initial_registers: Registers,
+    pub final_registers: Registers,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VmError {
+    #[error("pc {0} is outside of the code")]
+    PcOutOfCode(u32),
+    #[error("memory cell {address} does not hold an instruction")]
+    InvalidInstruction { address: u32 },
+    #[error("memory cell {0} is read before it is assigned")]
+    UninitializedMemory(u32),
+    #[error(
+        "assertion failed at pc {pc}: memory cell {address} holds {found}, expected {expected}"
+    )]
+    AssertionFailed {
+        pc: u32,
+        address: u32,
+        expected: M31,
+        found: M31,
+    },
+    #[error("the program did not halt within {0} steps")]
+    StepLimit(usize),
+}
+
+struct Memory(Vec<Option<M31>>);
+
+impl Memory {
+    fn read(&self, address: u32) -> Result<M31, VmError> {
+        self.0
+            .get(address as usize)
+            .copied()
+            .flatten()
+            .ok_or(VmError::UninitializedMemory(address))
+    }
+
+    /// Assigns `value` to the cell if it is empty, or checks that it already holds `value`, as
+    /// memory is write-once.
+    fn assert_eq(&mut self, pc: u32, address: u32, value: M31) -> Result<(), VmError> {
+        let index = address as usize;
+        if index >= self.0.len() {
+            self.0.resize(index + 1, None);
+        }
+        match self.0[index] {
+            None => {
+                self.0[index] = Some(value);
+                Ok(())
+            }
+            Some(found) if found == value => Ok(()),
+            Some(found) => Err(VmError::AssertionFailed {
+                pc,
+                address,
+                expected: value,
+                found,
+            }),
+        }
+    }
+}
+
+pub fn run(program: &Program) -> Result<Execution, VmError> {
+    let code_len = program.code.len() as u32;
+    let public_memory = program
+        .code
+        .iter()
+        .map(|instruction| M31::from(instruction.encode()))
+        .chain(program.inputs.iter().copied())
+        .enumerate()
+        .map(|(address, value)| (address as u32, value))
+        .collect::<Vec<_>>();
+    let mut memory = Memory(
+        public_memory
+            .iter()
+            .map(|(_, value)| Some(*value))
+            .collect(),
+    );
+
+    let initial_registers = Registers {
+        pc: 0,
+        ap: code_len + program.inputs.len() as u32,
+        fp: code_len,
+    };
+    let mut registers = initial_registers;
+    let mut steps = vec![];
+    while registers.pc != code_len {
+        if steps.len() == program.max_steps {
+            return Err(VmError::StepLimit(program.max_steps));
+        }
+        if registers.pc > code_len {
+            return Err(VmError::PcOutOfCode(registers.pc));
+        }
+
+        // Fetch and decode
+        let word = memory.read(registers.pc)?;
+        let instruction = Instruction::decode(word.0).ok_or(VmError::InvalidInstruction {
+            address: registers.pc,
+        })?;
+        let mut step = Step {
+            registers,
+            instruction,
+            dst: M31::from(0),
+            op0: M31::from(0),
+            op1: M31::from(0),
+        };
+
+        // Execute
+        step.op0 = memory.read(step.op0_address())?;
+        if instruction.opcode.uses_op1() {
+            step.op1 = memory.read(step.op1_address())?;
+        }
+        let pc = registers.pc;
+        registers = match instruction.opcode {
+            Opcode::Add | Opcode::Mul | Opcode::AssertEq => {
+                step.dst = match instruction.opcode {
+                    Opcode::Add => step.op0 + step.op1,
+                    Opcode::Mul => step.op0 * step.op1,
+                    _ => step.op0,
+                };
+                memory.assert_eq(pc, step.dst_address(), step.dst)?;
+                Registers {
+                    pc: pc + 1,
+                    ap: registers.ap + 1,
+                    fp: registers.fp,
+                }
+            }
+            Opcode::Jmp => Registers {
+                pc: step.op0.0,
+                ..registers
+            },
+        };
+        steps.push(step);
+    }
+
+    Ok(Execution {
+        steps,
+        memory: memory.0,
+        public_memory,
+        initial_registers,
+        final_registers: registers,
+    })
+}
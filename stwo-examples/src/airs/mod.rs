@@ -0,0 +1,146 @@
+//! Library versions of the AIRs built in the book's examples, wired into the
+//! [`pipeline`](crate::pipeline) so that they can be proven, serialized and verified separately.
+
+pub mod bitwise;
+pub mod catalog;
+pub mod components;
+pub mod dynamic_lookups;
+pub mod fibonacci;
+pub mod is_zero;
+pub mod keccak;
+pub mod local_row_constraints;
+pub mod local_row_constraints_fails;
+pub mod memory;
+pub mod mini_cairo;
+pub mod preprocessed_trace;
+pub mod public_permutation;
+pub mod range_check;
+pub mod sha256;
+pub mod simple;
+pub mod static_lookups;
+
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::logup::LogupTraceGenerator,
+    core::{
+        backend::simd::{
+            m31::{LOG_N_LANES, N_LANES},
+            qm31::PackedSecureField,
+            SimdBackend,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        utils::bit_reverse_coset_to_circle_domain_order,
+    },
+};
+
+use crate::pipeline::{Statement, MAX_COLUMN_LOG_SIZE};
+
+/// Checks that the log size `name` of a statement is one that the SIMD backend supports and the
+/// verifier accepts.
+pub fn check_log_size(name: &str, log_size: u32) -> Result<(), String> {
+    if !(LOG_N_LANES..=MAX_COLUMN_LOG_SIZE).contains(&log_size) {
+        return Err(format!(
+            "{name} must be between {LOG_N_LANES} and {MAX_COLUMN_LOG_SIZE}, got {log_size}"
+        ));
+    }
+    Ok(())
+}
+
+/// Statement of an AIR whose only public parameter is the log size of its trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogSizeStatement {
+    pub log_size: u32,
+}
+
+impl LogSizeStatement {
+    pub fn check(&self) -> Result<(), String> {
+        check_log_size("log size", self.log_size)
+    }
+}
+
+impl Statement for LogSizeStatement {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_size as u64);
+    }
+}
+
+/// Statement of a single-component AIR holding the LogUp claimed sum of the component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimedSumStatement {
+    pub claimed_sum: SecureField,
+}
+
+impl Statement for ClaimedSumStatement {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[self.claimed_sum]);
+    }
+}
+
+/// Generates the interaction trace of a component whose relation entries are batched as in
+/// `finalize_logup_in_pairs`. `entries[i][row]` is the `(numerator, denominator)` of the `i`-th
+/// entry at a packed row.
+pub fn gen_logup_trace_in_pairs(
+    log_size: u32,
+    entries: Vec<Vec<(PackedSecureField, PackedSecureField)>>,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    for pair in entries.chunks(2) {
+        let mut col_gen = logup_gen.new_col();
+        for row in 0..(1 << (log_size - LOG_N_LANES)) {
+            match pair {
+                // n1 / d1 + n2 / d2 = (n1 * d2 + n2 * d1) / (d1 * d2)
+                [first, second] => {
+                    let ((n1, d1), (n2, d2)) = (first[row], second[row]);
+                    col_gen.write_frac(row, n1 * d2 + n2 * d1, d1 * d2);
+                }
+                [single] => col_gen.write_frac(row, single[row].0, single[row].1),
+                _ => unreachable!(),
+            }
+        }
+        col_gen.finalize_col();
+    }
+
+    logup_gen.finalize_last()
+}
+
+/// Like [`gen_logup_trace_in_pairs`], but from the `(numerator, denominator)` of the entries of
+/// each row, in row order. The rows are put in circle domain order, like the trace of a component
+/// that compares rows. The rows after `rows.len()` are padding rows with no entries.
+pub fn gen_logup_trace_from_rows(
+    log_size: u32,
+    rows: &[Vec<(SecureField, SecureField)>],
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let n_entries = rows.first().map_or(0, Vec::len);
+    let entries = (0..n_entries)
+        .map(|i| {
+            // The padding rows add 0 / 1
+            let mut entry = vec![(SecureField::zero(), SecureField::one()); 1 << log_size];
+            for (fraction, row) in entry.iter_mut().zip(rows) {
+                *fraction = row[i];
+            }
+            bit_reverse_coset_to_circle_domain_order(&mut entry);
+            entry
+                .chunks(N_LANES)
+                .map(|chunk| {
+                    let (numerators, denominators): (Vec<_>, Vec<_>) =
+                        chunk.iter().copied().unzip();
+                    (
+                        PackedSecureField::from_array(numerators.try_into().unwrap()),
+                        PackedSecureField::from_array(denominators.try_into().unwrap()),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    gen_logup_trace_in_pairs(log_size, entries)
+}
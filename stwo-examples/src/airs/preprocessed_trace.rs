@@ -0,0 +1,201 @@
+//! The AIR from "Preprocessed Trace": an `IsFirst` preprocessed column selects one of two
+//! constraints on the columns `col_1, col_2, col_3`.
+
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, EvalAtRow, FrameworkComponent, FrameworkEval,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::{
+            simd::{column::BaseColumn, SimdBackend},
+            Column,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+};
+
+use super::LogSizeStatement;
+use crate::{
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::{registry::PreprocessedRegistry, IsFirst, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+// ANCHOR: preprocessed_registry
+pub fn preprocessed_registry(log_size: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(log_size));
+    registry
+}
+// ANCHOR_END: preprocessed_registry
+
+// ANCHOR: preprocessed_trace_eval
+pub struct PreprocessedTraceEval {
+    pub is_first_id: PreProcessedColumnId,
+    pub log_size: u32,
+}
+
+impl FrameworkEval for PreprocessedTraceEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let is_first = eval.get_preprocessed_column(self.is_first_id.clone());
+
+        let col_1 = eval.next_trace_mask();
+        let col_2 = eval.next_trace_mask();
+        let col_3 = eval.next_trace_mask();
+
+        // If is_first is 1, then the constraint is col_1 * col_2 - col_3 = 0
+        // If is_first is 0, then the constraint is col_1 * col_2 + col_1 - col_3 = 0
+        eval.add_constraint(
+            (col_1.clone() * col_2.clone() - col_3.clone()) * is_first.clone()
+                + (col_1.clone() * col_2.clone() + col_1.clone() - col_3.clone())
+                    * (E::F::from(M31::from(1)) - is_first.clone()),
+        );
+
+        eval
+    }
+}
+// ANCHOR_END: preprocessed_trace_eval
+
+pub type PreprocessedTraceComponent = FrameworkComponent<PreprocessedTraceEval>;
+
+pub struct PreprocessedTraceComponents {
+    pub component: PreprocessedTraceComponent,
+}
+
+impl PreprocessedTraceComponents {
+    // ANCHOR: create_component
+    pub fn new(statement0: &LogSizeStatement) -> Self {
+        Self {
+            component: preprocessed_registry(statement0.log_size)
+                .component_builder()
+                .build(
+                    PreprocessedTraceEval {
+                        is_first_id: IsFirst::new(statement0.log_size).id(),
+                        log_size: statement0.log_size,
+                    },
+                    SecureField::zero(),
+                ),
+        }
+    }
+    // ANCHOR_END: create_component
+}
+
+impl AirComponents for PreprocessedTraceComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct PreprocessedTraceAir {
+    pub log_size: u32,
+}
+
+impl Air for PreprocessedTraceAir {
+    const NAME: &'static str = "preprocessed_trace";
+    const COMPONENT_NAMES: &'static [&'static str] = &["preprocessed_trace"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ();
+    type LookupElements = ();
+    type Components = PreprocessedTraceComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(_channel: &mut impl Channel) -> Self::LookupElements {}
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.log_size)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        PreprocessedTraceComponents::new(statement0)
+            .component
+            .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(_statement1: &Self::Statement1) -> SecureField {
+        SecureField::zero()
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        _lookup_elements: &Self::LookupElements,
+        _statement1: &Self::Statement1,
+    ) -> Self::Components {
+        PreprocessedTraceComponents::new(statement0)
+    }
+}
+
+impl AirProver for PreprocessedTraceAir {
+    type Witness = ();
+
+    // ANCHOR: gen_trace
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let log_size = self.log_size;
+
+        // Create the table. The first row stays first in bit-reversed circle domain order, where
+        // `IsFirst` is committed, and the constraint on the other rows does not depend on their
+        // order.
+        let mut col_1 = BaseColumn::zeros(1 << log_size);
+        col_1.set(0, M31::from(1));
+        col_1.set(1, M31::from(7));
+
+        let mut col_2 = BaseColumn::zeros(1 << log_size);
+        col_2.set(0, M31::from(5));
+        col_2.set(1, M31::from(11));
+
+        let mut col_3 = BaseColumn::zeros(1 << log_size);
+        col_3.set(0, col_1.at(0) * col_2.at(0));
+        col_3.set(1, col_1.at(1) * col_2.at(1) + col_1.at(1));
+
+        // Convert table to trace polynomials
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let trace = vec![col_1, col_2, col_3]
+            .into_iter()
+            .map(|col| CircleEvaluation::new(domain, col))
+            .collect();
+
+        (LogSizeStatement { log_size }, trace, ())
+    }
+    // ANCHOR_END: gen_trace
+
+    fn gen_interaction_trace(
+        &self,
+        _witness: &Self::Witness,
+        _lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        ((), vec![])
+    }
+}
@@ -0,0 +1,233 @@
+//! An AIR with public inputs: proves that the committed column is a permutation of a list of
+//! public values. The component yields every value of the column, and the verifier consumes the
+//! public values itself.
+
+use num_traits::One;
+use rand::{prelude::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkComponent, FrameworkEval, Relation,
+        RelationEntry,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{
+            m31::{M31, P},
+            qm31::SecureField,
+        },
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::{check_log_size, ClaimedSumStatement};
+use crate::{
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::registry::PreprocessedRegistry,
+    public_inputs::PublicEntries,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+relation!(ValueElements, 1);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicPermutationStatement {
+    pub log_size: u32,
+    /// The public values, each consumed once by the verifier.
+    pub public_values: PublicEntries,
+}
+
+impl Statement for PublicPermutationStatement {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_size as u64);
+        self.public_values.mix_into(channel);
+    }
+}
+
+pub struct PublicPermutationEval {
+    pub log_size: u32,
+    pub lookup_elements: ValueElements,
+}
+
+impl FrameworkEval for PublicPermutationEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let value_col = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::one(),
+            &[value_col],
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+pub type PublicPermutationComponent = FrameworkComponent<PublicPermutationEval>;
+
+pub struct PublicPermutationComponents {
+    pub component: PublicPermutationComponent,
+}
+
+impl PublicPermutationComponents {
+    pub fn new(
+        statement0: &PublicPermutationStatement,
+        lookup_elements: &ValueElements,
+        statement1: &ClaimedSumStatement,
+    ) -> Self {
+        Self {
+            component: PreprocessedRegistry::new().component_builder().build(
+                PublicPermutationEval {
+                    log_size: statement0.log_size,
+                    lookup_elements: lookup_elements.clone(),
+                },
+                statement1.claimed_sum,
+            ),
+        }
+    }
+}
+
+impl AirComponents for PublicPermutationComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct PublicPermutationAir {
+    pub log_size: u32,
+}
+
+impl Air for PublicPermutationAir {
+    const NAME: &'static str = "public_permutation";
+    const COMPONENT_NAMES: &'static [&'static str] = &["public_permutation"];
+
+    type Statement0 = PublicPermutationStatement;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = ValueElements;
+    type Components = PublicPermutationComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        check_log_size("log size", statement0.log_size)?;
+        statement0.public_values.check_arity(1)
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        ValueElements::draw(channel)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        PublicPermutationComponents::new(
+            statement0,
+            &ValueElements::dummy(),
+            &ClaimedSumStatement {
+                claimed_sum: SecureField::one(),
+            },
+        )
+        .component
+        .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn public_sum(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+    ) -> SecureField {
+        statement0.public_values.logup_sum(lookup_elements)
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        PublicPermutationComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for PublicPermutationAir {
+    type Witness = BaseColumn;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let mut rng = rand::thread_rng();
+        let public_values = (0..(1 << self.log_size))
+            .map(|_| M31::from(rng.gen_range(0..P)))
+            .collect::<Vec<_>>();
+
+        let mut public_entries = PublicEntries::new();
+        for value in &public_values {
+            public_entries.add(M31::from(1), vec![*value]);
+        }
+
+        // The trace holds the public values in a random order
+        let mut values = public_values;
+        values.shuffle(&mut rng);
+        let value_col = BaseColumn::from_iter(values);
+
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        let trace = vec![CircleEvaluation::new(domain, value_col.clone())];
+
+        (
+            PublicPermutationStatement {
+                log_size: self.log_size,
+                public_values: public_entries,
+            },
+            trace,
+            value_col,
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let mut logup_gen = LogupTraceGenerator::new(self.log_size);
+
+        // Only the yielding side is in the trace, the verifier adds the consuming side
+        let mut col_gen = logup_gen.new_col();
+        for row in 0..(1 << (self.log_size - LOG_N_LANES)) {
+            let value: PackedSecureField = lookup_elements.combine(&[witness.data[row]]);
+            col_gen.write_frac(row, -PackedSecureField::one(), value);
+        }
+        col_gen.finalize_col();
+
+        let (logup_cols, claimed_sum) = logup_gen.finalize_last();
+        (ClaimedSumStatement { claimed_sum }, logup_cols)
+    }
+}
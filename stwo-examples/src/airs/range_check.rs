@@ -0,0 +1,575 @@
+//! A family of range-check components, generalizing "Static Lookups" to any bit width.
+//!
+//! A range check is described by the widths of the elements of the checked tuples: `[8]` checks
+//! 8-bit values, while `[9, 9]` or `[4, 4, 4]` check tuples against a table of `2^18` or `2^12`
+//! rows, which is how a wide check is split into smaller tables. The table component yields every
+//! tuple with its multiplicity, and any consumer component checks tuples with [`range_check`],
+//! whatever its number of rows compared to the table. The multiplicities are accumulated across
+//! all the consumers by [`RangeCheckMultiplicities`].
+//!
+//! Each range check draws its own lookup elements, so that the tuples of different widths do not
+//! mix.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkComponent, FrameworkEval, Relation,
+        RelationEntry, PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::{check_log_size, gen_logup_trace_in_pairs};
+use crate::{
+    pipeline::{Air, AirComponents, AirProver, Statement},
+    preprocessed::{registry::PreprocessedRegistry, PreprocessedColumn, RangeCheckTuple},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// The maximum number of elements of a checked tuple.
+pub const MAX_RANGE_CHECK_ARITY: usize = 4;
+
+/// Combines a checked tuple.
+relation!(RangeCheckElements, MAX_RANGE_CHECK_ARITY);
+
+/// The widths of the elements of the checked tuples.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RangeCheckWidths(pub Vec<u32>);
+
+impl RangeCheckWidths {
+    pub fn new(n_bits: &[u32]) -> Self {
+        let widths = Self(n_bits.to_vec());
+        if let Err(err) = widths.check() {
+            panic!("{err}");
+        }
+        widths
+    }
+
+    /// Checks the widths accepted by [`Self::new`], for widths read from a statement.
+    pub fn check(&self) -> Result<(), String> {
+        let n_bits = &self.0;
+        if !(1..=MAX_RANGE_CHECK_ARITY).contains(&n_bits.len()) {
+            return Err(format!(
+                "range checks have 1 to {MAX_RANGE_CHECK_ARITY} elements, got {}",
+                n_bits.len()
+            ));
+        }
+        // Summed as u64 so that widths read from a statement cannot overflow
+        if !(n_bits.iter().all(|&n| n > 0) && n_bits.iter().map(|&n| n as u64).sum::<u64>() < 31) {
+            return Err(format!("unsupported widths {n_bits:?}"));
+        }
+        Ok(())
+    }
+
+    pub fn arity(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The log size of the table, which holds the `2^sum(n_bits)` tuples and at least a SIMD
+    /// vector.
+    pub fn log_size(&self) -> u32 {
+        self.table_columns()[0].log_size()
+    }
+
+    pub fn table_columns(&self) -> Vec<RangeCheckTuple> {
+        RangeCheckTuple::columns(&self.0)
+    }
+
+    pub fn register(&self, registry: &mut PreprocessedRegistry) {
+        for column in self.table_columns() {
+            registry.add(column);
+        }
+    }
+
+    /// The row of the table holding `tuple`.
+    pub fn row_of(&self, tuple: &[u32]) -> usize {
+        assert_eq!(tuple.len(), self.arity());
+        self.0.iter().zip(tuple).fold(0, |row, (&n_bits, &value)| {
+            assert!(
+                value >> n_bits == 0,
+                "{value} does not fit in {n_bits} bits"
+            );
+            (row << n_bits) | value as usize
+        })
+    }
+}
+
+/// Checks that `tuple` is in the table: consumes it from the range check. `numerator` is the
+/// number of checks, usually 1 or an enabler column.
+pub fn range_check<E: EvalAtRow>(
+    eval: &mut E,
+    range_check_elements: &RangeCheckElements,
+    numerator: E::EF,
+    tuple: &[E::F],
+) {
+    eval.add_to_relation(RelationEntry::new(range_check_elements, numerator, tuple));
+}
+
+/// The number of times each tuple is checked, accumulated over all the consumer components,
+/// padding rows included.
+#[derive(Clone, Debug)]
+pub struct RangeCheckMultiplicities {
+    pub widths: RangeCheckWidths,
+    multiplicities: Vec<u32>,
+}
+
+impl RangeCheckMultiplicities {
+    pub fn new(widths: RangeCheckWidths) -> Self {
+        let multiplicities = vec![0; 1 << widths.log_size()];
+        Self {
+            widths,
+            multiplicities,
+        }
+    }
+
+    pub fn add(&mut self, tuple: &[u32]) {
+        self.multiplicities[self.widths.row_of(tuple)] += 1;
+    }
+
+    /// Generates the multiplicity column of the table, in the bit-reversed order of the
+    /// preprocessed columns. When the table repeats itself, the repeated rows have a multiplicity
+    /// of 0.
+    pub fn gen_column(&self) -> BaseColumn {
+        let mut col = BaseColumn::from_iter(self.multiplicities.iter().map(|&m| M31::from(m)));
+        bit_reverse_coset_to_circle_domain_order(col.as_mut_slice());
+        col
+    }
+}
+
+/// The table: yields every tuple with multiplicity `mult`.
+pub struct RangeCheckEval {
+    pub widths: RangeCheckWidths,
+    pub range_check_elements: RangeCheckElements,
+}
+
+impl FrameworkEval for RangeCheckEval {
+    fn log_size(&self) -> u32 {
+        self.widths.log_size()
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.widths.log_size() + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let tuple = self
+            .widths
+            .table_columns()
+            .into_iter()
+            .map(|column| eval.get_preprocessed_column(column.id()))
+            .collect_vec();
+        let mult = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.range_check_elements,
+            -E::EF::from(mult),
+            &tuple,
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+pub fn gen_range_check_logup_trace(
+    widths: &RangeCheckWidths,
+    multiplicity_col: &BaseColumn,
+    range_check_elements: &RangeCheckElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let log_size = widths.log_size();
+    let table_cols = widths
+        .table_columns()
+        .iter()
+        .map(|column| column.gen_column().values)
+        .collect_vec();
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let tuple = table_cols.iter().map(|col| col.data[row]).collect_vec();
+        let denom: PackedSecureField = range_check_elements.combine(&tuple);
+        col_gen.write_frac(
+            row,
+            -PackedSecureField::from(multiplicity_col.data[row]),
+            denom,
+        );
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+/// An example consumer: checks `n_tuples` tuples per row, stored as consecutive groups of
+/// `widths.arity()` columns.
+pub struct ConsumerEval {
+    pub log_size: u32,
+    pub widths: RangeCheckWidths,
+    pub n_tuples: usize,
+    pub range_check_elements: RangeCheckElements,
+}
+
+impl FrameworkEval for ConsumerEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        for _ in 0..self.n_tuples {
+            let tuple = (0..self.widths.arity())
+                .map(|_| eval.next_trace_mask())
+                .collect_vec();
+            range_check(&mut eval, &self.range_check_elements, E::EF::one(), &tuple);
+        }
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates `2^log_size` rows of `n_tuples` random tuples, and counts them in `multiplicities`.
+pub fn gen_consumer_trace(
+    log_size: u32,
+    n_tuples: usize,
+    multiplicities: &mut RangeCheckMultiplicities,
+) -> Vec<BaseColumn> {
+    let widths = multiplicities.widths.clone();
+    let mut rng = rand::thread_rng();
+    let mut columns = vec![Vec::with_capacity(1 << log_size); n_tuples * widths.arity()];
+    for _ in 0..1 << log_size {
+        for tuple_columns in columns.chunks_mut(widths.arity()) {
+            let tuple = widths
+                .0
+                .iter()
+                .map(|&n_bits| rng.gen_range(0..1 << n_bits))
+                .collect_vec();
+            multiplicities.add(&tuple);
+            for (column, value) in tuple_columns.iter_mut().zip(tuple) {
+                column.push(M31::from(value));
+            }
+        }
+    }
+    columns.into_iter().map(BaseColumn::from_iter).collect()
+}
+
+pub fn gen_consumer_logup_trace(
+    log_size: u32,
+    arity: usize,
+    consumer_columns: &[BaseColumn],
+    range_check_elements: &RangeCheckElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let entries = consumer_columns
+        .chunks(arity)
+        .map(|tuple_columns| {
+            (0..1 << (log_size - LOG_N_LANES))
+                .map(|row| {
+                    let tuple = tuple_columns.iter().map(|col| col.data[row]).collect_vec();
+                    (
+                        PackedSecureField::one(),
+                        range_check_elements.combine(&tuple),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    gen_logup_trace_in_pairs(log_size, entries)
+}
+
+/// The number of tuples checked per row by each example consumer.
+pub const CONSUMER_N_TUPLES: [usize; 2] = [3, 1];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeCheckStatement0 {
+    pub widths: RangeCheckWidths,
+    pub consumer_log_sizes: [u32; 2],
+}
+
+impl Statement for RangeCheckStatement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.widths.arity() as u64);
+        for &n_bits in &self.widths.0 {
+            channel.mix_u64(n_bits as u64);
+        }
+        for log_size in self.consumer_log_sizes {
+            channel.mix_u64(log_size as u64);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeCheckStatement1 {
+    pub consumer_claimed_sums: [SecureField; 2],
+    pub table_claimed_sum: SecureField,
+}
+
+impl Statement for RangeCheckStatement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&self.consumer_claimed_sums);
+        channel.mix_felts(&[self.table_claimed_sum]);
+    }
+}
+
+pub fn preprocessed_registry(widths: &RangeCheckWidths) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    widths.register(&mut registry);
+    registry
+}
+
+pub type ConsumerComponent = FrameworkComponent<ConsumerEval>;
+pub type RangeCheckComponent = FrameworkComponent<RangeCheckEval>;
+
+pub struct RangeCheckComponents {
+    pub consumer_components: Vec<ConsumerComponent>,
+    pub table_component: RangeCheckComponent,
+}
+
+impl RangeCheckComponents {
+    pub fn new(
+        statement0: &RangeCheckStatement0,
+        lookup_elements: &RangeCheckElements,
+        statement1: &RangeCheckStatement1,
+    ) -> Self {
+        let builder = &mut preprocessed_registry(&statement0.widths).component_builder();
+
+        let consumer_components = statement0
+            .consumer_log_sizes
+            .into_iter()
+            .zip(CONSUMER_N_TUPLES)
+            .zip(statement1.consumer_claimed_sums)
+            .map(|((log_size, n_tuples), claimed_sum)| {
+                builder.build(
+                    ConsumerEval {
+                        log_size,
+                        widths: statement0.widths.clone(),
+                        n_tuples,
+                        range_check_elements: lookup_elements.clone(),
+                    },
+                    claimed_sum,
+                )
+            })
+            .collect();
+
+        let table_component = builder.build(
+            RangeCheckEval {
+                widths: statement0.widths.clone(),
+                range_check_elements: lookup_elements.clone(),
+            },
+            statement1.table_claimed_sum,
+        );
+
+        Self {
+            consumer_components,
+            table_component,
+        }
+    }
+}
+
+impl AirComponents for RangeCheckComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        chain!(
+            self.consumer_components
+                .iter()
+                .map(|component| component as &dyn Component),
+            [&self.table_component as &dyn Component]
+        )
+        .collect()
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        chain!(
+            self.consumer_components
+                .iter()
+                .map(|component| component as &dyn ComponentProver<SimdBackend>),
+            [&self.table_component as &dyn ComponentProver<SimdBackend>]
+        )
+        .collect()
+    }
+}
+
+/// Range checks random tuples of the given widths from two consumers: one of `2^log_size` rows,
+/// and one larger than the table.
+pub struct RangeCheckAir {
+    pub widths: RangeCheckWidths,
+    pub log_size: u32,
+}
+
+impl RangeCheckAir {
+    fn consumer_log_sizes(&self) -> [u32; 2] {
+        [self.log_size, self.widths.log_size() + 1]
+    }
+}
+
+impl Air for RangeCheckAir {
+    const NAME: &'static str = "range_check";
+    const COMPONENT_NAMES: &'static [&'static str] = &["consumer_0", "consumer_1", "table"];
+
+    type Statement0 = RangeCheckStatement0;
+    type Statement1 = RangeCheckStatement1;
+    type LookupElements = RangeCheckElements;
+    type Components = RangeCheckComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.widths.check()?;
+        for log_size in statement0.consumer_log_sizes {
+            check_log_size("consumer log size", log_size)?;
+        }
+        Ok(())
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        RangeCheckElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(&statement0.widths)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let components = RangeCheckComponents::new(
+            statement0,
+            &RangeCheckElements::dummy(),
+            &RangeCheckStatement1 {
+                consumer_claimed_sums: [SecureField::one(); 2],
+                table_claimed_sum: SecureField::one(),
+            },
+        );
+        let mut log_sizes = TreeVec::concat_cols(
+            components
+                .components()
+                .into_iter()
+                .map(|component| component.trace_log_degree_bounds()),
+        );
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] = preprocessed_registry(&statement0.widths).log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.consumer_claimed_sums[0]
+            + statement1.consumer_claimed_sums[1]
+            + statement1.table_claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        RangeCheckComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for RangeCheckAir {
+    /// The columns of each consumer, followed by the multiplicity column of the table.
+    type Witness = Vec<Vec<BaseColumn>>;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let mut multiplicities = RangeCheckMultiplicities::new(self.widths.clone());
+        let consumer_log_sizes = self.consumer_log_sizes();
+        let consumers_columns = consumer_log_sizes
+            .iter()
+            .zip(CONSUMER_N_TUPLES)
+            .map(|(&log_size, n_tuples)| {
+                gen_consumer_trace(log_size, n_tuples, &mut multiplicities)
+            })
+            .collect_vec();
+        let multiplicity_col = multiplicities.gen_column();
+
+        let trace = chain!(
+            consumer_log_sizes
+                .iter()
+                .zip(&consumers_columns)
+                .flat_map(|(&log_size, columns)| {
+                    let domain = CanonicCoset::new(log_size).circle_domain();
+                    columns
+                        .iter()
+                        .map(move |col| CircleEvaluation::new(domain, col.clone()))
+                }),
+            [CircleEvaluation::new(
+                CanonicCoset::new(self.widths.log_size()).circle_domain(),
+                multiplicity_col.clone()
+            )]
+        )
+        .collect();
+
+        (
+            RangeCheckStatement0 {
+                widths: self.widths.clone(),
+                consumer_log_sizes,
+            },
+            trace,
+            chain!(consumers_columns, [vec![multiplicity_col]]).collect(),
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let Some((table_columns, consumers_columns)) = witness.split_last() else {
+            panic!("unexpected witness layout");
+        };
+        let [multiplicity_col] = &table_columns[..] else {
+            panic!("unexpected witness layout");
+        };
+        let (consumer_cols, consumer_claimed_sums): (Vec<_>, Vec<_>) = self
+            .consumer_log_sizes()
+            .into_iter()
+            .zip(consumers_columns)
+            .map(|(log_size, columns)| {
+                gen_consumer_logup_trace(log_size, self.widths.arity(), columns, lookup_elements)
+            })
+            .unzip();
+        let (table_cols, table_claimed_sum) =
+            gen_range_check_logup_trace(&self.widths, multiplicity_col, lookup_elements);
+
+        (
+            RangeCheckStatement1 {
+                consumer_claimed_sums: consumer_claimed_sums.try_into().unwrap(),
+                table_claimed_sum,
+            },
+            chain!(consumer_cols.into_iter().flatten(), table_cols).collect(),
+        )
+    }
+}
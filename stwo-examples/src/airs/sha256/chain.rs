@@ -0,0 +1,108 @@
+//! The links between the hashes of a chain: row `i` consumes the digest of instance `i`, and
+//! either yields the block of instance `i + 1`, which hashes the digest, or yields the digest as
+//! the output of the chain.
+//!
+//! Whether an instance is linked is a witness bit: the verifier yields the first block of every
+//! chain and consumes its output, so linking or not linking the wrong instances unbalances the
+//! relations.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval},
+    core::fields::m31::M31,
+};
+
+use super::{
+    limbs,
+    reference::{digest_block, Digest},
+    Sha256Elements, Sha256Relation, Sha256Row,
+};
+use crate::{
+    gadgets::enabler::Enabler,
+    preprocessed::{PreprocessedColumn, Seq},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+pub struct ChainEval {
+    pub log_size: u32,
+    pub elements: Sha256Elements,
+}
+
+impl FrameworkEval for ChainEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let elements = &self.elements;
+        let enabled = Enabler::monotone(self.log_size).evaluate(&mut eval);
+        let instance = eval.get_preprocessed_column(Seq::new(self.log_size).id());
+
+        let is_link = eval.next_trace_mask();
+        let digest = (0..16).map(|_| eval.next_trace_mask()).collect_vec();
+        eval.add_constraint(is_link.clone() * (is_link.clone() - E::F::one()));
+
+        let values = chain!([instance.clone()], digest.clone()).collect_vec();
+        enabled.add_to_relation(&mut eval, &elements.digest, E::EF::one(), &values);
+
+        // The block hashing the digest: its 8 words followed by constant padding
+        let padding = digest_block(&[0; 8])[8..]
+            .iter()
+            .flat_map(|&word| limbs(word))
+            .map(E::F::from)
+            .collect_vec();
+        let block = chain!(digest, padding).collect_vec();
+        let next_instance = instance + E::F::one();
+        for (t, word) in block.chunks(2).enumerate() {
+            enabled.add_to_relation(
+                &mut eval,
+                &elements.message,
+                -E::EF::from(is_link.clone()),
+                &chain!(
+                    [next_instance.clone(), E::F::from(M31::from(t))],
+                    word.iter().cloned()
+                )
+                .collect_vec(),
+            );
+        }
+
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.output,
+            -E::EF::from(E::F::one() - is_link),
+            &values,
+        );
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the link of instance `instance`, with its digest.
+pub fn gen_chain_row(instance: usize, digest: &Digest, is_link: bool) -> Sha256Row {
+    let mut row = Sha256Row::new();
+    let instance = M31::from(instance);
+    let is_link = M31::from(is_link as u32);
+
+    row.columns.push(is_link);
+    row.columns
+        .extend(digest.iter().flat_map(|&word| limbs(word)));
+
+    let values = chain!([instance], digest.iter().flat_map(|&word| limbs(word))).collect_vec();
+    row.entry(Sha256Relation::Digest, M31::one(), values.clone());
+    for (t, &word) in digest_block(digest).iter().enumerate() {
+        row.entry(
+            Sha256Relation::Message,
+            -is_link,
+            chain!([instance + M31::one(), M31::from(t)], limbs(word)).collect(),
+        );
+    }
+    row.entry(Sha256Relation::Output, -(M31::one() - is_link), values);
+    row
+}
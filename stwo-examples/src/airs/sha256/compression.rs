@@ -0,0 +1,150 @@
+//! The compressions: row `i` starts the rounds of instance `i` from the initial hash, and adds the
+//! state after the last round to it into the digest.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval},
+    core::fields::m31::M31,
+};
+
+use super::{
+    add_carries, limbs,
+    reference::{Digest, INITIAL_HASH},
+    word_bytes, Sha256Elements, Sha256Relation, Sha256Row,
+};
+use crate::{
+    gadgets::{
+        bytes::{bytes_to_limbs, check_bytes, ByteTables},
+        enabler::Enabler,
+    },
+    preprocessed::{PreprocessedColumn, Seq},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+pub struct CompressionEval {
+    pub log_size: u32,
+    pub elements: Sha256Elements,
+}
+
+impl FrameworkEval for CompressionEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let elements = &self.elements;
+        let enabled = Enabler::monotone(self.log_size).evaluate(&mut eval);
+        let instance = eval.get_preprocessed_column(Seq::new(self.log_size).id());
+
+        let state = (0..16).map(|_| eval.next_trace_mask()).collect_vec();
+        let digest = (0..32).map(|_| eval.next_trace_mask()).collect_vec();
+        let carry_bits = (0..16).map(|_| eval.next_trace_mask()).collect_vec();
+
+        let initial_state = INITIAL_HASH
+            .into_iter()
+            .flat_map(limbs)
+            .map(E::F::from)
+            .collect_vec();
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.state,
+            -E::EF::one(),
+            &chain!(
+                [instance.clone(), E::F::from(M31::from(0))],
+                initial_state.clone()
+            )
+            .collect_vec(),
+        );
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.state,
+            E::EF::one(),
+            &chain!([instance.clone(), E::F::from(M31::from(64))], state.clone()).collect_vec(),
+        );
+        check_bytes(
+            &mut eval,
+            &elements.byte_split,
+            E::EF::from(enabled.enabler.clone()),
+            &digest,
+        );
+
+        // digest = initial hash + state, limb by limb with a carry bit out of each limb
+        for bit in &carry_bits {
+            eval.add_constraint(bit.clone() * (bit.clone() - E::F::one()));
+        }
+        let digest = bytes_to_limbs(&digest);
+        for i in (0..16).step_by(2) {
+            let [carry_lo, carry_hi] = [&carry_bits[i], &carry_bits[i + 1]];
+            eval.add_constraint(
+                enabled.enabler.clone()
+                    * (initial_state[i].clone() + state[i].clone()
+                        - digest[i].clone()
+                        - carry_lo.clone() * M31::from(1 << 16)),
+            );
+            eval.add_constraint(
+                enabled.enabler.clone()
+                    * (initial_state[i + 1].clone() + state[i + 1].clone() + carry_lo.clone()
+                        - digest[i + 1].clone()
+                        - carry_hi.clone() * M31::from(1 << 16)),
+            );
+        }
+
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.digest,
+            -E::EF::one(),
+            &chain!([instance], digest).collect_vec(),
+        );
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates the compression of instance `instance` from the state after its last round, and
+/// returns it with the digest.
+pub fn gen_compression_row(
+    tables: &mut ByteTables,
+    instance: usize,
+    state: [u32; 8],
+) -> (Sha256Row, Digest) {
+    let mut row = Sha256Row::new();
+    let instance = M31::from(instance);
+    let digest: Digest = std::array::from_fn(|i| INITIAL_HASH[i].wrapping_add(state[i]));
+
+    row.columns.extend(state.into_iter().flat_map(limbs));
+    let digest_bytes = digest.into_iter().flat_map(word_bytes).collect_vec();
+    row.push(&digest_bytes);
+    for (initial, word) in INITIAL_HASH.into_iter().zip(state) {
+        let (carry_lo, carry_hi) = add_carries(&[initial, word]);
+        row.push(&[carry_lo, carry_hi]);
+    }
+
+    row.entry(
+        Sha256Relation::State,
+        -M31::one(),
+        chain!(
+            [instance, M31::from(0)],
+            INITIAL_HASH.into_iter().flat_map(limbs)
+        )
+        .collect(),
+    );
+    row.entry(
+        Sha256Relation::State,
+        M31::one(),
+        chain!([instance, M31::from(64)], state.into_iter().flat_map(limbs)).collect(),
+    );
+    row.check(tables, &digest_bytes);
+    row.entry(
+        Sha256Relation::Digest,
+        -M31::one(),
+        chain!([instance], digest.into_iter().flat_map(limbs)).collect(),
+    );
+    (row, digest)
+}
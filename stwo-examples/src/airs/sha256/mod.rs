@@ -0,0 +1,769 @@
+//! SHA-256 over single-block messages, hashed once or chained, as in the Sha2 and Sha2-Chain
+//! workloads of the Benchmarks Report.
+//!
+//! Every compression is an instance. Words are stored as little-endian bytes and move between
+//! components as 16-bit limbs:
+//! - [`schedule`] has a row per word of the message schedule, 64 per instance. It consumes the
+//!   16 words of the block and expands the others from the previous rows.
+//! - [`round`] has a row per round, 64 per instance, which consumes the state before the round and
+//!   the word of the schedule, and yields the state after it.
+//! - [`compression`] has a row per instance, which yields the initial state, consumes the state
+//!   after the last round and adds it to the initial hash into the digest.
+//! - [`chain`] has a row per instance, which turns the digest into the block of the next instance
+//!   of its chain, or into the output of the chain.
+//!
+//! Rotations, shifts, XOR and AND are looked up in byte tables (see [`crate::gadgets::bytes`]),
+//! and the verifier closes the relations with the first block and the output of every chain.
+
+pub mod chain;
+pub mod compression;
+pub mod reference;
+pub mod round;
+pub mod schedule;
+
+use std::fmt;
+
+use itertools::chain;
+use num_traits::One;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, FrameworkComponent, Relation,
+        PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::simd::SimdBackend,
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+    relation,
+};
+
+use self::{
+    chain::{gen_chain_row, ChainEval},
+    compression::{gen_compression_row, CompressionEval},
+    reference::{
+        digest_block, message_schedule, pad_single_block, Block, Digest, INITIAL_HASH,
+        MAX_SINGLE_BLOCK_LEN, ROUND_CONSTANTS,
+    },
+    round::{gen_round_row, RoundEval},
+    schedule::{gen_schedule_row, ScheduleEval},
+};
+use super::{
+    bitwise::{gen_table_logup_trace, BitwiseElements, BitwiseTableEval},
+    check_log_size,
+};
+use crate::{
+    gadgets::{
+        bytes::{
+            gen_byte_split_table_logup_trace, ByteRelation, ByteSplitElements, ByteSplitTableEval,
+            ByteTables, TraceRow,
+        },
+        enabler::padded_log_size,
+    },
+    pipeline::{Air, AirComponents, AirProver, Statement, MAX_COLUMN_LOG_SIZE},
+    preprocessed::{
+        registry::PreprocessedRegistry, BitwiseOp, BitwiseTable, ByteSplit, IsFirst,
+        PreprocessedColumn, Seq,
+    },
+    public_inputs::PublicEntries,
+};
+
+/// Combines `(instance, t, lo, hi)` for the word `t` of the block of an instance.
+relation!(MessageElements, 4);
+/// Combines `(instance, t, lo, hi)` for the word `t` of the message schedule of an instance.
+relation!(ScheduleElements, 4);
+/// Combines `(instance, t, a_lo, a_hi, .., h_lo, h_hi)` for the state before round `t`.
+relation!(StateElements, 18);
+/// Combines `(instance, h0_lo, h0_hi, .., h7_lo, h7_hi)` for the digest of an instance.
+relation!(DigestElements, 17);
+/// Combines `(instance, h0_lo, h0_hi, .., h7_lo, h7_hi)` for the output of a chain.
+relation!(OutputElements, 17);
+
+#[derive(Clone)]
+pub struct Sha256Elements {
+    pub xor: BitwiseElements,
+    pub and: BitwiseElements,
+    pub byte_split: ByteSplitElements,
+    pub message: MessageElements,
+    pub schedule: ScheduleElements,
+    pub state: StateElements,
+    pub digest: DigestElements,
+    pub output: OutputElements,
+}
+
+impl Sha256Elements {
+    pub fn draw(channel: &mut impl Channel) -> Self {
+        Self {
+            xor: BitwiseElements::draw(channel),
+            and: BitwiseElements::draw(channel),
+            byte_split: ByteSplitElements::draw(channel),
+            message: MessageElements::draw(channel),
+            schedule: ScheduleElements::draw(channel),
+            state: StateElements::draw(channel),
+            digest: DigestElements::draw(channel),
+            output: OutputElements::draw(channel),
+        }
+    }
+
+    pub fn dummy() -> Self {
+        Self {
+            xor: BitwiseElements::dummy(),
+            and: BitwiseElements::dummy(),
+            byte_split: ByteSplitElements::dummy(),
+            message: MessageElements::dummy(),
+            schedule: ScheduleElements::dummy(),
+            state: StateElements::dummy(),
+            digest: DigestElements::dummy(),
+            output: OutputElements::dummy(),
+        }
+    }
+
+    pub fn combine(&self, relation: Sha256Relation, values: &[M31]) -> SecureField {
+        match relation {
+            Sha256Relation::Xor => self.xor.combine(values),
+            Sha256Relation::And => self.and.combine(values),
+            Sha256Relation::ByteSplit => self.byte_split.combine(values),
+            Sha256Relation::Message => self.message.combine(values),
+            Sha256Relation::Schedule => self.schedule.combine(values),
+            Sha256Relation::State => self.state.combine(values),
+            Sha256Relation::Digest => self.digest.combine(values),
+            Sha256Relation::Output => self.output.combine(values),
+        }
+    }
+}
+
+/// The relations of the AIR, to tag the entries of the generated rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sha256Relation {
+    Xor,
+    And,
+    ByteSplit,
+    Message,
+    Schedule,
+    State,
+    Digest,
+    Output,
+}
+
+impl From<ByteRelation> for Sha256Relation {
+    fn from(relation: ByteRelation) -> Self {
+        match relation {
+            ByteRelation::Xor => Self::Xor,
+            ByteRelation::And => Self::And,
+            ByteRelation::Split => Self::ByteSplit,
+        }
+    }
+}
+
+pub type Sha256Row = TraceRow<Sha256Relation>;
+
+/// The `(lo, hi)` 16-bit limbs of `word`.
+pub fn limbs(word: u32) -> [M31; 2] {
+    [M31::from(word & 0xffff), M31::from(word >> 16)]
+}
+
+/// The 4 bytes of `word`, least significant first.
+pub fn word_bytes(word: u32) -> Vec<u32> {
+    crate::gadgets::bytes::to_bytes(word as u64, 4)
+}
+
+/// The carries out of the low and the high limbs when adding `words` limb by limb.
+pub fn add_carries(words: &[u32]) -> (u32, u32) {
+    let lo = words.iter().map(|word| word & 0xffff).sum::<u32>();
+    let hi = words.iter().map(|word| word >> 16).sum::<u32>() + (lo >> 16);
+    (lo >> 16, hi >> 16)
+}
+
+/// The columns of the round layout, shared by [`schedule`] and [`round`]: row `64 * i + t` is
+/// word or round `t` of instance `i`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundColumnKind {
+    /// `t`.
+    Round,
+    /// `i`.
+    Instance,
+    /// The low limb of the round constant `K[t]`.
+    ConstantLow,
+    /// The high limb of the round constant `K[t]`.
+    ConstantHigh,
+    /// 1 if word `t` is expanded from the previous words (`t >= 16`), 0 if it is in the block.
+    IsExpanded,
+}
+
+impl fmt::Display for RoundColumnKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Round => write!(f, "round"),
+            Self::Instance => write!(f, "instance"),
+            Self::ConstantLow => write!(f, "constant_low"),
+            Self::ConstantHigh => write!(f, "constant_high"),
+            Self::IsExpanded => write!(f, "is_expanded"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundColumn {
+    pub kind: RoundColumnKind,
+    pub log_size: u32,
+}
+
+impl RoundColumn {
+    pub fn new(kind: RoundColumnKind, log_size: u32) -> Self {
+        assert!(log_size >= 6, "a round layout holds at least one instance");
+        Self { kind, log_size }
+    }
+}
+
+impl PreprocessedColumn for RoundColumn {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!("sha256_{}_{}", self.kind, self.log_size),
+        }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        let t = row % 64;
+        M31::from(match self.kind {
+            RoundColumnKind::Round => t as u32,
+            RoundColumnKind::Instance => (row / 64) as u32,
+            RoundColumnKind::ConstantLow => ROUND_CONSTANTS[t] & 0xffff,
+            RoundColumnKind::ConstantHigh => ROUND_CONSTANTS[t] >> 16,
+            RoundColumnKind::IsExpanded => (t >= 16) as u32,
+        })
+    }
+}
+
+/// The log size of the [`schedule`] and [`round`] components: 64 rows per instance.
+pub fn rounds_log_size(n_instances: usize) -> u32 {
+    n_instances.next_power_of_two().ilog2() + 6
+}
+
+/// The log size of the [`compression`] and [`chain`] components: one row per instance.
+pub fn instances_log_size(n_instances: usize) -> u32 {
+    padded_log_size(n_instances)
+}
+
+pub fn preprocessed_registry(n_instances: usize) -> PreprocessedRegistry {
+    let rounds_log_size = rounds_log_size(n_instances);
+    let instances_log_size = instances_log_size(n_instances);
+
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(rounds_log_size));
+    for kind in [
+        RoundColumnKind::Round,
+        RoundColumnKind::Instance,
+        RoundColumnKind::ConstantLow,
+        RoundColumnKind::ConstantHigh,
+        RoundColumnKind::IsExpanded,
+    ] {
+        registry.add(RoundColumn::new(kind, rounds_log_size));
+    }
+    registry.add(IsFirst::new(instances_log_size));
+    registry.add(Seq::new(instances_log_size));
+    for column in chain!(
+        BitwiseTable::columns(BitwiseOp::Xor, 8),
+        BitwiseTable::columns(BitwiseOp::And, 8)
+    ) {
+        registry.add(column);
+    }
+    for column in ByteSplit::columns() {
+        registry.add(column);
+    }
+    registry
+}
+
+/// The first block and the output of every chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sha256Statement0 {
+    pub blocks: Vec<Block>,
+    /// The number of hashes of every chain.
+    pub chain_length: u32,
+    pub digests: Vec<Digest>,
+}
+
+impl Sha256Statement0 {
+    pub fn n_instances(&self) -> usize {
+        self.blocks.len() * self.chain_length as usize
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.blocks.is_empty() || self.chain_length == 0 {
+            return Err("nothing is hashed".to_string());
+        }
+        if self.digests.len() != self.blocks.len() {
+            return Err(format!(
+                "{} chains but {} digests",
+                self.blocks.len(),
+                self.digests.len()
+            ));
+        }
+        // Bounds the number of instances before it is padded
+        if self.n_instances() > 1 << MAX_COLUMN_LOG_SIZE {
+            return Err(format!("too many instances: {}", self.n_instances()));
+        }
+        check_log_size("rounds log size", rounds_log_size(self.n_instances()))
+    }
+
+    /// The blocks yielded to the first instance of every chain, and the outputs consumed from
+    /// the last one.
+    pub fn public_entries(&self) -> (PublicEntries, PublicEntries) {
+        let chain_length = self.chain_length as usize;
+        let mut messages = PublicEntries::new();
+        let mut outputs = PublicEntries::new();
+        for (i, (block, digest)) in self.blocks.iter().zip(&self.digests).enumerate() {
+            let first = M31::from((i * chain_length) as u32);
+            for (t, &word) in block.iter().enumerate() {
+                messages.add(
+                    -M31::one(),
+                    chain!([first, M31::from(t as u32)], limbs(word)).collect(),
+                );
+            }
+            let last = M31::from(((i + 1) * chain_length - 1) as u32);
+            outputs.add(
+                M31::one(),
+                chain!([last], digest.iter().flat_map(|&word| limbs(word))).collect(),
+            );
+        }
+        (messages, outputs)
+    }
+}
+
+impl Statement for Sha256Statement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.blocks.len() as u64);
+        channel.mix_u64(self.chain_length as u64);
+        for word in chain!(self.blocks.iter().flatten(), self.digests.iter().flatten()) {
+            channel.mix_u64(*word as u64);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sha256Statement1 {
+    pub schedule_claimed_sum: SecureField,
+    pub round_claimed_sum: SecureField,
+    pub compression_claimed_sum: SecureField,
+    pub chain_claimed_sum: SecureField,
+    pub xor_table_claimed_sum: SecureField,
+    pub and_table_claimed_sum: SecureField,
+    pub byte_split_table_claimed_sum: SecureField,
+}
+
+impl Statement for Sha256Statement1 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[
+            self.schedule_claimed_sum,
+            self.round_claimed_sum,
+            self.compression_claimed_sum,
+            self.chain_claimed_sum,
+            self.xor_table_claimed_sum,
+            self.and_table_claimed_sum,
+            self.byte_split_table_claimed_sum,
+        ]);
+    }
+}
+
+pub type ScheduleComponent = FrameworkComponent<ScheduleEval>;
+pub type RoundComponent = FrameworkComponent<RoundEval>;
+pub type CompressionComponent = FrameworkComponent<CompressionEval>;
+pub type ChainComponent = FrameworkComponent<ChainEval>;
+pub type BitwiseTableComponent = FrameworkComponent<BitwiseTableEval>;
+pub type ByteSplitTableComponent = FrameworkComponent<ByteSplitTableEval>;
+
+pub struct Sha256Components {
+    pub schedule: ScheduleComponent,
+    pub round: RoundComponent,
+    pub compression: CompressionComponent,
+    pub chain: ChainComponent,
+    pub xor_table: BitwiseTableComponent,
+    pub and_table: BitwiseTableComponent,
+    pub byte_split_table: ByteSplitTableComponent,
+}
+
+impl Sha256Components {
+    pub fn new(
+        statement0: &Sha256Statement0,
+        elements: &Sha256Elements,
+        statement1: &Sha256Statement1,
+    ) -> Self {
+        let n_instances = statement0.n_instances();
+        let rounds_log_size = rounds_log_size(n_instances);
+        let instances_log_size = instances_log_size(n_instances);
+        let builder = &mut preprocessed_registry(n_instances).component_builder();
+
+        Self {
+            schedule: builder.build(
+                ScheduleEval {
+                    log_size: rounds_log_size,
+                    elements: elements.clone(),
+                },
+                statement1.schedule_claimed_sum,
+            ),
+            round: builder.build(
+                RoundEval {
+                    log_size: rounds_log_size,
+                    elements: elements.clone(),
+                },
+                statement1.round_claimed_sum,
+            ),
+            compression: builder.build(
+                CompressionEval {
+                    log_size: instances_log_size,
+                    elements: elements.clone(),
+                },
+                statement1.compression_claimed_sum,
+            ),
+            chain: builder.build(
+                ChainEval {
+                    log_size: instances_log_size,
+                    elements: elements.clone(),
+                },
+                statement1.chain_claimed_sum,
+            ),
+            xor_table: builder.build(
+                BitwiseTableEval {
+                    op: BitwiseOp::Xor,
+                    n_bits: 8,
+                    lookup_elements: elements.xor.clone(),
+                },
+                statement1.xor_table_claimed_sum,
+            ),
+            and_table: builder.build(
+                BitwiseTableEval {
+                    op: BitwiseOp::And,
+                    n_bits: 8,
+                    lookup_elements: elements.and.clone(),
+                },
+                statement1.and_table_claimed_sum,
+            ),
+            byte_split_table: builder.build(
+                ByteSplitTableEval {
+                    lookup_elements: elements.byte_split.clone(),
+                },
+                statement1.byte_split_table_claimed_sum,
+            ),
+        }
+    }
+}
+
+impl AirComponents for Sha256Components {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![
+            &self.schedule as &dyn Component,
+            &self.round,
+            &self.compression,
+            &self.chain,
+            &self.xor_table,
+            &self.and_table,
+            &self.byte_split_table,
+        ]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![
+            &self.schedule as &dyn ComponentProver<SimdBackend>,
+            &self.round,
+            &self.compression,
+            &self.chain,
+            &self.xor_table,
+            &self.and_table,
+            &self.byte_split_table,
+        ]
+    }
+}
+
+/// `n_messages` sample messages of [`MAX_SINGLE_BLOCK_LEN`] bytes, the most a block holds.
+pub fn sample_messages(n_messages: usize) -> Vec<Vec<u8>> {
+    (0..n_messages)
+        .map(|i| {
+            (0..MAX_SINGLE_BLOCK_LEN)
+                .map(|j| ((i * MAX_SINGLE_BLOCK_LEN + j) % 251) as u8)
+                .collect()
+        })
+        .collect()
+}
+
+/// Hashes every message, which must fit in a single block, `chain_length` times: every hash but
+/// the first of a chain takes the previous digest as its message.
+pub struct Sha256Air {
+    pub messages: Vec<Vec<u8>>,
+    pub chain_length: usize,
+}
+
+impl Air for Sha256Air {
+    const NAME: &'static str = "sha256";
+    const COMPONENT_NAMES: &'static [&'static str] = &[
+        "schedule",
+        "round",
+        "compression",
+        "chain",
+        "xor_table",
+        "and_table",
+        "byte_split_table",
+    ];
+
+    type Statement0 = Sha256Statement0;
+    type Statement1 = Sha256Statement1;
+    type LookupElements = Sha256Elements;
+    type Components = Sha256Components;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        Sha256Elements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.n_instances())
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let components = Sha256Components::new(
+            statement0,
+            &Sha256Elements::dummy(),
+            &Sha256Statement1 {
+                schedule_claimed_sum: SecureField::one(),
+                round_claimed_sum: SecureField::one(),
+                compression_claimed_sum: SecureField::one(),
+                chain_claimed_sum: SecureField::one(),
+                xor_table_claimed_sum: SecureField::one(),
+                and_table_claimed_sum: SecureField::one(),
+                byte_split_table_claimed_sum: SecureField::one(),
+            },
+        );
+        let mut log_sizes = TreeVec::concat_cols(
+            components
+                .components()
+                .into_iter()
+                .map(|component| component.trace_log_degree_bounds()),
+        );
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] =
+            preprocessed_registry(statement0.n_instances()).log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.schedule_claimed_sum
+            + statement1.round_claimed_sum
+            + statement1.compression_claimed_sum
+            + statement1.chain_claimed_sum
+            + statement1.xor_table_claimed_sum
+            + statement1.and_table_claimed_sum
+            + statement1.byte_split_table_claimed_sum
+    }
+
+    fn public_sum(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+    ) -> SecureField {
+        let (messages, outputs) = statement0.public_entries();
+        messages.logup_sum(&lookup_elements.message) + outputs.logup_sum(&lookup_elements.output)
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        Sha256Components::new(statement0, lookup_elements, statement1)
+    }
+}
+
+/// The rows of the components and the multiplicities of the tables.
+pub struct Sha256Witness {
+    pub schedule: Vec<Sha256Row>,
+    pub round: Vec<Sha256Row>,
+    pub compression: Vec<Sha256Row>,
+    pub chain: Vec<Sha256Row>,
+    pub tables: ByteTables,
+}
+
+impl Sha256Air {
+    pub fn n_instances(&self) -> usize {
+        self.messages.len() * self.chain_length
+    }
+
+    /// Runs every chain, generating the rows of its instances, and returns the outputs.
+    pub fn gen_witness(&self) -> (Vec<Digest>, Sha256Witness) {
+        assert!(
+            !self.messages.is_empty() && self.chain_length > 0,
+            "nothing to hash"
+        );
+        let mut witness = Sha256Witness {
+            schedule: vec![],
+            round: vec![],
+            compression: vec![],
+            chain: vec![],
+            tables: ByteTables::new(),
+        };
+        let tables = &mut witness.tables;
+
+        let mut outputs = vec![];
+        for (i, message) in self.messages.iter().enumerate() {
+            let mut block = pad_single_block(message);
+            for k in 0..self.chain_length {
+                let instance = i * self.chain_length + k;
+                let w = message_schedule(&block);
+                for t in 0..64 {
+                    witness
+                        .schedule
+                        .push(gen_schedule_row(tables, instance, t, &w));
+                }
+
+                let mut state = INITIAL_HASH;
+                for (t, &word) in w.iter().enumerate() {
+                    let (row, next_state) = gen_round_row(tables, instance, t, state, word);
+                    witness.round.push(row);
+                    state = next_state;
+                }
+
+                let (row, digest) = gen_compression_row(tables, instance, state);
+                witness.compression.push(row);
+                let is_link = k + 1 < self.chain_length;
+                witness
+                    .chain
+                    .push(gen_chain_row(instance, &digest, is_link));
+
+                if is_link {
+                    block = digest_block(&digest);
+                } else {
+                    outputs.push(digest);
+                }
+            }
+        }
+
+        (outputs, witness)
+    }
+}
+
+impl AirProver for Sha256Air {
+    type Witness = Sha256Witness;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let (digests, witness) = self.gen_witness();
+        let rounds_log_size = rounds_log_size(self.n_instances());
+        let instances_log_size = instances_log_size(self.n_instances());
+
+        let component_columns = [
+            (rounds_log_size, &witness.schedule),
+            (rounds_log_size, &witness.round),
+            (instances_log_size, &witness.compression),
+            (instances_log_size, &witness.chain),
+        ]
+        .into_iter()
+        .map(|(log_size, rows)| (log_size, Sha256Row::gen_trace(log_size, rows)));
+        let table_columns = [
+            (2 * 8, witness.tables.xor.gen_column()),
+            (2 * 8, witness.tables.and.gen_column()),
+            (ByteSplit::LOG_SIZE, witness.tables.split.gen_column()),
+        ]
+        .into_iter()
+        .map(|(log_size, column)| (log_size, vec![column]));
+        let trace = chain!(component_columns, table_columns)
+            .flat_map(|(log_size, columns)| {
+                let domain = CanonicCoset::new(log_size).circle_domain();
+                columns
+                    .into_iter()
+                    .map(move |column| CircleEvaluation::new(domain, column))
+            })
+            .collect();
+
+        (
+            Sha256Statement0 {
+                blocks: self
+                    .messages
+                    .iter()
+                    .map(|message| pad_single_block(message))
+                    .collect(),
+                chain_length: self.chain_length as u32,
+                digests,
+            },
+            trace,
+            witness,
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let rounds_log_size = rounds_log_size(self.n_instances());
+        let instances_log_size = instances_log_size(self.n_instances());
+
+        let combine = |relation, values: &[M31]| lookup_elements.combine(relation, values);
+        let (schedule_cols, schedule_claimed_sum) =
+            Sha256Row::gen_logup_trace(rounds_log_size, &witness.schedule, combine);
+        let (round_cols, round_claimed_sum) =
+            Sha256Row::gen_logup_trace(rounds_log_size, &witness.round, combine);
+        let (compression_cols, compression_claimed_sum) =
+            Sha256Row::gen_logup_trace(instances_log_size, &witness.compression, combine);
+        let (chain_cols, chain_claimed_sum) =
+            Sha256Row::gen_logup_trace(instances_log_size, &witness.chain, combine);
+        let (xor_table_cols, xor_table_claimed_sum) = gen_table_logup_trace(
+            BitwiseOp::Xor,
+            8,
+            &witness.tables.xor.gen_column(),
+            &lookup_elements.xor,
+        );
+        let (and_table_cols, and_table_claimed_sum) = gen_table_logup_trace(
+            BitwiseOp::And,
+            8,
+            &witness.tables.and.gen_column(),
+            &lookup_elements.and,
+        );
+        let (byte_split_table_cols, byte_split_table_claimed_sum) =
+            gen_byte_split_table_logup_trace(
+                &witness.tables.split.gen_column(),
+                &lookup_elements.byte_split,
+            );
+
+        (
+            Sha256Statement1 {
+                schedule_claimed_sum,
+                round_claimed_sum,
+                compression_claimed_sum,
+                chain_claimed_sum,
+                xor_table_claimed_sum,
+                and_table_claimed_sum,
+                byte_split_table_claimed_sum,
+            },
+            [
+                schedule_cols,
+                round_cols,
+                compression_cols,
+                chain_cols,
+                xor_table_cols,
+                and_table_cols,
+                byte_split_table_cols,
+            ]
+            .concat(),
+        )
+    }
+}
@@ -0,0 +1,135 @@
+//! A plain implementation of SHA-256 (FIPS 180-4), which the trace generators follow and the AIR
+//! is checked against.
+
+pub const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+pub const INITIAL_HASH: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The largest message that fits in a single block, with its padding.
+pub const MAX_SINGLE_BLOCK_LEN: usize = 55;
+
+pub type Block = [u32; 16];
+pub type Digest = [u32; 8];
+
+pub fn big_sigma0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+pub fn big_sigma1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+pub fn small_sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+pub fn small_sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+pub fn ch(e: u32, f: u32, g: u32) -> u32 {
+    (e & f) ^ (!e & g)
+}
+
+pub fn maj(a: u32, b: u32, c: u32) -> u32 {
+    (a & b) ^ (c & (a ^ b))
+}
+
+/// Expands a block into the 64 words of the message schedule.
+pub fn message_schedule(block: &Block) -> [u32; 64] {
+    let mut w = [0; 64];
+    w[..16].copy_from_slice(block);
+    for t in 16..64 {
+        w[t] = small_sigma1(w[t - 2])
+            .wrapping_add(w[t - 7])
+            .wrapping_add(small_sigma0(w[t - 15]))
+            .wrapping_add(w[t - 16]);
+    }
+    w
+}
+
+/// The state `[a, b, c, d, e, f, g, h]` after one round.
+pub fn round(state: [u32; 8], t: usize, w: u32) -> [u32; 8] {
+    let [a, b, c, d, e, f, g, h] = state;
+    let t1 = h
+        .wrapping_add(big_sigma1(e))
+        .wrapping_add(ch(e, f, g))
+        .wrapping_add(ROUND_CONSTANTS[t])
+        .wrapping_add(w);
+    let t2 = big_sigma0(a).wrapping_add(maj(a, b, c));
+    [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g]
+}
+
+/// The SHA-256 compression function.
+pub fn compress(hash: &Digest, block: &Block) -> Digest {
+    let w = message_schedule(block);
+    let state = (0..64).fold(*hash, |state, t| round(state, t, w[t]));
+    std::array::from_fn(|i| hash[i].wrapping_add(state[i]))
+}
+
+/// Pads a message of at most [`MAX_SINGLE_BLOCK_LEN`] bytes into a single block.
+pub fn pad_single_block(message: &[u8]) -> Block {
+    assert!(
+        message.len() <= MAX_SINGLE_BLOCK_LEN,
+        "a {}-byte message does not fit in a single block",
+        message.len()
+    );
+    let mut bytes = [0u8; 64];
+    bytes[..message.len()].copy_from_slice(message);
+    bytes[message.len()] = 0x80;
+    bytes[56..].copy_from_slice(&(8 * message.len() as u64).to_be_bytes());
+    std::array::from_fn(|i| u32::from_be_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+/// SHA-256 of a message of at most [`MAX_SINGLE_BLOCK_LEN`] bytes.
+pub fn sha256_single_block(message: &[u8]) -> Digest {
+    compress(&INITIAL_HASH, &pad_single_block(message))
+}
+
+/// The block hashing a previous digest in [`chain`] mode.
+pub fn digest_block(digest: &Digest) -> Block {
+    let mut block = [0; 16];
+    block[..8].copy_from_slice(digest);
+    block[8] = 0x80000000;
+    block[15] = 256;
+    block
+}
+
+/// Hashes `input` `n` times: every hash but the first takes the previous digest as its message.
+pub fn chain(input: &[u8], n: usize) -> Digest {
+    assert!(n > 0, "a chain has at least one hash");
+    (1..n).fold(sha256_single_block(input), |digest, _| {
+        compress(&INITIAL_HASH, &digest_block(&digest))
+    })
+}
+
+pub fn to_hex(digest: &Digest) -> String {
+    digest.iter().map(|word| format!("{word:08x}")).collect()
+}
+
+/// `(message, SHA-256 digest)` test vectors from FIPS 180-4 and NIST's examples.
+pub const TEST_VECTORS: [(&str, &str); 3] = [
+    (
+        "",
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    ),
+    (
+        "abc",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    ),
+    (
+        "The quick brown fox jumps over the lazy dog",
+        "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592",
+    ),
+];
@@ -0,0 +1,302 @@
+//! The rounds: row `64 * i + t` consumes the state before round `t` of instance `i` and word
+//! `w[t]` of its schedule, and yields the state after the round.
+//!
+//! Σ0, Σ1, Ch and Maj are computed byte by byte in the tables, and the two additions of the round
+//! are constrained on 16-bit limbs with the carries as witness bits.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval},
+    core::fields::m31::M31,
+};
+
+use super::{
+    add_carries, limbs,
+    reference::{big_sigma0, big_sigma1, ch, maj, round, ROUND_CONSTANTS},
+    word_bytes, RoundColumn, RoundColumnKind, Sha256Elements, Sha256Relation, Sha256Row,
+};
+use crate::{
+    gadgets::{
+        bytes::{bitwise_bytes, bytes_to_limbs, check_bytes, split_bytes, ByteTables},
+        enabler::Enabler,
+    },
+    preprocessed::{BitwiseOp, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+pub struct RoundEval {
+    pub log_size: u32,
+    pub elements: Sha256Elements,
+}
+
+impl FrameworkEval for RoundEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let elements = &self.elements;
+        let enabled = Enabler::monotone(self.log_size).evaluate(&mut eval);
+        let numerator = E::EF::from(enabled.enabler.clone());
+        let [instance, t, k_lo, k_hi] = [
+            RoundColumnKind::Instance,
+            RoundColumnKind::Round,
+            RoundColumnKind::ConstantLow,
+            RoundColumnKind::ConstantHigh,
+        ]
+        .map(|kind| eval.get_preprocessed_column(RoundColumn::new(kind, self.log_size).id()));
+
+        let state: [Vec<E::F>; 8] =
+            std::array::from_fn(|_| (0..4).map(|_| eval.next_trace_mask()).collect());
+        let [w_lo, w_hi] = std::array::from_fn(|_| eval.next_trace_mask());
+        let values = chain!(
+            [instance.clone(), t.clone()],
+            state.iter().flat_map(|bytes| bytes_to_limbs(bytes))
+        )
+        .collect_vec();
+        enabled.add_to_relation(&mut eval, &elements.state, E::EF::one(), &values);
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.schedule,
+            E::EF::one(),
+            &[instance.clone(), t.clone(), w_lo.clone(), w_hi.clone()],
+        );
+        let [a, b, c, d, e, f, g, h] = state;
+
+        // Σ1(e) = (e >>> 6) ^ (e >>> 11) ^ (e >>> 25)
+        let [split6, split3, split1] = [6, 3, 1].map(|shift| {
+            split_bytes(
+                &mut eval,
+                &elements.byte_split,
+                numerator.clone(),
+                &e,
+                shift,
+            )
+        });
+        let x = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &split6.rotate_right(6),
+            &split3.rotate_right(11),
+        );
+        let sigma1 = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &x,
+            &split1.rotate_right(25),
+        );
+
+        // Ch(e, f, g) = (e & f) ^ (!e & g)
+        let e_and_f = bitwise_bytes(&mut eval, &elements.and, numerator.clone(), &e, &f);
+        let not_e = e
+            .iter()
+            .map(|byte| E::F::from(M31::from(255)) - byte.clone())
+            .collect_vec();
+        let not_e_and_g = bitwise_bytes(&mut eval, &elements.and, numerator.clone(), &not_e, &g);
+        let ch = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &e_and_f,
+            &not_e_and_g,
+        );
+
+        // Σ0(a) = (a >>> 2) ^ (a >>> 13) ^ (a >>> 22)
+        let [split2, split5, split6] = [2, 5, 6].map(|shift| {
+            split_bytes(
+                &mut eval,
+                &elements.byte_split,
+                numerator.clone(),
+                &a,
+                shift,
+            )
+        });
+        let x = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &split2.rotate_right(2),
+            &split5.rotate_right(13),
+        );
+        let sigma0 = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &x,
+            &split6.rotate_right(22),
+        );
+
+        // Maj(a, b, c) = (a & b) ^ (c & (a ^ b))
+        let a_and_b = bitwise_bytes(&mut eval, &elements.and, numerator.clone(), &a, &b);
+        let a_xor_b = bitwise_bytes(&mut eval, &elements.xor, numerator.clone(), &a, &b);
+        let c_and = bitwise_bytes(&mut eval, &elements.and, numerator.clone(), &c, &a_xor_b);
+        let maj = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &a_and_b,
+            &c_and,
+        );
+
+        let new_a = (0..4).map(|_| eval.next_trace_mask()).collect_vec();
+        let new_e = (0..4).map(|_| eval.next_trace_mask()).collect_vec();
+        check_bytes(
+            &mut eval,
+            &elements.byte_split,
+            numerator,
+            &chain!(new_a.clone(), new_e.clone()).collect_vec(),
+        );
+
+        // The 3-bit carries out of the low and the high limbs of new_e and new_a
+        let carry_bits = (0..12).map(|_| eval.next_trace_mask()).collect_vec();
+        for bit in &carry_bits {
+            eval.add_constraint(bit.clone() * (bit.clone() - E::F::one()));
+        }
+        let [e_carry_lo, e_carry_hi, a_carry_lo, a_carry_hi] = [0, 3, 6, 9].map(|i| {
+            carry_bits[i].clone()
+                + carry_bits[i + 1].clone() * M31::from(2)
+                + carry_bits[i + 2].clone() * M31::from(4)
+        });
+
+        // new_e = d + t1 and new_a = t1 + t2, with t1 = h + Σ1 + Ch + K + w and t2 = Σ0 + Maj
+        let [d, h, sigma1, ch, sigma0, maj, new_a_limbs, new_e_limbs] =
+            [&d, &h, &sigma1, &ch, &sigma0, &maj, &new_a, &new_e]
+                .map(|bytes| bytes_to_limbs(bytes));
+        let k = [k_lo, k_hi];
+        let w = [w_lo, w_hi];
+        let t1 = |i: usize| {
+            h[i].clone() + sigma1[i].clone() + ch[i].clone() + k[i].clone() + w[i].clone()
+        };
+        let t2 = |i: usize| sigma0[i].clone() + maj[i].clone();
+        let two_pow_16 = M31::from(1 << 16);
+        let enabler = enabled.enabler.clone();
+        eval.add_constraint(
+            enabler.clone()
+                * (d[0].clone() + t1(0) - new_e_limbs[0].clone() - e_carry_lo.clone() * two_pow_16),
+        );
+        eval.add_constraint(
+            enabler.clone()
+                * (d[1].clone() + t1(1) + e_carry_lo
+                    - new_e_limbs[1].clone()
+                    - e_carry_hi * two_pow_16),
+        );
+        eval.add_constraint(
+            enabler.clone()
+                * (t1(0) + t2(0) - new_a_limbs[0].clone() - a_carry_lo.clone() * two_pow_16),
+        );
+        eval.add_constraint(
+            enabler
+                * (t1(1) + t2(1) + a_carry_lo - new_a_limbs[1].clone() - a_carry_hi * two_pow_16),
+        );
+
+        let values = chain!(
+            [instance, t + E::F::one()],
+            [&new_a, &a, &b, &c, &new_e, &e, &f, &g]
+                .into_iter()
+                .flat_map(|bytes| bytes_to_limbs(bytes))
+        )
+        .collect_vec();
+        enabled.add_to_relation(&mut eval, &elements.state, -E::EF::one(), &values);
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates round `t` of instance `instance` from the state before the round, and returns it
+/// with the state after the round.
+pub fn gen_round_row(
+    tables: &mut ByteTables,
+    instance: usize,
+    t: usize,
+    state: [u32; 8],
+    w: u32,
+) -> (Sha256Row, [u32; 8]) {
+    let mut row = Sha256Row::new();
+    let [a, b, c, d, e, f, g, h] = state;
+    let [instance_value, t_value] = [instance, t].map(M31::from);
+
+    for word in state {
+        row.push(&word_bytes(word));
+    }
+    let [w_lo, w_hi] = limbs(w);
+    row.columns.extend([w_lo, w_hi]);
+    row.entry(
+        Sha256Relation::State,
+        M31::one(),
+        chain!([instance_value, t_value], state.into_iter().flat_map(limbs)).collect(),
+    );
+    row.entry(
+        Sha256Relation::Schedule,
+        M31::one(),
+        vec![instance_value, t_value, w_lo, w_hi],
+    );
+
+    // Σ1(e)
+    for shift in [6, 3, 1] {
+        row.split(tables, &word_bytes(e), shift);
+    }
+    let x = row.bitwise(
+        tables,
+        BitwiseOp::Xor,
+        &word_bytes(e.rotate_right(6)),
+        &word_bytes(e.rotate_right(11)),
+    );
+    row.bitwise(tables, BitwiseOp::Xor, &x, &word_bytes(e.rotate_right(25)));
+
+    // Ch(e, f, g)
+    let e_and_f = row.bitwise(tables, BitwiseOp::And, &word_bytes(e), &word_bytes(f));
+    let not_e_and_g = row.bitwise(tables, BitwiseOp::And, &word_bytes(!e), &word_bytes(g));
+    row.bitwise(tables, BitwiseOp::Xor, &e_and_f, &not_e_and_g);
+
+    // Σ0(a)
+    for shift in [2, 5, 6] {
+        row.split(tables, &word_bytes(a), shift);
+    }
+    let x = row.bitwise(
+        tables,
+        BitwiseOp::Xor,
+        &word_bytes(a.rotate_right(2)),
+        &word_bytes(a.rotate_right(13)),
+    );
+    row.bitwise(tables, BitwiseOp::Xor, &x, &word_bytes(a.rotate_right(22)));
+
+    // Maj(a, b, c)
+    let a_and_b = row.bitwise(tables, BitwiseOp::And, &word_bytes(a), &word_bytes(b));
+    let a_xor_b = row.bitwise(tables, BitwiseOp::Xor, &word_bytes(a), &word_bytes(b));
+    let c_and = row.bitwise(tables, BitwiseOp::And, &word_bytes(c), &a_xor_b);
+    row.bitwise(tables, BitwiseOp::Xor, &a_and_b, &c_and);
+
+    let next_state = round(state, t, w);
+    let [new_a, new_e] = [next_state[0], next_state[4]].map(word_bytes);
+    row.push(&new_a);
+    row.push(&new_e);
+    row.check(tables, &chain!(new_a, new_e).collect_vec());
+
+    let t1 = [h, big_sigma1(e), ch(e, f, g), ROUND_CONSTANTS[t], w];
+    let t2 = [big_sigma0(a), maj(a, b, c)];
+    let (e_carry_lo, e_carry_hi) = add_carries(&chain!([d], t1).collect_vec());
+    let (a_carry_lo, a_carry_hi) = add_carries(&chain!(t1, t2).collect_vec());
+    for carry in [e_carry_lo, e_carry_hi, a_carry_lo, a_carry_hi] {
+        row.push(&[carry & 1, (carry >> 1) & 1, carry >> 2]);
+    }
+
+    row.entry(
+        Sha256Relation::State,
+        -M31::one(),
+        chain!(
+            [instance_value, t_value + M31::one()],
+            next_state.into_iter().flat_map(limbs)
+        )
+        .collect(),
+    );
+    (row, next_state)
+}
@@ -0,0 +1,214 @@
+//! The message schedule: row `64 * i + t` holds word `w[t]` of instance `i`.
+//!
+//! The first 16 words are consumed from the block, the others are constrained to
+//! `w[t] = σ1(w[t - 2]) + w[t - 7] + σ0(w[t - 15]) + w[t - 16]` through masks on the previous
+//! rows of the same instance. Every word is yielded to the rounds.
+
+use itertools::{chain, Itertools};
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval, RelationEntry, ORIGINAL_TRACE_IDX},
+    core::fields::m31::M31,
+};
+
+use super::{
+    add_carries, limbs,
+    reference::{small_sigma0, small_sigma1},
+    word_bytes, RoundColumn, RoundColumnKind, Sha256Elements, Sha256Relation, Sha256Row,
+};
+use crate::{
+    airs::bitwise::BitwiseElements,
+    gadgets::{
+        bytes::{bitwise_bytes, bytes_to_limbs, split_bytes, ByteTables},
+        enabler::Enabler,
+    },
+    preprocessed::{BitwiseOp, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+pub struct ScheduleEval {
+    pub log_size: u32,
+    pub elements: Sha256Elements,
+}
+
+/// Like [`bitwise_bytes`] for XOR, but also returns the result on the row `offset` rows before.
+fn xor_bytes_with_offset<E: EvalAtRow>(
+    eval: &mut E,
+    elements: &BitwiseElements,
+    numerator: E::EF,
+    a: &[E::F],
+    b: &[E::F],
+    offset: isize,
+) -> Vec<E::F> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let [result_offset_row, result] =
+                eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [offset, 0]);
+            eval.add_to_relation(RelationEntry::new(
+                elements,
+                numerator.clone(),
+                &[a.clone(), b.clone(), result],
+            ));
+            result_offset_row
+        })
+        .collect()
+}
+
+impl FrameworkEval for ScheduleEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let elements = &self.elements;
+        let enabled = Enabler::monotone(self.log_size).evaluate(&mut eval);
+        let numerator = E::EF::from(enabled.enabler.clone());
+        let [instance, t, is_expanded] = [
+            RoundColumnKind::Instance,
+            RoundColumnKind::Round,
+            RoundColumnKind::IsExpanded,
+        ]
+        .map(|kind| eval.get_preprocessed_column(RoundColumn::new(kind, self.log_size).id()));
+
+        let words = (0..4)
+            .map(|_| eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-16, -7, 0]))
+            .collect_vec();
+        let [w_16, w_7, w] =
+            std::array::from_fn(|i| words.iter().map(|masks| masks[i].clone()).collect_vec());
+
+        // The splits of w for the rotations and shifts of σ0 and σ1
+        let [split7, split2, split3, split1] = [7, 2, 3, 1].map(|shift| {
+            split_bytes(
+                &mut eval,
+                &elements.byte_split,
+                numerator.clone(),
+                &w,
+                shift,
+            )
+        });
+
+        // σ0(w) = (w >>> 7) ^ (w >>> 18) ^ (w >> 3), used 15 rows later
+        let x = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &split7.rotate_right(7),
+            &split2.rotate_right(18),
+        );
+        let sigma0_15 = xor_bytes_with_offset(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &x,
+            &split3.shift_right(3),
+            -15,
+        );
+        // σ1(w) = (w >>> 17) ^ (w >>> 19) ^ (w >> 10), used 2 rows later
+        let x = bitwise_bytes(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &split1.rotate_right(17),
+            &split3.rotate_right(19),
+        );
+        let sigma1_2 = xor_bytes_with_offset(
+            &mut eval,
+            &elements.xor,
+            numerator.clone(),
+            &x,
+            &split2.shift_right(10),
+            -2,
+        );
+
+        // The 2-bit carries out of the low and the high limbs
+        let carry_bits = (0..4).map(|_| eval.next_trace_mask()).collect_vec();
+        for bit in &carry_bits {
+            eval.add_constraint(bit.clone() * (bit.clone() - E::F::one()));
+        }
+        let [carry_lo, carry_hi] =
+            [0, 2].map(|i| carry_bits[i].clone() + carry_bits[i + 1].clone() * M31::from(2));
+
+        let [w_16, w_7, sigma0_15, sigma1_2, w] =
+            [w_16, w_7, sigma0_15, sigma1_2, w].map(|bytes| bytes_to_limbs(&bytes));
+        let sum = |i: usize| {
+            sigma1_2[i].clone() + w_7[i].clone() + sigma0_15[i].clone() + w_16[i].clone()
+        };
+        eval.add_constraint(
+            is_expanded.clone() * (sum(0) - w[0].clone() - carry_lo.clone() * M31::from(1 << 16)),
+        );
+        eval.add_constraint(
+            is_expanded.clone()
+                * (sum(1) + carry_lo - w[1].clone() - carry_hi * M31::from(1 << 16)),
+        );
+
+        let values = chain!([instance, t], w).collect_vec();
+        enabled.add_to_relation(
+            &mut eval,
+            &elements.message,
+            E::EF::from(E::F::one() - is_expanded),
+            &values,
+        );
+        enabled.add_to_relation(&mut eval, &elements.schedule, -E::EF::one(), &values);
+
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generates row `t` of instance `instance`, with the words `w` of its message schedule.
+pub fn gen_schedule_row(
+    tables: &mut ByteTables,
+    instance: usize,
+    t: usize,
+    w: &[u32; 64],
+) -> Sha256Row {
+    let mut row = Sha256Row::new();
+    let word = w[t];
+    let bytes = word_bytes(word);
+    row.push(&bytes);
+    for shift in [7, 2, 3, 1] {
+        row.split(tables, &bytes, shift);
+    }
+
+    let x = row.bitwise(
+        tables,
+        BitwiseOp::Xor,
+        &word_bytes(word.rotate_right(7)),
+        &word_bytes(word.rotate_right(18)),
+    );
+    row.bitwise(tables, BitwiseOp::Xor, &x, &word_bytes(word >> 3));
+    let x = row.bitwise(
+        tables,
+        BitwiseOp::Xor,
+        &word_bytes(word.rotate_right(17)),
+        &word_bytes(word.rotate_right(19)),
+    );
+    row.bitwise(tables, BitwiseOp::Xor, &x, &word_bytes(word >> 10));
+
+    let (carry_lo, carry_hi) = if t < 16 {
+        (0, 0)
+    } else {
+        add_carries(&[
+            small_sigma1(w[t - 2]),
+            w[t - 7],
+            small_sigma0(w[t - 15]),
+            w[t - 16],
+        ])
+    };
+    row.push(&[carry_lo & 1, carry_lo >> 1, carry_hi & 1, carry_hi >> 1]);
+
+    let values = chain!([M31::from(instance), M31::from(t)], limbs(word)).collect_vec();
+    row.entry(
+        Sha256Relation::Message,
+        M31::from((t < 16) as u32),
+        values.clone(),
+    );
+    row.entry(Sha256Relation::Schedule, -M31::one(), values);
+    row
+}
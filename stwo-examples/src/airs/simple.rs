@@ -0,0 +1,176 @@
+//! The AIR from "Proving and Verifying an AIR": `col_1 * col_2 + col_1 - col_3 = 0`.
+
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval},
+    core::{
+        air::{Component, ComponentProver},
+        backend::{
+            simd::{column::BaseColumn, SimdBackend},
+            Column,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        ColumnVec,
+    },
+};
+
+use super::LogSizeStatement;
+use crate::{
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::registry::PreprocessedRegistry,
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+// ANCHOR: simple_eval
+pub struct SimpleEval {
+    pub log_size: u32,
+}
+
+impl FrameworkEval for SimpleEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let col_1 = eval.next_trace_mask();
+        let col_2 = eval.next_trace_mask();
+        let col_3 = eval.next_trace_mask();
+        eval.add_constraint(col_1.clone() * col_2.clone() + col_1.clone() - col_3.clone());
+        eval
+    }
+}
+// ANCHOR_END: simple_eval
+
+pub type SimpleComponent = FrameworkComponent<SimpleEval>;
+
+pub struct SimpleComponents {
+    pub component: SimpleComponent,
+}
+
+// ANCHOR: simple_components
+impl SimpleComponents {
+    pub fn new(statement0: &LogSizeStatement) -> Self {
+        Self {
+            component: PreprocessedRegistry::new().component_builder().build(
+                SimpleEval {
+                    log_size: statement0.log_size,
+                },
+                SecureField::zero(),
+            ),
+        }
+    }
+}
+// ANCHOR_END: simple_components
+
+impl AirComponents for SimpleComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct SimpleAir {
+    pub log_size: u32,
+}
+
+impl Air for SimpleAir {
+    const NAME: &'static str = "simple";
+    const COMPONENT_NAMES: &'static [&'static str] = &["simple"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ();
+    type LookupElements = ();
+    type Components = SimpleComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(_channel: &mut impl Channel) -> Self::LookupElements {}
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        SimpleComponents::new(statement0)
+            .component
+            .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(_statement1: &Self::Statement1) -> SecureField {
+        SecureField::zero()
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        _lookup_elements: &Self::LookupElements,
+        _statement1: &Self::Statement1,
+    ) -> Self::Components {
+        SimpleComponents::new(statement0)
+    }
+}
+
+impl AirProver for SimpleAir {
+    type Witness = ();
+
+    // ANCHOR: gen_trace
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let num_rows = 1 << self.log_size;
+
+        // Create the table
+        let mut col_1 = BaseColumn::zeros(num_rows);
+        col_1.set(0, M31::from(1));
+        col_1.set(1, M31::from(7));
+
+        let mut col_2 = BaseColumn::zeros(num_rows);
+        col_2.set(0, M31::from(5));
+        col_2.set(1, M31::from(11));
+
+        let mut col_3 = BaseColumn::zeros(num_rows);
+        col_3.set(0, col_1.at(0) * col_2.at(0) + col_1.at(0));
+        col_3.set(1, col_1.at(1) * col_2.at(1) + col_1.at(1));
+
+        // Convert table to trace polynomials
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        let trace = vec![col_1, col_2, col_3]
+            .into_iter()
+            .map(|col| CircleEvaluation::new(domain, col))
+            .collect();
+
+        (
+            LogSizeStatement {
+                log_size: self.log_size,
+            },
+            trace,
+            (),
+        )
+    }
+    // ANCHOR_END: gen_trace
+
+    fn gen_interaction_trace(
+        &self,
+        _witness: &Self::Witness,
+        _lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        ((), vec![])
+    }
+}
@@ -0,0 +1,282 @@
+//! The AIR from "Static Lookups": two columns range-checked against a preprocessed 4-bit table.
+//!
+//! When the trace is larger than the table, the table is repeated to fill it, and the repeated
+//! rows have a multiplicity of 0.
+
+use num_traits::One;
+use rand::Rng;
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, EvalAtRow,
+        FrameworkComponent, FrameworkEval, Relation, RelationEntry,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::{
+            simd::{column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend},
+            Column,
+        },
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+
+use super::{ClaimedSumStatement, LogSizeStatement};
+use crate::{
+    pipeline::{Air, AirComponents, AirProver},
+    preprocessed::{registry::PreprocessedRegistry, PreprocessedColumn, RangeCheck},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Log size of the range-check table, which is also the smallest trace size.
+pub const RANGE_LOG_SIZE: u32 = LOG_N_LANES;
+
+// ANCHOR: relation
+relation!(SmallerThan16Elements, 1);
+// ANCHOR_END: relation
+
+pub fn preprocessed_registry(log_size: u32) -> PreprocessedRegistry {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(RangeCheck::repeated(RANGE_LOG_SIZE, log_size));
+    registry
+}
+
+// ANCHOR: static_lookups_eval
+pub struct StaticLookupsEval {
+    pub range_check_id: PreProcessedColumnId,
+    pub log_size: u32,
+    pub lookup_elements: SmallerThan16Elements,
+}
+
+impl FrameworkEval for StaticLookupsEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let range_check_col = eval.get_preprocessed_column(self.range_check_id.clone());
+
+        let lookup_col_1 = eval.next_trace_mask();
+        let lookup_col_2 = eval.next_trace_mask();
+        let multiplicity_col = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::from(multiplicity_col),
+            &[range_check_col],
+        ));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            E::EF::one(),
+            &[lookup_col_1],
+        ));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            E::EF::one(),
+            &[lookup_col_2],
+        ));
+
+        eval.finalize_logup_batched(&vec![0, 1, 1]);
+
+        eval
+    }
+}
+// ANCHOR_END: static_lookups_eval
+
+pub type StaticLookupsComponent = FrameworkComponent<StaticLookupsEval>;
+
+pub struct StaticLookupsComponents {
+    pub component: StaticLookupsComponent,
+}
+
+impl StaticLookupsComponents {
+    pub fn new(
+        statement0: &LogSizeStatement,
+        lookup_elements: &SmallerThan16Elements,
+        statement1: &ClaimedSumStatement,
+    ) -> Self {
+        Self {
+            component: preprocessed_registry(statement0.log_size)
+                .component_builder()
+                .build(
+                    StaticLookupsEval {
+                        range_check_id: RangeCheck::repeated(RANGE_LOG_SIZE, statement0.log_size)
+                            .id(),
+                        log_size: statement0.log_size,
+                        lookup_elements: lookup_elements.clone(),
+                    },
+                    statement1.claimed_sum,
+                ),
+        }
+    }
+}
+
+impl AirComponents for StaticLookupsComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+pub struct StaticLookupsAir {
+    pub log_size: u32,
+}
+
+impl Air for StaticLookupsAir {
+    const NAME: &'static str = "static_lookups";
+    const COMPONENT_NAMES: &'static [&'static str] = &["static_lookups"];
+
+    type Statement0 = LogSizeStatement;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = SmallerThan16Elements;
+    type Components = StaticLookupsComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        statement0.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        SmallerThan16Elements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        preprocessed_registry(statement0.log_size)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        StaticLookupsComponents::new(
+            statement0,
+            &SmallerThan16Elements::dummy(),
+            &ClaimedSumStatement {
+                claimed_sum: SecureField::one(),
+            },
+        )
+        .component
+        .trace_log_degree_bounds()
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        StaticLookupsComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for StaticLookupsAir {
+    /// The range-check column followed by the original trace columns.
+    type Witness = Vec<BaseColumn>;
+
+    // ANCHOR: gen_trace
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let log_size = self.log_size;
+
+        // Create a table with random values
+        let mut rng = rand::thread_rng();
+        let lookup_col_1 =
+            BaseColumn::from_iter((0..(1 << log_size)).map(|_| M31::from(rng.gen_range(0..16))));
+        let lookup_col_2 =
+            BaseColumn::from_iter((0..(1 << log_size)).map(|_| M31::from(rng.gen_range(0..16))));
+
+        // The values are counted on the first rows of the table, so the repeated rows stay at 0
+        let mut multiplicity_col = BaseColumn::zeros(1 << log_size);
+        lookup_col_1
+            .as_slice()
+            .iter()
+            .chain(lookup_col_2.as_slice().iter())
+            .for_each(|value| {
+                let index = value.0 as usize;
+                multiplicity_col.set(index, multiplicity_col.at(index) + M31::from(1));
+            });
+
+        // The range-check table is in bit-reversed circle domain order, so the multiplicities must
+        // be too
+        bit_reverse_coset_to_circle_domain_order(multiplicity_col.as_mut_slice());
+
+        // Convert table to trace polynomials
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let columns = vec![lookup_col_1, lookup_col_2, multiplicity_col];
+        let trace = columns
+            .iter()
+            .map(|col| CircleEvaluation::new(domain, col.clone()))
+            .collect();
+
+        let range_check_col = RangeCheck::repeated(RANGE_LOG_SIZE, log_size)
+            .gen_column()
+            .values;
+        let witness = [vec![range_check_col], columns].concat();
+
+        (LogSizeStatement { log_size }, trace, witness)
+    }
+    // ANCHOR_END: gen_trace
+
+    // ANCHOR: gen_interaction_trace
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        let [range_check_col, lookup_col_1, lookup_col_2, multiplicity_col] = &witness[..] else {
+            panic!("unexpected witness layout");
+        };
+        let mut logup_gen = LogupTraceGenerator::new(self.log_size);
+
+        let mut col_gen = logup_gen.new_col();
+        for simd_row in 0..(1 << (self.log_size - LOG_N_LANES)) {
+            let numerator: PackedSecureField =
+                PackedSecureField::from(multiplicity_col.data[simd_row]);
+            let denom: PackedSecureField =
+                lookup_elements.combine(&[range_check_col.data[simd_row]]);
+            col_gen.write_frac(simd_row, -numerator, denom);
+        }
+        col_gen.finalize_col();
+
+        let mut col_gen = logup_gen.new_col();
+        for simd_row in 0..(1 << (self.log_size - LOG_N_LANES)) {
+            let lookup_col_1_val: PackedSecureField =
+                lookup_elements.combine(&[lookup_col_1.data[simd_row]]);
+            let lookup_col_2_val: PackedSecureField =
+                lookup_elements.combine(&[lookup_col_2.data[simd_row]]);
+            // 1 / denom1 + 1 / denom2 = (denom1 + denom2) / (denom1 * denom2)
+            let numerator = lookup_col_1_val + lookup_col_2_val;
+            let denom = lookup_col_1_val * lookup_col_2_val;
+            col_gen.write_frac(simd_row, numerator, denom);
+        }
+        col_gen.finalize_col();
+
+        let (logup_cols, claimed_sum) = logup_gen.finalize_last();
+        (ClaimedSumStatement { claimed_sum }, logup_cols)
+    }
+    // ANCHOR_END: gen_interaction_trace
+}
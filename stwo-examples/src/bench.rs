@@ -0,0 +1,168 @@
+//! Measures the metrics of the Benchmarks Report for an AIR: prover time, verifier time, proof
+//! size and peak memory.
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use stwo_prover::core::{
+    backend::{simd::SimdBackend, BackendForChannel},
+    channel::MerkleChannel,
+    pcs::PcsConfig,
+    prover::StarkProof,
+};
+use thiserror::Error;
+
+use crate::{
+    pipeline::{prove_air, verify_air, AirProver},
+    proof::{ChannelName, ConfigHeader, ProofEnvelope, ProofFormatError},
+};
+
+/// One row of the Benchmarks Report tables, in the units used there.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchMetrics {
+    pub benchmark: String,
+    /// The size of the workload, e.g. the number of Fibonacci steps.
+    pub n: usize,
+    pub prover_time_s: f64,
+    pub verifier_time_ms: f64,
+    /// The size of the STARK proof in the binary format of [`ProofEnvelope`], without the header
+    /// and the statements.
+    pub proof_size_kb: f64,
+    /// The size of the whole proof file in the binary format: the STARK proof with the header and
+    /// the statements.
+    pub file_size_kb: f64,
+    /// The peak resident set size while proving and verifying, or of the whole process so far
+    /// where it cannot be reset. `None` where it is not available.
+    pub peak_memory_gb: Option<f64>,
+}
+
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error("proving failed: {0}")]
+    Proving(String),
+    #[error("verification failed: {0}")]
+    Verification(String),
+    #[error(transparent)]
+    Format(#[from] ProofFormatError),
+}
+
+/// Proves and verifies `air` once and measures it. `n` is the size of the workload reported
+/// alongside the metrics.
+pub fn measure<A: AirProver, MC: MerkleChannel + ChannelName>(
+    air: &A,
+    n: usize,
+    config: PcsConfig,
+) -> Result<BenchMetrics, BenchError>
+where
+    SimdBackend: BackendForChannel<MC>,
+    A::Statement0: Serialize + DeserializeOwned,
+    A::Statement1: Serialize + DeserializeOwned,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    reset_peak_memory();
+    let (proof, prover_time) = timed(|| prove_air::<A, MC>(air, config));
+    let proof = proof.map_err(|err| BenchError::Proving(err.to_string()))?;
+
+    let envelope = ProofEnvelope::<A, MC>::new(proof, config);
+    let proof_size = envelope.stark_proof_size()?;
+    let file_size = envelope.to_bytes()?.len();
+
+    let (result, verifier_time) = timed(|| verify_air::<A, MC>(envelope.into_proof(), config));
+    result.map_err(|err| BenchError::Verification(err.to_string()))?;
+
+    Ok(BenchMetrics {
+        benchmark: A::NAME.to_string(),
+        n,
+        prover_time_s: prover_time.as_secs_f64(),
+        verifier_time_ms: verifier_time.as_secs_f64() * 1e3,
+        proof_size_kb: proof_size as f64 / 1024.,
+        file_size_kb: file_size as f64 / 1024.,
+        peak_memory_gb: peak_memory_bytes().map(|bytes| bytes as f64 / (1 << 30) as f64),
+    })
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+/// Resets the peak resident set size of the process to its current size, where Linux allows it.
+/// Returns whether it was reset.
+pub fn reset_peak_memory() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// The peak resident set size of the process, read from `/proc/self/status`, so only available on
+/// Linux.
+pub fn peak_memory_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// A measurement of a sweep: the metrics and the parameters they were measured with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    #[serde(flatten)]
+    pub metrics: BenchMetrics,
+    pub log_size: u32,
+    pub channel: String,
+    #[serde(flatten)]
+    pub config: ConfigHeader,
+}
+
+/// The columns of [`to_csv`], in order.
+pub const CSV_COLUMNS: [&str; 13] = [
+    "benchmark",
+    "n",
+    "log_size",
+    "channel",
+    "pow_bits",
+    "log_blowup_factor",
+    "log_last_layer_degree_bound",
+    "n_queries",
+    "prover_time_s",
+    "verifier_time_ms",
+    "proof_size_kb",
+    "file_size_kb",
+    "peak_memory_gb",
+];
+
+/// Formats `records` as CSV with a header row. A missing peak memory is an empty field.
+pub fn to_csv(records: &[BenchRecord]) -> String {
+    let rows = records.iter().map(|record| {
+        let BenchRecord {
+            metrics,
+            log_size,
+            channel,
+            config,
+        } = record;
+        [
+            metrics.benchmark.clone(),
+            metrics.n.to_string(),
+            log_size.to_string(),
+            channel.clone(),
+            config.pow_bits.to_string(),
+            config.log_blowup_factor.to_string(),
+            config.log_last_layer_degree_bound.to_string(),
+            config.n_queries.to_string(),
+            metrics.prover_time_s.to_string(),
+            metrics.verifier_time_ms.to_string(),
+            metrics.proof_size_kb.to_string(),
+            metrics.file_size_kb.to_string(),
+            metrics
+                .peak_memory_gb
+                .map_or(String::new(), |gb| gb.to_string()),
+        ]
+        .join(",")
+    });
+    std::iter::once(CSV_COLUMNS.join(","))
+        .chain(rows)
+        .map(|row| row + "\n")
+        .collect()
+}
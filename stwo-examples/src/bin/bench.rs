@@ -0,0 +1,225 @@
+//! Measures example AIRs over a sweep of log sizes and PCS configs, and writes the metrics of the
+//! Benchmarks Report as CSV or JSON.
+//!
+//! Usage: `bench [--airs <names>|all] [--log-sizes <sizes>] [--log-blowup-factors <values>]
+//! [--n-queries <values>] [--pow-bits <values>] [--channel blake2s|poseidon252] [--csv <file>]
+//! [--json <file>]`
+//!
+//! Every list is comma-separated, and log sizes also accept inclusive ranges such as `10..=14`.
+//! Every AIR is measured at every log size and every combination of the config values, the values
+//! left out defaulting to [`PcsConfig::default`]. Without `--csv` or `--json`, the JSON is printed.
+
+use std::{env, fs, marker::PhantomData, process::ExitCode, str::FromStr};
+
+use itertools::iproduct;
+use serde::{de::DeserializeOwned, Serialize};
+use stwo_examples::{
+    airs::catalog::{visit_air, AirVisitor, AIR_NAMES},
+    bench::{measure, to_csv, BenchRecord},
+    cli::MAX_LOG_SIZE,
+    pipeline::AirProver,
+    proof::{ChannelName, ConfigHeader},
+};
+use stwo_prover::core::{
+    backend::{
+        simd::{m31::LOG_N_LANES, SimdBackend},
+        BackendForChannel,
+    },
+    channel::MerkleChannel,
+    pcs::PcsConfig,
+    prover::StarkProof,
+    vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+};
+
+const USAGE: &str = "usage: bench [--airs <names>|all] [--log-sizes <sizes>] \
+    [--log-blowup-factors <values>] [--n-queries <values>] [--pow-bits <values>] \
+    [--channel blake2s|poseidon252] [--csv <file>] [--json <file>]";
+
+struct Sweep {
+    airs: Vec<String>,
+    log_sizes: Vec<u32>,
+    configs: Vec<PcsConfig>,
+    channel: String,
+    csv: Option<String>,
+    json: Option<String>,
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{flag} expects numbers, got `{value}`"))
+}
+
+fn parse_list<T: FromStr>(flag: &str, value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|item| parse_number(flag, item))
+        .collect()
+}
+
+/// Parses a list of log sizes and inclusive ranges of log sizes.
+fn parse_log_sizes(value: &str) -> Result<Vec<u32>, String> {
+    let mut log_sizes = vec![];
+    for item in value.split(',') {
+        match item.split_once("..=") {
+            Some((start, end)) => log_sizes
+                .extend(parse_number("--log-sizes", start)?..=parse_number("--log-sizes", end)?),
+            None => log_sizes.push(parse_number("--log-sizes", item)?),
+        }
+    }
+    if let Some(log_size) = log_sizes
+        .iter()
+        .find(|log_size| !(LOG_N_LANES..=MAX_LOG_SIZE).contains(log_size))
+    {
+        return Err(format!(
+            "log sizes must be between {LOG_N_LANES} and {MAX_LOG_SIZE}, got {log_size}"
+        ));
+    }
+    Ok(log_sizes)
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Sweep, String> {
+    let default = ConfigHeader::from(PcsConfig::default());
+    let mut sweep = Sweep {
+        airs: AIR_NAMES.map(String::from).to_vec(),
+        log_sizes: vec![LOG_N_LANES],
+        configs: vec![],
+        channel: Blake2sMerkleChannel::NAME.to_string(),
+        csv: None,
+        json: None,
+    };
+    let mut log_blowup_factors = vec![default.log_blowup_factor];
+    let mut n_queries = vec![default.n_queries];
+    let mut pow_bits = vec![default.pow_bits];
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(format!("{flag} expects a value"))?;
+        match flag.as_str() {
+            "--airs" if value == "all" => {}
+            "--airs" => sweep.airs = value.split(',').map(String::from).collect(),
+            "--log-sizes" => sweep.log_sizes = parse_log_sizes(&value)?,
+            "--log-blowup-factors" => log_blowup_factors = parse_list(&flag, &value)?,
+            "--n-queries" => n_queries = parse_list(&flag, &value)?,
+            "--pow-bits" => pow_bits = parse_list(&flag, &value)?,
+            "--channel" => sweep.channel = value,
+            "--csv" => sweep.csv = Some(value),
+            "--json" => sweep.json = Some(value),
+            _ => return Err(format!("unknown argument `{flag}`")),
+        }
+    }
+    if let Some(air) = sweep
+        .airs
+        .iter()
+        .find(|air| !AIR_NAMES.contains(&air.as_str()))
+    {
+        return Err(format!("unknown AIR `{air}`"));
+    }
+
+    sweep.configs = iproduct!(log_blowup_factors, n_queries, pow_bits)
+        .map(|(log_blowup_factor, n_queries, pow_bits)| {
+            ConfigHeader {
+                log_blowup_factor,
+                n_queries,
+                pow_bits,
+                ..default
+            }
+            .into()
+        })
+        .collect();
+    Ok(sweep)
+}
+
+struct Measure<MC> {
+    log_size: u32,
+    config: PcsConfig,
+    channel: PhantomData<MC>,
+}
+
+impl<MC: MerkleChannel + ChannelName> AirVisitor for Measure<MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    type Output = Result<BenchRecord, String>;
+
+    fn visit<A: AirProver>(self, air: &A, n: usize) -> Self::Output
+    where
+        A::Statement0: Serialize + DeserializeOwned,
+        A::Statement1: Serialize + DeserializeOwned,
+    {
+        let metrics = measure::<A, MC>(air, n, self.config).map_err(|err| err.to_string())?;
+        Ok(BenchRecord {
+            metrics,
+            log_size: self.log_size,
+            channel: MC::NAME.to_string(),
+            config: self.config.into(),
+        })
+    }
+}
+
+fn run_sweep<MC: MerkleChannel + ChannelName>(sweep: &Sweep) -> Result<Vec<BenchRecord>, String>
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    iproduct!(&sweep.airs, &sweep.log_sizes, &sweep.configs)
+        .map(|(air, &log_size, &config)| {
+            let visitor = Measure::<MC> {
+                log_size,
+                config,
+                channel: PhantomData,
+            };
+            let record = visit_air(air, log_size, visitor)?
+                .map_err(|err| format!("{air} at log size {log_size}: {err}"))?;
+            eprintln!(
+                "{air} log_size={log_size} n={}: prover {:.3}s, verifier {:.3}ms, proof {:.1}KB \
+                 (file {:.1}KB)",
+                record.metrics.n,
+                record.metrics.prover_time_s,
+                record.metrics.verifier_time_ms,
+                record.metrics.proof_size_kb,
+                record.metrics.file_size_kb,
+            );
+            Ok(record)
+        })
+        .collect()
+}
+
+fn run(sweep: &Sweep) -> Result<(), String> {
+    let records = match sweep.channel.as_str() {
+        Blake2sMerkleChannel::NAME => run_sweep::<Blake2sMerkleChannel>(sweep)?,
+        Poseidon252MerkleChannel::NAME => run_sweep::<Poseidon252MerkleChannel>(sweep)?,
+        channel => return Err(format!("unknown channel `{channel}`")),
+    };
+
+    let json = serde_json::to_string_pretty(&records).map_err(|err| err.to_string())?;
+    if let Some(path) = &sweep.csv {
+        fs::write(path, to_csv(&records)).map_err(|err| format!("{path}: {err}"))?;
+    }
+    if let Some(path) = &sweep.json {
+        fs::write(path, json).map_err(|err| format!("{path}: {err}"))?;
+    } else if sweep.csv.is_none() {
+        println!("{json}");
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let sweep = match parse_args(env::args().skip(1)) {
+        Ok(sweep) => sweep,
+        Err(err) => {
+            eprintln!("error: {err}");
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(&sweep) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Proves one of the example AIRs and writes the proof to a file.
+//!
+//! Usage: `stwo-prove <air> <proof-file> [--log-size <n>] [--channel blake2s|poseidon252]
+//! [--pow-bits <n>] [--log-blowup-factor <n>] [--n-queries <n>]`
+//!
+//! The log size must be between `LOG_N_LANES` and `cli::MAX_LOG_SIZE`, and defaults to the former.
+//! The PCS config defaults to `PcsConfig::default`, which `stwo-verify` also expects unless given
+//! the same flags.
+//!
+//! Proofs are written as JSON if `<proof-file>` ends in `.json`, and in the binary format
+//! otherwise.
+
+use std::{
+    env,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use stwo_examples::{
+    airs::catalog::{visit_air, AirVisitor},
+    cli::{parse_config, parse_log_size},
+    pipeline::{prove_air, AirProver},
+    proof::{ChannelName, ProofEnvelope, ProofFormat},
+};
+use stwo_prover::core::{
+    backend::{simd::SimdBackend, BackendForChannel},
+    channel::MerkleChannel,
+    pcs::PcsConfig,
+    prover::StarkProof,
+    vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+};
+
+const USAGE: &str = "usage: stwo-prove <air> <proof-file> [--log-size <n>] \
+                     [--channel blake2s|poseidon252] [--pow-bits <n>] \
+                     [--log-blowup-factor <n>] [--n-queries <n>]";
+
+struct ProveToFile<'a, MC> {
+    path: &'a Path,
+    config: PcsConfig,
+    channel: PhantomData<MC>,
+}
+
+impl<MC: MerkleChannel + ChannelName> AirVisitor for ProveToFile<'_, MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    type Output = Result<(), String>;
+
+    fn visit<A: AirProver>(self, air: &A, _n: usize) -> Self::Output
+    where
+        A::Statement0: Serialize + DeserializeOwned,
+        A::Statement1: Serialize + DeserializeOwned,
+    {
+        let config = self.config;
+        let proof =
+            prove_air::<A, MC>(air, config).map_err(|err| format!("proving failed: {err}"))?;
+        ProofEnvelope::<A, MC>::new(proof, config)
+            .write_to_file(self.path, ProofFormat::from_path(self.path))
+            .map_err(|err| err.to_string())?;
+        println!("wrote {} proof to {}", A::NAME, self.path.display());
+        Ok(())
+    }
+}
+
+fn prove_named<MC: MerkleChannel + ChannelName>(
+    air: &str,
+    log_size: u32,
+    path: &Path,
+    config: PcsConfig,
+) -> Result<(), String>
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    let visitor = ProveToFile::<MC> {
+        path,
+        config,
+        channel: PhantomData,
+    };
+    visit_air(air, log_size, visitor)?
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (mut positional, mut channel) = (vec![], "blake2s".to_string());
+    let parsed = parse_log_size(args.iter().cloned())
+        .and_then(|log_size| parse_config(&args).map(|config| (log_size, config)));
+    let (log_size, config) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("error: {err}");
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--channel" => match args.next() {
+                Some(value) => channel = value,
+                None => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            // Parsed by `parse_log_size` and `parse_config`
+            "--log-size" | "--pow-bits" | "--log-blowup-factor" | "--n-queries" => {
+                args.next();
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let [air, path] = &positional[..] else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let path = PathBuf::from(path);
+
+    let result = match channel.as_str() {
+        Blake2sMerkleChannel::NAME => {
+            prove_named::<Blake2sMerkleChannel>(air, log_size, &path, config)
+        }
+        Poseidon252MerkleChannel::NAME => {
+            prove_named::<Poseidon252MerkleChannel>(air, log_size, &path, config)
+        }
+        _ => Err(format!("unknown channel `{channel}`")),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Verifies a proof file written by `stwo-prove` against one of the example AIRs.
+//!
+//! Usage: `stwo-verify <air> <proof-file> [--pow-bits <n>] [--log-blowup-factor <n>]
+//! [--n-queries <n>]`
+//!
+//! The proof must have been made with the PCS config given by the flags, which default to those of
+//! `PcsConfig::default`, like in `stwo-prove`; any other config in its header is rejected.
+//!
+//! Prints a JSON object with `"result": "accept"` or `"result": "reject"`, together with the stage
+//! at which the proof was rejected and the reason, and exits with a non-zero code on rejection.
+
+use std::{env, path::Path, process::ExitCode};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use stwo_examples::{
+    airs::{
+        bitwise::BitwiseAir, components::ComponentsAir, dynamic_lookups::DynamicLookupsAir,
+        fibonacci::FibonacciAir, is_zero::IsZeroAir, keccak::Sha3Air,
+        local_row_constraints::LocalRowConstraintsAir, memory::MemoryAir, mini_cairo::MiniCairoAir,
+        preprocessed_trace::PreprocessedTraceAir, public_permutation::PublicPermutationAir,
+        range_check::RangeCheckAir, sha256::Sha256Air, simple::SimpleAir,
+        static_lookups::StaticLookupsAir,
+    },
+    cli::{parse_config, positional_args},
+    pipeline::{verify_air, Air},
+    proof::{read_header, ChannelName, ProofEnvelope, ProofFormat, ProofFormatError},
+};
+use stwo_prover::core::{
+    backend::{simd::SimdBackend, BackendForChannel},
+    channel::MerkleChannel,
+    pcs::PcsConfig,
+    prover::{StarkProof, VerificationError},
+    vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+};
+
+const USAGE: &str = "usage: stwo-verify <air> <proof-file> [--pow-bits <n>] \
+                     [--log-blowup-factor <n>] [--n-queries <n>]";
+
+/// Why a proof was rejected.
+struct Rejection {
+    stage: &'static str,
+    reason: String,
+}
+
+impl From<ProofFormatError> for Rejection {
+    fn from(err: ProofFormatError) -> Self {
+        let stage = match err {
+            ProofFormatError::UnsupportedVersion(_) | ProofFormatError::HeaderMismatch { .. } => {
+                "header"
+            }
+            _ => "decode",
+        };
+        Self {
+            stage,
+            reason: err.to_string(),
+        }
+    }
+}
+
+impl From<VerificationError> for Rejection {
+    fn from(err: VerificationError) -> Self {
+        let stage = match err {
+            VerificationError::InvalidStructure(_) => "structure",
+            VerificationError::OodsNotMatching => "oods",
+            VerificationError::Merkle(_) => "merkle",
+            VerificationError::ProofOfWork => "proof_of_work",
+            VerificationError::Fri(_) => "fri",
+            #[allow(unreachable_patterns)]
+            _ => "verify",
+        };
+        Self {
+            stage,
+            reason: err.to_string(),
+        }
+    }
+}
+
+fn verify_file<A: Air, MC: MerkleChannel + ChannelName>(
+    path: &Path,
+    config: PcsConfig,
+) -> Result<(), Rejection>
+where
+    SimdBackend: BackendForChannel<MC>,
+    A::Statement0: Serialize + DeserializeOwned,
+    A::Statement1: Serialize + DeserializeOwned,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    let envelope =
+        ProofEnvelope::<A, MC>::read_from_file(path, ProofFormat::from_path(path), config)?;
+    verify_air::<A, MC>(envelope.into_proof(), config)?;
+    Ok(())
+}
+
+fn verify_named<MC: MerkleChannel + ChannelName>(
+    air: &str,
+    path: &Path,
+    config: PcsConfig,
+) -> Result<(), Rejection>
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    match air {
+        SimpleAir::NAME => verify_file::<SimpleAir, MC>(path, config),
+        PreprocessedTraceAir::NAME => verify_file::<PreprocessedTraceAir, MC>(path, config),
+        StaticLookupsAir::NAME => verify_file::<StaticLookupsAir, MC>(path, config),
+        DynamicLookupsAir::NAME => verify_file::<DynamicLookupsAir, MC>(path, config),
+        ComponentsAir::NAME => verify_file::<ComponentsAir, MC>(path, config),
+        LocalRowConstraintsAir::NAME => verify_file::<LocalRowConstraintsAir, MC>(path, config),
+        PublicPermutationAir::NAME => verify_file::<PublicPermutationAir, MC>(path, config),
+        IsZeroAir::NAME => verify_file::<IsZeroAir, MC>(path, config),
+        BitwiseAir::NAME => verify_file::<BitwiseAir, MC>(path, config),
+        RangeCheckAir::NAME => verify_file::<RangeCheckAir, MC>(path, config),
+        MemoryAir::NAME => verify_file::<MemoryAir, MC>(path, config),
+        MiniCairoAir::NAME => verify_file::<MiniCairoAir, MC>(path, config),
+        FibonacciAir::NAME => verify_file::<FibonacciAir, MC>(path, config),
+        Sha256Air::NAME => verify_file::<Sha256Air, MC>(path, config),
+        Sha3Air::NAME => verify_file::<Sha3Air, MC>(path, config),
+        _ => Err(Rejection {
+            stage: "arguments",
+            reason: format!("unknown AIR `{air}`"),
+        }),
+    }
+}
+
+fn verify(air: &str, path: &Path, config: PcsConfig) -> Result<(), Rejection> {
+    // The channel is read from the header; the rest of the header is checked against the AIR when
+    // the proof is decoded.
+    let header = read_header(path, ProofFormat::from_path(path))?;
+    match header.channel.as_str() {
+        Blake2sMerkleChannel::NAME => verify_named::<Blake2sMerkleChannel>(air, path, config),
+        Poseidon252MerkleChannel::NAME => {
+            verify_named::<Poseidon252MerkleChannel>(air, path, config)
+        }
+        channel => Err(Rejection {
+            stage: "header",
+            reason: format!("unknown channel `{channel}`"),
+        }),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let positional = positional_args(&args);
+    let [air, path] = &positional[..] else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let config = match parse_config(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}");
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match verify(air, Path::new(path), config) {
+        Ok(()) => {
+            println!("{}", json!({ "air": air, "result": "accept" }));
+            ExitCode::SUCCESS
+        }
+        Err(Rejection { stage, reason }) => {
+            println!(
+                "{}",
+                json!({ "air": air, "result": "reject", "stage": stage, "reason": reason })
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Command-line arguments shared by the examples.
+
+use std::{env, process, str::FromStr};
+
+use stwo_prover::core::{backend::simd::m31::LOG_N_LANES, pcs::PcsConfig};
+
+use crate::proof::ConfigHeader;
+
+/// The largest log size accepted by [`log_size_arg`].
+pub const MAX_LOG_SIZE: u32 = 24;
+
+/// Parses the value of `--<name> <value>` in `args`, or `None` if the flag is absent.
+pub fn parse_flag<T: FromStr>(
+    args: impl IntoIterator<Item = String>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    let flag = format!("--{name}");
+    let mut args = args.into_iter();
+    let mut value = None;
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            value = Some(
+                args.next()
+                    .and_then(|value| value.parse().ok())
+                    .ok_or(format!("{flag} expects a number"))?,
+            );
+        }
+    }
+    Ok(value)
+}
+
+/// Parses the log size of the trace from `--log-size <n>` in `args`, defaulting to `LOG_N_LANES`,
+/// the smallest trace the SIMD backend supports.
+pub fn parse_log_size(args: impl IntoIterator<Item = String>) -> Result<u32, String> {
+    let log_size = parse_flag(args, "log-size")?.unwrap_or(LOG_N_LANES);
+    if !(LOG_N_LANES..=MAX_LOG_SIZE).contains(&log_size) {
+        return Err(format!(
+            "log size must be between {LOG_N_LANES} and {MAX_LOG_SIZE}, got {log_size}"
+        ));
+    }
+    Ok(log_size)
+}
+
+/// Parses the PCS config from `--pow-bits <n>`, `--log-blowup-factor <n>` and `--n-queries <n>`
+/// in `args`, each defaulting to the value in [`PcsConfig::default`].
+pub fn parse_config(args: &[String]) -> Result<PcsConfig, String> {
+    let default = ConfigHeader::from(PcsConfig::default());
+    let flag = |name, default| parse_flag(args.iter().cloned(), name).map(|n| n.unwrap_or(default));
+    let config = ConfigHeader {
+        pow_bits: flag("pow-bits", default.pow_bits)?,
+        log_blowup_factor: flag("log-blowup-factor", default.log_blowup_factor)?,
+        n_queries: flag("n-queries", default.n_queries as u32)? as usize,
+        ..default
+    };
+    // The bounds asserted by `FriConfig::new`
+    if !(1..=16).contains(&config.log_blowup_factor) {
+        return Err(format!(
+            "log blowup factor must be between 1 and 16, got {}",
+            config.log_blowup_factor
+        ));
+    }
+    if config.n_queries == 0 {
+        return Err("at least one query is needed".to_string());
+    }
+    Ok(config.into())
+}
+
+/// The arguments in `args` that are neither a `--<name>` flag nor its value.
+pub fn positional_args(args: &[String]) -> Vec<String> {
+    let mut positional = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            args.next();
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+    positional
+}
+
+/// The log size passed to the running example, which exits on invalid arguments.
+pub fn log_size_arg() -> u32 {
+    parse_log_size(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        eprintln!("usage: cargo run --example <name> -- [--log-size <n>]");
+        process::exit(2);
+    })
+}
+
+/// The value of `--<name> <n>` passed to the running example, or `default`, which exits on invalid
+/// arguments.
+pub fn flag_arg<T: FromStr>(name: &str, default: T) -> T {
+    parse_flag(env::args().skip(1), name)
+        .unwrap_or_else(|err| {
+            eprintln!("error: {err}");
+            process::exit(2);
+        })
+        .unwrap_or(default)
+}
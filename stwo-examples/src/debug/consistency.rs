@@ -0,0 +1,79 @@
+//! Checks that a constraint evaluator agrees with the witness generator of its trace, by
+//! evaluating the constraints on witnesses generated from many seeds.
+//!
+//! The generator is handed a seeded rng, so a failure is reproduced by running it on the reported
+//! seed alone.
+
+use std::fmt;
+
+use rand::{rngs::StdRng, SeedableRng};
+use stwo_prover::constraint_framework::FrameworkEval;
+
+use super::constraints::{find_constraint_failures, ConstraintFailure, DebugTrace};
+
+/// The number of seeds [`assert_consistent`] is usually run over.
+pub const DEFAULT_N_SEEDS: u64 = 16;
+
+/// A constraint that does not vanish on a generated witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inconsistency {
+    pub seed: u64,
+    /// The failure at the smallest row over all seeds, and the smallest seed among those.
+    pub failure: ConstraintFailure,
+    /// The number of seeds whose witness does not satisfy the constraints.
+    pub n_failing_seeds: usize,
+    pub n_seeds: usize,
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "the constraints fail on the witnesses of {} out of {} seeds; with seed {}, {}",
+            self.n_failing_seeds, self.n_seeds, self.seed, self.failure
+        )
+    }
+}
+
+/// Evaluates the constraints of the evaluator returned by `gen` on the trace it returns, for the
+/// rng of every seed, and returns the failure at the smallest row if any.
+pub fn find_inconsistency<E: FrameworkEval>(
+    seeds: impl IntoIterator<Item = u64>,
+    gen: impl Fn(&mut StdRng) -> (E, DebugTrace),
+) -> Option<Inconsistency> {
+    let mut n_seeds = 0;
+    let mut failures = vec![];
+    for seed in seeds {
+        n_seeds += 1;
+        let (eval, trace) = gen(&mut StdRng::seed_from_u64(seed));
+        // The failures are ordered by row, so the first one is at the smallest row.
+        if let Some(failure) = find_constraint_failures(&eval, &trace).into_iter().next() {
+            failures.push((seed, failure));
+        }
+    }
+
+    let n_failing_seeds = failures.len();
+    let (seed, failure) = failures
+        .into_iter()
+        .min_by_key(|(seed, failure)| (failure.row, *seed))?;
+    Some(Inconsistency {
+        seed,
+        failure,
+        n_failing_seeds,
+        n_seeds,
+    })
+}
+
+/// Panics with the failing row of smallest index if the constraints of the evaluator returned by
+/// `gen` fail on the trace it returns, for the rng of any of the seeds `0..n_seeds`.
+///
+/// Meant to be called from a `#[test]`, with `gen` building the trace through the same witness
+/// generator the prover uses.
+pub fn assert_consistent<E: FrameworkEval>(
+    n_seeds: u64,
+    gen: impl Fn(&mut StdRng) -> (E, DebugTrace),
+) {
+    if let Some(inconsistency) = find_inconsistency(0..n_seeds, gen) {
+        panic!("{inconsistency}");
+    }
+}
@@ -0,0 +1,289 @@
+//! Row-by-row constraint evaluation that reports which constraint fails, where, and on which
+//! values.
+//!
+//! Only the constraints added with `add_constraint` are checked. LogUp relations are ignored here;
+//! see the LogUp diagnostics for unbalanced lookups.
+
+use std::{collections::HashMap, fmt, ops::Mul};
+
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, EvalAtRow, FrameworkEval, ORIGINAL_TRACE_IDX,
+        PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        backend::simd::SimdBackend,
+        fields::{m31::M31, qm31::SecureField, secure_column::SECURE_EXTENSION_DEGREE},
+        lookups::utils::Fraction,
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+
+use super::to_row_order;
+
+/// The trace of a single component, with every column in row order.
+pub struct DebugTrace {
+    preprocessed: HashMap<String, Vec<M31>>,
+    /// Columns of each tree, indexed like the committed trees. The preprocessed tree is empty, as
+    /// preprocessed columns are looked up by id.
+    trees: Vec<Vec<Vec<M31>>>,
+}
+
+impl DebugTrace {
+    /// Builds the trace from the evaluations that would be committed, converting them from
+    /// bit-reversed circle domain order to row order.
+    pub fn new(
+        preprocessed: &[(
+            PreProcessedColumnId,
+            &CircleEvaluation<SimdBackend, M31, BitReversedOrder>,
+        )],
+        trace: &[CircleEvaluation<SimdBackend, M31, BitReversedOrder>],
+    ) -> Self {
+        Self::from_row_order(
+            preprocessed
+                .iter()
+                .map(|(id, column)| (id.clone(), to_row_order(column)))
+                .collect(),
+            trace.iter().map(to_row_order).collect(),
+        )
+    }
+
+    /// Builds the trace from columns that are already in row order.
+    pub fn from_row_order(
+        preprocessed: Vec<(PreProcessedColumnId, Vec<M31>)>,
+        trace: Vec<Vec<M31>>,
+    ) -> Self {
+        let mut trees = vec![vec![]; ORIGINAL_TRACE_IDX + 1];
+        trees[ORIGINAL_TRACE_IDX] = trace;
+        Self {
+            preprocessed: preprocessed
+                .into_iter()
+                .map(|(id, values)| (id.id, values))
+                .collect(),
+            trees,
+        }
+    }
+}
+
+/// The column a mask value was read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnRef {
+    Preprocessed(String),
+    Trace { tree: usize, index: usize },
+}
+
+impl fmt::Display for ColumnRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Preprocessed(id) => write!(f, "preprocessed `{id}`"),
+            Self::Trace { tree, index } => write!(f, "tree {tree} column {index}"),
+        }
+    }
+}
+
+/// A value read by the evaluator through a mask.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaskValue {
+    pub column: ColumnRef,
+    pub offset: isize,
+    /// The row the value was read from, after applying the offset.
+    pub row: usize,
+    pub value: M31,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintFailure {
+    pub row: usize,
+    /// Index of the constraint, in the order of the `add_constraint` calls.
+    pub constraint_index: usize,
+    pub value: SecureField,
+    /// Every value the evaluator read at this row.
+    pub mask_values: Vec<MaskValue>,
+}
+
+impl fmt::Display for ConstraintFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "constraint #{} is not satisfied at row {} (evaluates to {})",
+            self.constraint_index, self.row, self.value
+        )?;
+        for mask_value in &self.mask_values {
+            writeln!(
+                f,
+                "    {} [offset {:+}, row {}] = {}",
+                mask_value.column, mask_value.offset, mask_value.row, mask_value.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates the constraints of `eval` at each of its `2^log_size` rows and returns all failures,
+/// ordered by row and constraint index. Masks wrap around the rows of the component.
+///
+/// Panics if a column the evaluator reads, preprocessed or not, does not have `2^log_size` rows:
+/// the prover reads every column of a component on the component's domain, so a column of another
+/// size does not hold the values the trace suggests.
+pub fn find_constraint_failures<E: FrameworkEval>(
+    eval: &E,
+    trace: &DebugTrace,
+) -> Vec<ConstraintFailure> {
+    (0..1 << eval.log_size())
+        .flat_map(|row| {
+            let evaluator = eval.evaluate(RowEvaluator::new(trace, eval.log_size(), row));
+            let mask_values = evaluator.mask_values;
+            evaluator
+                .failures
+                .into_iter()
+                .map(move |(constraint_index, value)| ConstraintFailure {
+                    row,
+                    constraint_index,
+                    value,
+                    mask_values: mask_values.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Panics with a readable report if any constraint of `eval` fails on `trace`.
+pub fn assert_constraints_hold<E: FrameworkEval>(eval: &E, trace: &DebugTrace) {
+    const MAX_REPORTED: usize = 5;
+
+    let failures = find_constraint_failures(eval, trace);
+    if failures.is_empty() {
+        return;
+    }
+    let report = failures
+        .iter()
+        .take(MAX_REPORTED)
+        .map(|failure| failure.to_string())
+        .collect::<String>();
+    panic!(
+        "{} constraint failures, the first {} are:\n{report}",
+        failures.len(),
+        failures.len().min(MAX_REPORTED)
+    );
+}
+
+/// The row `offset` rows away from `row`, wrapping around the `2^log_size` rows of the component.
+fn wrap_row(row: usize, offset: isize, log_size: u32) -> usize {
+    (row as isize + offset).rem_euclid(1 << log_size) as usize
+}
+
+/// Panics unless `column` has the `2^log_size` rows of the component.
+fn check_column_size(column: &ColumnRef, values: &[M31], log_size: u32) {
+    assert_eq!(
+        values.len(),
+        1 << log_size,
+        "{column} has {} rows, but the component has {}",
+        values.len(),
+        1 << log_size
+    );
+}
+
+struct RowEvaluator<'a> {
+    trace: &'a DebugTrace,
+    log_size: u32,
+    row: usize,
+    next_column: Vec<usize>,
+    mask_values: Vec<MaskValue>,
+    n_constraints: usize,
+    failures: Vec<(usize, SecureField)>,
+}
+
+impl<'a> RowEvaluator<'a> {
+    fn new(trace: &'a DebugTrace, log_size: u32, row: usize) -> Self {
+        Self {
+            trace,
+            log_size,
+            row,
+            next_column: vec![0; trace.trees.len()],
+            mask_values: vec![],
+            n_constraints: 0,
+            failures: vec![],
+        }
+    }
+}
+
+impl EvalAtRow for RowEvaluator<'_> {
+    type F = M31;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        assert_ne!(
+            interaction, PREPROCESSED_TRACE_IDX,
+            "preprocessed columns must be read with `get_preprocessed_column`"
+        );
+        let trace = self.trace;
+        let index = self.next_column[interaction];
+        self.next_column[interaction] += 1;
+        let column = trace
+            .trees
+            .get(interaction)
+            .and_then(|tree| tree.get(index))
+            .unwrap_or_else(|| panic!("tree {interaction} has no column {index}"));
+        let column_ref = ColumnRef::Trace {
+            tree: interaction,
+            index,
+        };
+        check_column_size(&column_ref, column, self.log_size);
+
+        offsets.map(|offset| {
+            let row = wrap_row(self.row, offset, self.log_size);
+            self.mask_values.push(MaskValue {
+                column: column_ref.clone(),
+                offset,
+                row,
+                value: column[row],
+            });
+            column[row]
+        })
+    }
+
+    fn get_preprocessed_column(&mut self, column: PreProcessedColumnId) -> Self::F {
+        let values = self
+            .trace
+            .preprocessed
+            .get(&column.id)
+            .unwrap_or_else(|| panic!("preprocessed column `{}` was not provided", column.id));
+        let column_ref = ColumnRef::Preprocessed(column.id);
+        check_column_size(&column_ref, values, self.log_size);
+        let value = values[self.row];
+        self.mask_values.push(MaskValue {
+            column: column_ref,
+            offset: 0,
+            row: self.row,
+            value,
+        });
+        value
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        let value = SecureField::from(constraint);
+        if !value.is_zero() {
+            self.failures.push((self.n_constraints, value));
+        }
+        self.n_constraints += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn write_logup_frac(&mut self, _fraction: Fraction<Self::EF, Self::EF>) {}
+
+    fn finalize_logup_batched(&mut self, _batching: &Vec<usize>) {}
+
+    fn finalize_logup(&mut self) {}
+
+    fn finalize_logup_in_pairs(&mut self) {}
+}
@@ -0,0 +1,122 @@
+//! LogUp balance diagnostics: collects the relation entries of each component from the original
+//! trace and reports the tuples whose multiplicities do not cancel out, before any LogUp column
+//! is generated.
+
+use std::{collections::BTreeMap, fmt};
+
+use itertools::Itertools;
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{
+        relation_tracker::{RelationTrackerComponent, RelationTrackerEntry},
+        FrameworkEval, TraceLocationAllocator,
+    },
+    core::{
+        backend::{simd::SimdBackend, Column},
+        fields::m31::{M31, P},
+        pcs::TreeVec,
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+
+/// Collects the relation entries that `eval` adds at every row of its trace.
+///
+/// `preprocessed` holds the preprocessed columns in the order the component first reads them,
+/// and `trace` the component's columns in the original trace, both as they would be committed.
+pub fn relation_entries<E: FrameworkEval>(
+    eval: E,
+    preprocessed: &[CircleEvaluation<SimdBackend, M31, BitReversedOrder>],
+    trace: &[CircleEvaluation<SimdBackend, M31, BitReversedOrder>],
+) -> Vec<RelationTrackerEntry> {
+    let n_rows = 1 << eval.log_size();
+    let columns = [preprocessed, trace].map(|tree| {
+        tree.iter()
+            .map(|column| column.values.to_cpu())
+            .collect_vec()
+    });
+    let trace = TreeVec::new(
+        columns
+            .iter()
+            .map(|tree| tree.iter().collect_vec())
+            .collect(),
+    );
+
+    RelationTrackerComponent::new(&mut TraceLocationAllocator::default(), eval, n_rows)
+        .entries(&trace)
+}
+
+/// A tuple whose multiplicities in a relation do not sum to zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelationImbalance {
+    pub relation: String,
+    pub values: Vec<M31>,
+    /// The net multiplicity of the tuple. Positive means it is consumed more often than it is
+    /// yielded, negative means it is yielded more often than it is consumed.
+    pub multiplicity: i64,
+}
+
+impl fmt::Display for RelationImbalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self.values.iter().map(|value| value.0).join(", ");
+        if self.multiplicity > 0 {
+            write!(
+                f,
+                "{} ({values}) is consumed {} more time(s) than it is yielded",
+                self.relation, self.multiplicity
+            )
+        } else {
+            write!(
+                f,
+                "{} ({values}) is yielded {} more time(s) than it is consumed",
+                self.relation, -self.multiplicity
+            )
+        }
+    }
+}
+
+/// Sums the multiplicities of every (relation, tuple) pair and returns the pairs that do not
+/// cancel out, sorted by relation and tuple.
+///
+/// Following the framework's convention, a positive multiplicity consumes a tuple and a negative
+/// multiplicity yields it.
+pub fn find_unbalanced_entries(entries: &[RelationTrackerEntry]) -> Vec<RelationImbalance> {
+    let mut sums = BTreeMap::<(&str, Vec<u32>), M31>::new();
+    for entry in entries {
+        let key = (
+            entry.relation.as_str(),
+            entry.values.iter().map(|value| value.0).collect(),
+        );
+        *sums.entry(key).or_insert_with(M31::zero) += entry.mult;
+    }
+
+    sums.into_iter()
+        .filter(|(_, multiplicity)| multiplicity.0 != 0)
+        .map(|((relation, values), multiplicity)| RelationImbalance {
+            relation: relation.to_string(),
+            values: values.into_iter().map(M31::from_u32_unchecked).collect(),
+            multiplicity: to_signed(multiplicity),
+        })
+        .collect()
+}
+
+/// Panics with the unbalanced tuples if the relation entries do not cancel out.
+pub fn assert_logup_balanced(entries: &[RelationTrackerEntry]) {
+    let imbalances = find_unbalanced_entries(entries);
+    if imbalances.is_empty() {
+        return;
+    }
+    panic!(
+        "{} unbalanced LogUp tuples:\n{}",
+        imbalances.len(),
+        imbalances.iter().join("\n")
+    );
+}
+
+/// Interprets an M31 multiplicity as a signed integer in `(-P/2, P/2]`.
+fn to_signed(value: M31) -> i64 {
+    if value.0 > P / 2 {
+        value.0 as i64 - P as i64
+    } else {
+        value.0 as i64
+    }
+}
@@ -0,0 +1,27 @@
+//! Tools for debugging AIRs on the trace itself, before anything is committed or proven.
+
+pub mod consistency;
+pub mod constraints;
+pub mod logup;
+
+use stwo_prover::core::{
+    backend::{simd::SimdBackend, Column},
+    fields::m31::M31,
+    poly::{circle::CircleEvaluation, BitReversedOrder},
+    utils::{bit_reverse_index, coset_index_to_circle_domain_index},
+};
+
+/// Returns the values of `column` in row order, i.e. the order in which `EvalAtRow` masks with
+/// offsets `-1`, `0`, `1` see consecutive rows.
+///
+/// This undoes `bit_reverse_coset_to_circle_domain_order`: a column that was written in row order
+/// and committed without being bit-reversed will come back scrambled.
+pub fn to_row_order(column: &CircleEvaluation<SimdBackend, M31, BitReversedOrder>) -> Vec<M31> {
+    let values = column.values.to_cpu();
+    let log_size = values.len().ilog2();
+    (0..values.len())
+        .map(|row| {
+            values[bit_reverse_index(coset_index_to_circle_domain_index(row, log_size), log_size)]
+        })
+        .collect()
+}
@@ -0,0 +1,664 @@
+//! AIRs described in a small text format instead of Rust, interpreted at runtime by
+//! [`DeclaredEval`] and proven over a trace imported from a spreadsheet.
+//!
+//! A description is a list of declarations, one per line, with `#` starting a comment:
+//!
+//! ```text
+//! # The AIR of "Proving an AIR", where col_2 is also a permutation of col_1
+//! column col_1
+//! column col_2
+//! column col_3
+//! preprocessed is_first = is_first
+//! relation values 1
+//!
+//! constraint col_1 * col_2 + col_1 - col_3
+//! constraint is_first * (col_2[1] - col_1)
+//! lookup values 1 : col_1
+//! lookup values -1 : col_2
+//! ```
+//!
+//! - `column <name>` declares the next column of the trace.
+//! - `preprocessed <name> = is_first | is_last | seq` declares a preprocessed column of the size of
+//!   the trace.
+//! - `relation <name> <size>` declares a LogUp relation of tuples of `size` values.
+//! - `constraint <expr>` declares a polynomial that is zero on every row.
+//! - `lookup <relation> <multiplicity> : <values>` adds `multiplicity / combine(values)` to the
+//!   LogUp sum, positive to consume a tuple and negative to yield it.
+//!
+//! Expressions are built from constants, columns, `+`, `-`, `*` and parentheses, and `name[k]`
+//! reads the column `k` rows below the current one, wrapping around. The rows of the trace are in
+//! circle domain order, so the offsets follow the rows of the spreadsheet.
+//!
+//! The padding rows of the trace are zero, so the constraints and lookups must also hold on zero
+//! rows, e.g. with a column of the spreadsheet that enables the real rows.
+
+pub mod parse;
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use itertools::{chain, Itertools};
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, EvalAtRow, FrameworkComponent, FrameworkEval,
+        Relation, RelationEntry, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX,
+    },
+    core::{
+        air::{Component, ComponentProver},
+        backend::{
+            simd::{column::BaseColumn, SimdBackend},
+            BackendForChannel, Column,
+        },
+        channel::{Channel, MerkleChannel},
+        fields::{m31::M31, qm31::SecureField},
+        pcs::{PcsConfig, TreeVec},
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        prover::VerificationError,
+        utils::bit_reverse_coset_to_circle_domain_order,
+        ColumnVec,
+    },
+    relation,
+};
+use thiserror::Error;
+
+use self::parse::check_degree;
+pub use self::parse::{parse_spec, SpecError};
+use crate::{
+    airs::{check_log_size, gen_logup_trace_from_rows, ClaimedSumStatement},
+    pipeline::{verify_air, Air, AirComponents, AirProof, AirProver, Statement},
+    preprocessed::{registry::PreprocessedRegistry, IsFirst, IsLast, PreprocessedColumn, Seq},
+    spreadsheet::{parse_spreadsheet, ImportOptions, SpreadsheetError},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// The most values a lookup combines: the values of the largest relation and the index of the
+/// relation, which keeps the relations apart while they share the lookup elements.
+pub const MAX_RELATION_SIZE: usize = 16;
+/// The most row offsets a column can be read at.
+pub const MAX_OFFSETS: usize = 4;
+/// The highest degree of a constraint within the constraint evaluation blowup.
+pub const MAX_CONSTRAINT_DEGREE: usize = 3;
+
+relation!(DeclaredElements, MAX_RELATION_SIZE);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PreprocessedKind {
+    IsFirst,
+    IsLast,
+    Seq,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expr {
+    Const(u32),
+    /// The trace column at index `column`, `offset` rows below the current one.
+    Column {
+        column: usize,
+        offset: isize,
+    },
+    /// The preprocessed column at this index of [`AirSpec::preprocessed`].
+    Preprocessed(usize),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn degree(&self) -> usize {
+        match self {
+            Self::Const(_) => 0,
+            Self::Column { .. } | Self::Preprocessed(_) => 1,
+            Self::Neg(a) => a.degree(),
+            Self::Add(a, b) | Self::Sub(a, b) => a.degree().max(b.degree()),
+            Self::Mul(a, b) => a.degree() + b.degree(),
+        }
+    }
+
+    /// Evaluates the expression, with `column(index, offset)` and `preprocessed(index)` the values
+    /// of the columns it reads.
+    pub fn eval<F>(
+        &self,
+        column: &impl Fn(usize, isize) -> F,
+        preprocessed: &impl Fn(usize) -> F,
+    ) -> F
+    where
+        F: Clone
+            + From<M31>
+            + Add<Output = F>
+            + Sub<Output = F>
+            + Mul<Output = F>
+            + Neg<Output = F>,
+    {
+        let eval = |expr: &Self| expr.eval(column, preprocessed);
+        match self {
+            Self::Const(value) => F::from(M31::from(*value)),
+            Self::Column {
+                column: index,
+                offset,
+            } => column(*index, *offset),
+            Self::Preprocessed(index) => preprocessed(*index),
+            Self::Neg(a) => -eval(a),
+            Self::Add(a, b) => eval(a) + eval(b),
+            Self::Sub(a, b) => eval(a) - eval(b),
+            Self::Mul(a, b) => eval(a) * eval(b),
+        }
+    }
+
+    /// Checks that the expression only reads the `n_columns` trace columns and the
+    /// `n_preprocessed` preprocessed columns.
+    fn check_indices(&self, n_columns: usize, n_preprocessed: usize) -> Result<(), String> {
+        match self {
+            Self::Column { column, .. } if *column >= n_columns => {
+                Err(format!("there is no trace column {column}"))
+            }
+            Self::Preprocessed(index) if *index >= n_preprocessed => {
+                Err(format!("there is no preprocessed column {index}"))
+            }
+            Self::Const(_) | Self::Column { .. } | Self::Preprocessed(_) => Ok(()),
+            Self::Neg(a) => a.check_indices(n_columns, n_preprocessed),
+            Self::Add(a, b) | Self::Sub(a, b) | Self::Mul(a, b) => {
+                a.check_indices(n_columns, n_preprocessed)?;
+                b.check_indices(n_columns, n_preprocessed)
+            }
+        }
+    }
+
+    fn visit_columns(&self, f: &mut impl FnMut(usize, isize)) {
+        match self {
+            Self::Column { column, offset } => f(*column, *offset),
+            Self::Const(_) | Self::Preprocessed(_) => {}
+            Self::Neg(a) => a.visit_columns(f),
+            Self::Add(a, b) | Self::Sub(a, b) | Self::Mul(a, b) => {
+                a.visit_columns(f);
+                b.visit_columns(f);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreprocessedSpec {
+    pub name: String,
+    pub kind: PreprocessedKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationSpec {
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupSpec {
+    /// The index of the relation in [`AirSpec::relations`].
+    pub relation: usize,
+    pub multiplicity: Expr,
+    pub values: Vec<Expr>,
+}
+
+/// A parsed AIR description, with every name resolved to the index of its declaration.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AirSpec {
+    pub columns: Vec<String>,
+    pub preprocessed: Vec<PreprocessedSpec>,
+    pub relations: Vec<RelationSpec>,
+    pub constraints: Vec<Expr>,
+    pub lookups: Vec<LookupSpec>,
+}
+
+impl AirSpec {
+    fn exprs(&self) -> impl Iterator<Item = &Expr> {
+        chain!(
+            &self.constraints,
+            self.lookups
+                .iter()
+                .flat_map(|lookup| chain!([&lookup.multiplicity], &lookup.values))
+        )
+    }
+
+    /// Checks what [`parse_spec`] checks while parsing, for a spec read from a statement: every
+    /// index refers to a declaration, and the sizes and degrees are supported.
+    pub fn check(&self) -> Result<(), String> {
+        for expr in self.exprs() {
+            expr.check_indices(self.columns.len(), self.preprocessed.len())?;
+        }
+        for relation in &self.relations {
+            if !(1..MAX_RELATION_SIZE).contains(&relation.size) {
+                return Err(format!(
+                    "the size of a relation is between 1 and {}",
+                    MAX_RELATION_SIZE - 1
+                ));
+            }
+        }
+        for constraint in &self.constraints {
+            check_degree(constraint, MAX_CONSTRAINT_DEGREE, "the constraint")?;
+        }
+        for lookup in &self.lookups {
+            let relation = self
+                .relations
+                .get(lookup.relation)
+                .ok_or(format!("there is no relation {}", lookup.relation))?;
+            if lookup.values.len() != relation.size {
+                return Err(format!(
+                    "relation `{}` has {} values, got {}",
+                    relation.name,
+                    relation.size,
+                    lookup.values.len()
+                ));
+            }
+            check_degree(&lookup.multiplicity, 1, "the multiplicity")?;
+            for value in &lookup.values {
+                check_degree(value, 1, "a looked up value")?;
+            }
+        }
+        if self
+            .offsets()
+            .iter()
+            .any(|offsets| offsets.len() > MAX_OFFSETS)
+        {
+            return Err(format!(
+                "a column is read at more than {MAX_OFFSETS} row offsets"
+            ));
+        }
+        Ok(())
+    }
+
+    /// The sorted row offsets every column is read at, `[0]` for the columns that are not read.
+    pub fn offsets(&self) -> Vec<Vec<isize>> {
+        let mut offsets = vec![vec![]; self.columns.len()];
+        for expr in self.exprs() {
+            expr.visit_columns(&mut |column, offset| offsets[column].push(offset));
+        }
+        offsets
+            .into_iter()
+            .map(|offsets| {
+                if offsets.is_empty() {
+                    vec![0]
+                } else {
+                    offsets.into_iter().sorted().dedup().collect()
+                }
+            })
+            .collect()
+    }
+
+    fn preprocessed_ids(&self, log_size: u32) -> Vec<PreProcessedColumnId> {
+        self.preprocessed
+            .iter()
+            .map(|column| match column.kind {
+                PreprocessedKind::IsFirst => IsFirst::new(log_size).id(),
+                PreprocessedKind::IsLast => IsLast::new(log_size).id(),
+                PreprocessedKind::Seq => Seq::new(log_size).id(),
+            })
+            .collect()
+    }
+
+    fn preprocessed_values(&self, log_size: u32, row: usize) -> Vec<M31> {
+        self.preprocessed
+            .iter()
+            .map(|column| match column.kind {
+                PreprocessedKind::IsFirst => IsFirst::new(log_size).value_at_row(row),
+                PreprocessedKind::IsLast => IsLast::new(log_size).value_at_row(row),
+                PreprocessedKind::Seq => Seq::new(log_size).value_at_row(row),
+            })
+            .collect()
+    }
+
+    pub fn preprocessed_registry(&self, log_size: u32) -> PreprocessedRegistry {
+        let mut registry = PreprocessedRegistry::new();
+        for column in &self.preprocessed {
+            match column.kind {
+                PreprocessedKind::IsFirst => registry.add(IsFirst::new(log_size)),
+                PreprocessedKind::IsLast => registry.add(IsLast::new(log_size)),
+                PreprocessedKind::Seq => registry.add(Seq::new(log_size)),
+            };
+        }
+        registry
+    }
+}
+
+/// Reads the next trace column at `offsets`.
+fn next_mask<E: EvalAtRow>(eval: &mut E, offsets: &[isize]) -> Vec<E::F> {
+    match *offsets {
+        [a] => eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [a]).to_vec(),
+        [a, b] => eval
+            .next_interaction_mask(ORIGINAL_TRACE_IDX, [a, b])
+            .to_vec(),
+        [a, b, c] => eval
+            .next_interaction_mask(ORIGINAL_TRACE_IDX, [a, b, c])
+            .to_vec(),
+        [a, b, c, d] => eval
+            .next_interaction_mask(ORIGINAL_TRACE_IDX, [a, b, c, d])
+            .to_vec(),
+        _ => panic!("a column is read at more than {MAX_OFFSETS} offsets"),
+    }
+}
+
+/// Evaluates the constraints and lookups of an [`AirSpec`].
+pub struct DeclaredEval {
+    pub spec: AirSpec,
+    pub log_size: u32,
+    pub lookup_elements: DeclaredElements,
+}
+
+impl FrameworkEval for DeclaredEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let preprocessed = self
+            .spec
+            .preprocessed_ids(self.log_size)
+            .into_iter()
+            .map(|id| eval.get_preprocessed_column(id))
+            .collect_vec();
+        let offsets = self.spec.offsets();
+        let masks = offsets
+            .iter()
+            .map(|offsets| next_mask(&mut eval, offsets))
+            .collect_vec();
+        let column = |index: usize, offset: isize| {
+            masks[index][offsets[index].binary_search(&offset).unwrap()].clone()
+        };
+        let preprocessed = |index: usize| preprocessed[index].clone();
+
+        for constraint in &self.spec.constraints {
+            let value = constraint.eval(&column, &preprocessed);
+            eval.add_constraint(value);
+        }
+
+        if self.spec.lookups.is_empty() {
+            return eval;
+        }
+        for lookup in &self.spec.lookups {
+            let multiplicity = lookup.multiplicity.eval(&column, &preprocessed);
+            let values = chain!(
+                [E::F::from(M31::from(lookup.relation))],
+                lookup
+                    .values
+                    .iter()
+                    .map(|value| value.eval(&column, &preprocessed))
+            )
+            .collect_vec();
+            eval.add_to_relation(RelationEntry::new(
+                &self.lookup_elements,
+                E::EF::from(multiplicity),
+                &values,
+            ));
+        }
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// The description of the AIR is part of the statement, so that a proof is bound to the AIR it
+/// proves.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclaredStatement0 {
+    pub spec: AirSpec,
+    pub log_size: u32,
+}
+
+impl Statement for DeclaredStatement0 {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_size as u64);
+        let bytes = serde_json::to_vec(&self.spec).expect("the spec serializes to JSON");
+        channel.mix_u64(bytes.len() as u64);
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            channel.mix_u64(u64::from_le_bytes(word));
+        }
+    }
+}
+
+pub type DeclaredComponent = FrameworkComponent<DeclaredEval>;
+
+pub struct DeclaredComponents {
+    pub component: DeclaredComponent,
+}
+
+impl DeclaredComponents {
+    pub fn new(
+        statement0: &DeclaredStatement0,
+        lookup_elements: &DeclaredElements,
+        statement1: &ClaimedSumStatement,
+    ) -> Self {
+        Self {
+            component: statement0
+                .spec
+                .preprocessed_registry(statement0.log_size)
+                .component_builder()
+                .build(
+                    DeclaredEval {
+                        spec: statement0.spec.clone(),
+                        log_size: statement0.log_size,
+                        lookup_elements: lookup_elements.clone(),
+                    },
+                    statement1.claimed_sum,
+                ),
+        }
+    }
+}
+
+impl AirComponents for DeclaredComponents {
+    fn components(&self) -> Vec<&dyn Component> {
+        vec![&self.component as &dyn Component]
+    }
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+        vec![&self.component as &dyn ComponentProver<SimdBackend>]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeclaredError {
+    #[error(transparent)]
+    Spreadsheet(#[from] SpreadsheetError),
+    #[error("the spreadsheet has no column `{0}`")]
+    MissingColumn(String),
+}
+
+/// An AIR described by an [`AirSpec`], with its trace.
+pub struct DeclaredAir {
+    pub spec: AirSpec,
+    pub log_size: u32,
+    /// The values of every column of [`AirSpec::columns`], in row order.
+    pub columns: Vec<Vec<M31>>,
+}
+
+impl DeclaredAir {
+    /// Takes the columns of `spec` from a spreadsheet in CSV or TSV, by name, padded as set by
+    /// `options`.
+    pub fn from_spreadsheet(
+        spec: AirSpec,
+        text: &str,
+        options: ImportOptions,
+    ) -> Result<Self, DeclaredError> {
+        // The rows are put in circle domain order by `gen_trace`
+        let trace = parse_spreadsheet(
+            text,
+            ImportOptions {
+                bit_reverse: false,
+                ..options
+            },
+        )?;
+        let columns = spec
+            .columns
+            .iter()
+            .map(|name| {
+                trace
+                    .column(name)
+                    .map(|column| column.values.to_cpu())
+                    .ok_or_else(|| DeclaredError::MissingColumn(name.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            spec,
+            log_size: trace.log_size,
+            columns,
+        })
+    }
+
+    /// The value of trace column `column` `offset` rows below `row`, wrapping around.
+    fn value(&self, column: usize, row: usize, offset: isize) -> M31 {
+        let n_rows = 1 << self.log_size;
+        self.columns[column][(row as isize + offset).rem_euclid(n_rows) as usize]
+    }
+}
+
+impl Air for DeclaredAir {
+    const NAME: &'static str = "declared";
+    const COMPONENT_NAMES: &'static [&'static str] = &["declared"];
+
+    type Statement0 = DeclaredStatement0;
+    type Statement1 = ClaimedSumStatement;
+    type LookupElements = DeclaredElements;
+    type Components = DeclaredComponents;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        check_log_size("log size", statement0.log_size)?;
+        statement0.spec.check()
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        DeclaredElements::draw(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        statement0.spec.preprocessed_registry(statement0.log_size)
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        let statement1 = ClaimedSumStatement {
+            claimed_sum: SecureField::zero(),
+        };
+        let mut log_sizes =
+            DeclaredComponents::new(statement0, &DeclaredElements::dummy(), &statement1)
+                .component
+                .trace_log_degree_bounds();
+
+        // The preprocessed tree is committed in the registry's order
+        log_sizes[PREPROCESSED_TRACE_IDX] = statement0
+            .spec
+            .preprocessed_registry(statement0.log_size)
+            .log_sizes();
+        log_sizes
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        statement1.claimed_sum
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        DeclaredComponents::new(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for DeclaredAir {
+    type Witness = ();
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        // Masks with offsets see the rows in circle domain order
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        let trace = self
+            .columns
+            .iter()
+            .map(|values| {
+                let mut column = BaseColumn::from_iter(values.iter().copied());
+                bit_reverse_coset_to_circle_domain_order(column.as_mut_slice());
+                CircleEvaluation::new(domain, column)
+            })
+            .collect();
+
+        (
+            DeclaredStatement0 {
+                spec: self.spec.clone(),
+                log_size: self.log_size,
+            },
+            trace,
+            (),
+        )
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        _witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        if self.spec.lookups.is_empty() {
+            let claimed_sum = SecureField::zero();
+            return (ClaimedSumStatement { claimed_sum }, vec![]);
+        }
+
+        let rows = (0..1 << self.log_size)
+            .map(|row| {
+                let preprocessed = self.spec.preprocessed_values(self.log_size, row);
+                let column = |index: usize, offset: isize| self.value(index, row, offset);
+                let preprocessed = |index: usize| preprocessed[index];
+                self.spec
+                    .lookups
+                    .iter()
+                    .map(|lookup| {
+                        let values = chain!(
+                            [M31::from(lookup.relation)],
+                            lookup
+                                .values
+                                .iter()
+                                .map(|value| value.eval(&column, &preprocessed))
+                        )
+                        .collect_vec();
+                        (
+                            SecureField::from(lookup.multiplicity.eval(&column, &preprocessed)),
+                            lookup_elements.combine(&values),
+                        )
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let (trace, claimed_sum) = gen_logup_trace_from_rows(self.log_size, &rows);
+
+        (ClaimedSumStatement { claimed_sum }, trace)
+    }
+}
+
+/// Verifies a proof of a [`DeclaredAir`], after checking that it proves the AIR described by
+/// `spec` rather than one of the prover's choosing.
+pub fn verify_declared<MC: MerkleChannel>(
+    proof: AirProof<DeclaredAir, MC::H>,
+    spec: &AirSpec,
+    config: PcsConfig,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    if proof.statement0.spec != *spec {
+        return Err(VerificationError::InvalidStructure(
+            "the proof is of a different AIR".to_string(),
+        ));
+    }
+    verify_air::<DeclaredAir, MC>(proof, config)
+}
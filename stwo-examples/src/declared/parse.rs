@@ -0,0 +1,353 @@
+//! The parser of the AIR description format documented in [`super`].
+
+use std::{fmt, iter::Peekable, str::Chars};
+
+use stwo_prover::core::fields::m31::P;
+use thiserror::Error;
+
+use super::{
+    AirSpec, Expr, LookupSpec, PreprocessedKind, PreprocessedSpec, RelationSpec,
+    MAX_CONSTRAINT_DEGREE, MAX_OFFSETS, MAX_RELATION_SIZE,
+};
+
+#[derive(Debug, Error)]
+#[error("line {line}: {message}")]
+pub struct SpecError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(u64),
+    Name(String),
+    Symbol(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "`{value}`"),
+            Self::Name(name) => write!(f, "`{name}`"),
+            Self::Symbol(symbol) => write!(f, "`{symbol}`"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            let digits = take_while(&mut chars, |c| c.is_ascii_digit());
+            let value = digits
+                .parse()
+                .map_err(|_| format!("`{digits}` is too large"))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            tokens.push(Token::Name(take_while(&mut chars, |c| {
+                c.is_ascii_alphanumeric() || c == '_'
+            })));
+        } else if "+-*()[],".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected character `{c}`"));
+        }
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars<'_>>, f: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(&c) = chars.peek().filter(|&&c| f(c)) {
+        taken.push(c);
+        chars.next();
+    }
+    taken
+}
+
+/// A recursive descent parser over the tokens of one line:
+///
+/// ```text
+/// expr := term (("+" | "-") term)*
+/// term := unary ("*" unary)*
+/// unary := "-" unary | atom
+/// atom := number | name | name "[" ["-"] number "]" | "(" expr ")"
+/// ```
+struct ExprParser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    spec: &'a AirSpec,
+}
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let matches = self.peek() == Some(&Token::Symbol(symbol));
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected `{symbol}`"))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut expr = self.term()?;
+        loop {
+            if self.eat('+') {
+                expr = Expr::Add(Box::new(expr), Box::new(self.term()?));
+            } else if self.eat('-') {
+                expr = Expr::Sub(Box::new(expr), Box::new(self.term()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.eat('*') {
+            expr = Expr::Mul(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else {
+            self.atom()
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(value)) if value < P as u64 => Ok(Expr::Const(value as u32)),
+            Some(Token::Number(value)) => Err(format!("{value} is not an M31 value")),
+            Some(Token::Symbol('(')) => {
+                let expr = self.expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) => {
+                let mut offset = 0;
+                if self.eat('[') {
+                    let sign = if self.eat('-') { -1 } else { 1 };
+                    offset = match self.next() {
+                        Some(Token::Number(offset)) if offset <= i32::MAX as u64 => {
+                            sign * offset as isize
+                        }
+                        _ => return Err(format!("expected a row offset after `{name}[`")),
+                    };
+                    self.expect(']')?;
+                }
+                self.resolve(&name, offset)
+            }
+            Some(token) => Err(format!("unexpected {token}")),
+            None => Err("unexpected end of line".to_string()),
+        }
+    }
+
+    fn resolve(&self, name: &str, offset: isize) -> Result<Expr, String> {
+        if let Some(column) = self.spec.columns.iter().position(|column| column == name) {
+            return Ok(Expr::Column { column, offset });
+        }
+        match self.spec.preprocessed.iter().position(|p| p.name == name) {
+            Some(_) if offset != 0 => Err(format!(
+                "preprocessed column `{name}` can only be read on the current row"
+            )),
+            Some(index) => Ok(Expr::Preprocessed(index)),
+            None => Err(format!("unknown column `{name}`")),
+        }
+    }
+}
+
+/// Parses a comma-separated list of expressions spanning all of `tokens`.
+fn parse_exprs(tokens: &[Token], spec: &AirSpec) -> Result<Vec<Expr>, String> {
+    let mut parser = ExprParser {
+        tokens,
+        position: 0,
+        spec,
+    };
+    let mut exprs = vec![parser.expr()?];
+    while parser.eat(',') {
+        exprs.push(parser.expr()?);
+    }
+    match parser.peek() {
+        Some(token) => Err(format!("unexpected {token}")),
+        None => Ok(exprs),
+    }
+}
+
+fn parse_expr(text: &str, spec: &AirSpec) -> Result<Expr, String> {
+    match &parse_exprs(&tokenize(text)?, spec)?[..] {
+        [expr] => Ok(expr.clone()),
+        _ => Err("expected a single expression".to_string()),
+    }
+}
+
+pub(super) fn check_degree(expr: &Expr, max_degree: usize, what: &str) -> Result<(), String> {
+    match expr.degree() {
+        degree if degree > max_degree => Err(format!(
+            "{what} has degree {degree}, at most {max_degree} is supported"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Parses a declaration: a keyword followed by its arguments.
+fn parse_line(spec: &mut AirSpec, line: &str) -> Result<(), String> {
+    let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let is_name = |name: &str| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let check_new_name = |spec: &AirSpec, name: &str| {
+        if !is_name(name) {
+            return Err(format!("`{name}` is not a valid name"));
+        }
+        let taken = spec.columns.iter().any(|column| column == name)
+            || spec.preprocessed.iter().any(|p| p.name == name);
+        if taken {
+            return Err(format!("column `{name}` is declared twice"));
+        }
+        Ok(())
+    };
+
+    match keyword {
+        "column" => {
+            check_new_name(spec, rest)?;
+            spec.columns.push(rest.to_string());
+        }
+        "preprocessed" => {
+            let (name, kind) = rest
+                .split_once('=')
+                .ok_or("expected `preprocessed <name> = <kind>`")?;
+            let name = name.trim();
+            check_new_name(spec, name)?;
+            let kind = match kind.trim() {
+                "is_first" => PreprocessedKind::IsFirst,
+                "is_last" => PreprocessedKind::IsLast,
+                "seq" => PreprocessedKind::Seq,
+                kind => return Err(format!("unknown preprocessed column kind `{kind}`")),
+            };
+            spec.preprocessed.push(PreprocessedSpec {
+                name: name.to_string(),
+                kind,
+            });
+        }
+        "relation" => {
+            let (name, size) = rest
+                .split_once(char::is_whitespace)
+                .ok_or("expected `relation <name> <size>`")?;
+            if !is_name(name) || spec.relations.iter().any(|r| r.name == name) {
+                return Err(format!("`{name}` is not a new relation name"));
+            }
+            let size = size
+                .trim()
+                .parse()
+                .ok()
+                .filter(|size| (1..MAX_RELATION_SIZE).contains(size))
+                .ok_or(format!(
+                    "the size of a relation is between 1 and {}",
+                    MAX_RELATION_SIZE - 1
+                ))?;
+            spec.relations.push(RelationSpec {
+                name: name.to_string(),
+                size,
+            });
+        }
+        "constraint" => {
+            let constraint = parse_expr(rest, spec)?;
+            check_degree(&constraint, MAX_CONSTRAINT_DEGREE, "the constraint")?;
+            spec.constraints.push(constraint);
+        }
+        "lookup" => {
+            let (head, values) = rest
+                .split_once(':')
+                .ok_or("expected `lookup <relation> <multiplicity> : <values>`")?;
+            let (name, multiplicity) = head
+                .trim()
+                .split_once(char::is_whitespace)
+                .ok_or("expected `lookup <relation> <multiplicity> : <values>`")?;
+            let relation = spec
+                .relations
+                .iter()
+                .position(|r| r.name == name)
+                .ok_or(format!("unknown relation `{name}`"))?;
+            let multiplicity = parse_expr(multiplicity, spec)?;
+            let values = parse_exprs(&tokenize(values)?, spec)?;
+            let size = spec.relations[relation].size;
+            if values.len() != size {
+                return Err(format!(
+                    "relation `{name}` has {size} values, got {}",
+                    values.len()
+                ));
+            }
+            // The denominators and the numerator of a pair of lookups are multiplied together
+            check_degree(&multiplicity, 1, "the multiplicity")?;
+            for value in &values {
+                check_degree(value, 1, "a looked up value")?;
+            }
+            spec.lookups.push(LookupSpec {
+                relation,
+                multiplicity,
+                values,
+            });
+        }
+        keyword => return Err(format!("unknown declaration `{keyword}`")),
+    }
+
+    if spec
+        .offsets()
+        .iter()
+        .any(|offsets| offsets.len() > MAX_OFFSETS)
+    {
+        return Err(format!(
+            "a column is read at more than {MAX_OFFSETS} row offsets"
+        ));
+    }
+    Ok(())
+}
+
+/// Parses an AIR description. Columns and relations are declared before they are used.
+pub fn parse_spec(text: &str) -> Result<AirSpec, SpecError> {
+    let mut spec = AirSpec::default();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        parse_line(&mut spec, line).map_err(|message| SpecError {
+            line: i + 1,
+            message,
+        })?;
+    }
+    if spec.columns.is_empty() {
+        return Err(SpecError {
+            line: text.lines().count(),
+            message: "the AIR has no columns".to_string(),
+        });
+    }
+    Ok(spec)
+}
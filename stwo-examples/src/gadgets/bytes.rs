@@ -0,0 +1,457 @@
+//! Words stored as little-endian bytes, as in the hash AIRs. A rotation or a shift by `8 * q + s`
+//! bits moves whole bytes by `q` and splits every byte at bit `s`: the low `s` bits are a witness
+//! column, looked up together with the byte in the [`ByteSplit`] table, and the high bits follow
+//! from them. The same table range-checks bytes, as the split `(0, byte, 0)`.
+
+use std::ops::{Add, Mul, Sub};
+
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{
+        logup::LogupTraceGenerator, EvalAtRow, FrameworkEval, Relation, RelationEntry,
+    },
+    core::{
+        backend::simd::{
+            column::BaseColumn, m31::LOG_N_LANES, qm31::PackedSecureField, SimdBackend,
+        },
+        fields::{m31::M31, qm31::SecureField, FieldExpOps},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        utils::bit_reverse_coset_to_circle_domain_order,
+    },
+    relation,
+};
+
+use crate::{
+    airs::{
+        bitwise::{BitwiseElements, BitwiseMultiplicities},
+        gen_logup_trace_from_rows,
+    },
+    gadgets::enabler::Enabler,
+    preprocessed::{BitwiseOp, ByteSplit, PreprocessedColumn},
+    LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR,
+};
+
+/// Combines `(shift, byte, low)`.
+relation!(ByteSplitElements, 3);
+
+/// Checks that every value of `bytes` fits in 8 bits.
+pub fn check_bytes<E: EvalAtRow>(
+    eval: &mut E,
+    elements: &ByteSplitElements,
+    numerator: E::EF,
+    bytes: &[E::F],
+) {
+    for byte in bytes {
+        eval.add_to_relation(RelationEntry::new(
+            elements,
+            numerator.clone(),
+            &[E::F::zero(), byte.clone(), E::F::zero()],
+        ));
+    }
+}
+
+/// Reads the bytes of `a op b` from the next trace columns, and looks up every byte in the 8-bit
+/// table of `op` behind `elements`.
+pub fn bitwise_bytes<E: EvalAtRow>(
+    eval: &mut E,
+    elements: &BitwiseElements,
+    numerator: E::EF,
+    a: &[E::F],
+    b: &[E::F],
+) -> Vec<E::F> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let result = eval.next_trace_mask();
+            eval.add_to_relation(RelationEntry::new(
+                elements,
+                numerator.clone(),
+                &[a.clone(), b.clone(), result.clone()],
+            ));
+            result
+        })
+        .collect()
+}
+
+/// Reads the low `shift` bits of every byte of `bytes` from the next trace columns, and looks up
+/// the splits. The bytes themselves must be range-checked elsewhere.
+pub fn split_bytes<E: EvalAtRow>(
+    eval: &mut E,
+    elements: &ByteSplitElements,
+    numerator: E::EF,
+    bytes: &[E::F],
+    shift: u32,
+) -> SplitBytes<E::F> {
+    assert!(
+        (1..8).contains(&shift),
+        "cannot split a byte at bit {shift}"
+    );
+    let low = bytes
+        .iter()
+        .map(|byte| {
+            let low = eval.next_trace_mask();
+            eval.add_to_relation(RelationEntry::new(
+                elements,
+                numerator.clone(),
+                &[E::F::from(M31::from(shift)), byte.clone(), low.clone()],
+            ));
+            low
+        })
+        .collect();
+
+    SplitBytes {
+        shift,
+        bytes: bytes.to_vec(),
+        low,
+    }
+}
+
+/// The bytes of a word split at bit `shift`, returned by [`split_bytes`].
+#[derive(Clone, Debug)]
+pub struct SplitBytes<F> {
+    pub shift: u32,
+    pub bytes: Vec<F>,
+    pub low: Vec<F>,
+}
+
+impl<F> SplitBytes<F>
+where
+    F: Clone + Zero + Add<Output = F> + Sub<Output = F> + Mul<M31, Output = F>,
+{
+    /// The high `8 - shift` bits of byte `i`.
+    fn high(&self, i: usize) -> F {
+        (self.bytes[i].clone() - self.low[i].clone()) * M31::from(1 << self.shift).inverse()
+    }
+
+    /// Byte `i` of the result: the high bits of byte `i + q` followed by the low bits of byte
+    /// `i + q + 1`, where bytes past the end of the word are `wrap`ped around or 0.
+    fn moved_byte(&self, i: usize, q: usize, wrap: bool) -> F {
+        let n_bytes = self.bytes.len();
+        let byte = |j: usize, part: &dyn Fn(usize) -> F| match (j < n_bytes, wrap) {
+            (true, _) => part(j),
+            (false, true) => part(j - n_bytes),
+            (false, false) => F::zero(),
+        };
+        byte(i + q, &|j| self.high(j))
+            + byte(i + q + 1, &|j| self.low[j].clone()) * M31::from(1 << (8 - self.shift))
+    }
+
+    /// The bytes of the word rotated right by `amount` bits, which must be `shift` modulo 8.
+    pub fn rotate_right(&self, amount: u32) -> Vec<F> {
+        assert_eq!(
+            amount % 8,
+            self.shift,
+            "the bytes are split at the wrong bit"
+        );
+        let q = (amount / 8) as usize;
+        (0..self.bytes.len())
+            .map(|i| self.moved_byte(i, q, true))
+            .collect()
+    }
+
+    /// The bytes of the word shifted right by `amount` bits, which must be `shift` modulo 8.
+    pub fn shift_right(&self, amount: u32) -> Vec<F> {
+        assert_eq!(
+            amount % 8,
+            self.shift,
+            "the bytes are split at the wrong bit"
+        );
+        let q = (amount / 8) as usize;
+        (0..self.bytes.len())
+            .map(|i| self.moved_byte(i, q, false))
+            .collect()
+    }
+}
+
+/// The bytes of a word rotated right by `amount` bits. Whole bytes move for free; other amounts
+/// read and look up a split as [`split_bytes`].
+pub fn rotate_bytes_right<E: EvalAtRow>(
+    eval: &mut E,
+    elements: &ByteSplitElements,
+    numerator: E::EF,
+    bytes: &[E::F],
+    amount: u32,
+) -> Vec<E::F> {
+    match amount % 8 {
+        0 => {
+            let q = (amount / 8) as usize;
+            (0..bytes.len())
+                .map(|i| bytes[(i + q) % bytes.len()].clone())
+                .collect()
+        }
+        shift => split_bytes(eval, elements, numerator, bytes, shift).rotate_right(amount),
+    }
+}
+
+/// The `n_bytes` bytes of `word`, least significant first.
+pub fn to_bytes(word: u64, n_bytes: usize) -> Vec<u32> {
+    (0..n_bytes)
+        .map(|i| ((word >> (8 * i)) & 0xff) as u32)
+        .collect()
+}
+
+/// Combines little-endian bytes into `(lo, hi)` 16-bit limbs.
+pub fn bytes_to_limbs<F>(bytes: &[F]) -> Vec<F>
+where
+    F: Clone + Add<Output = F> + Mul<M31, Output = F>,
+{
+    bytes
+        .chunks(2)
+        .map(|pair| pair[0].clone() + pair[1].clone() * M31::from(1 << 8))
+        .collect()
+}
+
+/// The byte tables, looked up by the rows of the hash AIRs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteRelation {
+    Xor,
+    And,
+    Split,
+}
+
+/// The multiplicities of the byte tables.
+pub struct ByteTables {
+    pub xor: BitwiseMultiplicities,
+    pub and: BitwiseMultiplicities,
+    pub split: ByteSplitMultiplicities,
+}
+
+impl Default for ByteTables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ByteTables {
+    pub fn new() -> Self {
+        Self {
+            xor: BitwiseMultiplicities::new(BitwiseOp::Xor, 8),
+            and: BitwiseMultiplicities::new(BitwiseOp::And, 8),
+            split: ByteSplitMultiplicities::new(),
+        }
+    }
+}
+
+/// A row generated for a component: its columns, in the order `evaluate` reads them, and its
+/// relation entries as `(relation, numerator, values)`, in the order `evaluate` adds them.
+#[derive(Clone, Debug)]
+pub struct TraceRow<R> {
+    pub columns: Vec<M31>,
+    pub entries: Vec<(R, M31, Vec<M31>)>,
+}
+
+impl<R> Default for TraceRow<R> {
+    fn default() -> Self {
+        Self {
+            columns: vec![],
+            entries: vec![],
+        }
+    }
+}
+
+impl<R: From<ByteRelation>> TraceRow<R> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, values: &[u32]) {
+        self.columns
+            .extend(values.iter().map(|&value| M31::from(value)));
+    }
+
+    pub fn entry(&mut self, relation: R, numerator: M31, values: Vec<M31>) {
+        self.entries.push((relation, numerator, values));
+    }
+
+    /// The row side of [`bitwise_bytes`].
+    pub fn bitwise(
+        &mut self,
+        tables: &mut ByteTables,
+        op: BitwiseOp,
+        a: &[u32],
+        b: &[u32],
+    ) -> Vec<u32> {
+        let (multiplicities, relation) = match op {
+            BitwiseOp::Xor => (&mut tables.xor, ByteRelation::Xor),
+            BitwiseOp::And => (&mut tables.and, ByteRelation::And),
+            BitwiseOp::Or => panic!("the hash AIRs have no OR table"),
+        };
+        a.iter()
+            .zip(b)
+            .map(|(&a, &b)| {
+                let result = multiplicities.lookup(a, b);
+                self.push(&[result]);
+                self.entry(
+                    relation.into(),
+                    M31::from(1),
+                    vec![M31::from(a), M31::from(b), M31::from(result)],
+                );
+                result
+            })
+            .collect()
+    }
+
+    /// The row side of [`split_bytes`].
+    pub fn split(&mut self, tables: &mut ByteTables, bytes: &[u32], shift: u32) {
+        for &byte in bytes {
+            let low = tables.split.split(byte, shift);
+            self.push(&[low]);
+            self.entry(
+                ByteRelation::Split.into(),
+                M31::from(1),
+                vec![M31::from(shift), M31::from(byte), M31::from(low)],
+            );
+        }
+    }
+
+    /// The row side of [`rotate_bytes_right`].
+    pub fn rotate_right(&mut self, tables: &mut ByteTables, bytes: &[u32], amount: u32) {
+        if amount % 8 != 0 {
+            self.split(tables, bytes, amount % 8);
+        }
+    }
+
+    /// The row side of [`check_bytes`].
+    pub fn check(&mut self, tables: &mut ByteTables, bytes: &[u32]) {
+        tables.split.check(bytes);
+        for &byte in bytes {
+            self.entry(
+                ByteRelation::Split.into(),
+                M31::from(1),
+                vec![M31::from(0), M31::from(byte), M31::from(0)],
+            );
+        }
+    }
+}
+
+impl<R: Copy> TraceRow<R> {
+    /// Generates the columns of a component from its rows, behind a monotone [`Enabler`] and
+    /// padded to `2^log_size` rows.
+    pub fn gen_trace(log_size: u32, rows: &[Self]) -> Vec<BaseColumn> {
+        let n_columns = rows.first().map_or(0, |row| row.columns.len());
+        let columns = (0..n_columns)
+            .map(|i| rows.iter().map(|row| row.columns[i]).collect())
+            .collect();
+        Enabler::monotone(log_size).gen_trace(log_size, columns)
+    }
+
+    /// Generates the interaction trace of a component from its rows, where `combine` combines the
+    /// values of an entry with the elements of its relation.
+    pub fn gen_logup_trace(
+        log_size: u32,
+        rows: &[Self],
+        combine: impl Fn(R, &[M31]) -> SecureField,
+    ) -> (
+        Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        SecureField,
+    ) {
+        let fractions = rows
+            .iter()
+            .map(|row| {
+                row.entries
+                    .iter()
+                    .map(|(relation, numerator, values)| {
+                        (SecureField::from(*numerator), combine(*relation, values))
+                    })
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        gen_logup_trace_from_rows(log_size, &fractions)
+    }
+}
+
+/// Tallies the lookups into the [`ByteSplit`] table.
+pub struct ByteSplitMultiplicities {
+    /// Multiplicities in row order.
+    counts: Vec<u32>,
+}
+
+impl Default for ByteSplitMultiplicities {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ByteSplitMultiplicities {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; 1 << ByteSplit::LOG_SIZE],
+        }
+    }
+
+    /// Records a lookup of `(shift, byte, low)` and returns `low`.
+    pub fn split(&mut self, byte: u32, shift: u32) -> u32 {
+        assert!(byte < 256 && shift < 8);
+        self.counts[((shift << 8) | byte) as usize] += 1;
+        byte & ((1 << shift) - 1)
+    }
+
+    /// Records the range check of every byte.
+    pub fn check(&mut self, bytes: &[u32]) {
+        for &byte in bytes {
+            self.split(byte, 0);
+        }
+    }
+
+    /// Generates the multiplicity column of the table component, in the same bit-reversed circle
+    /// domain order as the preprocessed table.
+    pub fn gen_column(&self) -> BaseColumn {
+        let mut col = BaseColumn::from_iter(self.counts.iter().map(|&count| M31::from(count)));
+        bit_reverse_coset_to_circle_domain_order(col.as_mut_slice());
+        col
+    }
+}
+
+/// Yields every row of the [`ByteSplit`] table with its multiplicity.
+pub struct ByteSplitTableEval {
+    pub lookup_elements: ByteSplitElements,
+}
+
+impl FrameworkEval for ByteSplitTableEval {
+    fn log_size(&self) -> u32 {
+        ByteSplit::LOG_SIZE
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size() + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let [shift, byte, low] =
+            ByteSplit::columns().map(|column| eval.get_preprocessed_column(column.id()));
+        let multiplicity = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::from(multiplicity),
+            &[shift, byte, low],
+        ));
+
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+pub fn gen_byte_split_table_logup_trace(
+    multiplicity_col: &BaseColumn,
+    lookup_elements: &ByteSplitElements,
+) -> (
+    Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    SecureField,
+) {
+    let [shift_col, byte_col, low_col] =
+        ByteSplit::columns().map(|column| column.gen_column().values);
+    let mut logup_gen = LogupTraceGenerator::new(ByteSplit::LOG_SIZE);
+
+    let mut col_gen = logup_gen.new_col();
+    for row in 0..(1 << (ByteSplit::LOG_SIZE - LOG_N_LANES)) {
+        let numerator = PackedSecureField::from(multiplicity_col.data[row]);
+        let denom: PackedSecureField =
+            lookup_elements.combine(&[shift_col.data[row], byte_col.data[row], low_col.data[row]]);
+        col_gen.write_frac(row, -numerator, denom);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
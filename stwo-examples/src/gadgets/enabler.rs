@@ -0,0 +1,121 @@
+//! The enabler column from "ADD Opcode": a boolean column that is 1 on the real rows of a
+//! component and 0 on the padding rows that fill the trace up to a power of two. The relation
+//! entries of a row are multiplied by its enabler, so the padding rows take no part in the
+//! lookups. Components multiply the constraints of their real rows by the enabler too, so that the
+//! other columns of the padding rows only have to satisfy the ungated constraints, such as those
+//! of booleanity, which the zero rows of [`Enabler::gen_trace`] do.
+
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, EvalAtRow, Relation, RelationEntry,
+        ORIGINAL_TRACE_IDX,
+    },
+    core::{
+        backend::simd::{column::BaseColumn, m31::LOG_N_LANES},
+        fields::m31::M31,
+        utils::bit_reverse_coset_to_circle_domain_order,
+    },
+};
+
+use crate::preprocessed::{IsFirst, PreprocessedColumn};
+
+/// The smallest log size that fits `n_rows` real rows, and at least a SIMD vector.
+pub fn padded_log_size(n_rows: usize) -> u32 {
+    n_rows.next_power_of_two().ilog2().max(LOG_N_LANES)
+}
+
+/// Declares the enabler column of a component, read as the next trace column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Enabler {
+    /// The `IsFirst` column of a monotone enabler, `None` if the enabler is only boolean.
+    pub is_first_id: Option<PreProcessedColumnId>,
+}
+
+impl Enabler {
+    /// An enabler that is only constrained to be boolean, so the padding rows can be anywhere.
+    pub fn boolean() -> Self {
+        Self { is_first_id: None }
+    }
+
+    /// An enabler that is also constrained to never go from 0 back to 1, so the real rows come
+    /// first. The component must be given [`IsFirst`] of `log_size` in its preprocessed trace.
+    pub fn monotone(log_size: u32) -> Self {
+        Self {
+            is_first_id: Some(IsFirst::new(log_size).id()),
+        }
+    }
+
+    /// Reads the enabler column and constrains it.
+    pub fn evaluate<E: EvalAtRow>(&self, eval: &mut E) -> Enabled<E::F> {
+        let enabler = match &self.is_first_id {
+            None => eval.next_trace_mask(),
+            Some(is_first_id) => {
+                let [enabler_prev_row, enabler] =
+                    eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0]);
+                let not_first = E::F::one() - eval.get_preprocessed_column(is_first_id.clone());
+
+                // A real row is preceded by a real row, except on the first row, which wraps
+                // around to the last one
+                eval.add_constraint(not_first * enabler.clone() * (E::F::one() - enabler_prev_row));
+                enabler
+            }
+        };
+        eval.add_constraint(enabler.clone() * (enabler.clone() - E::F::one()));
+
+        Enabled { enabler }
+    }
+
+    /// Generates the columns of a component from the columns of its real rows, all of the same
+    /// length: the enabler column followed by `columns`, padded with zero rows to `log_size`.
+    ///
+    /// A monotone enabler compares the rows with the previous ones, so the columns are then in
+    /// circle domain order.
+    pub fn gen_trace(&self, log_size: u32, columns: Vec<Vec<M31>>) -> Vec<BaseColumn> {
+        let n_rows = columns.first().map_or(0, Vec::len);
+        assert!(columns.iter().all(|column| column.len() == n_rows));
+        assert!(
+            n_rows <= 1 << log_size,
+            "{n_rows} rows do not fit in 2^{log_size}"
+        );
+
+        let enabler = vec![M31::from(1); n_rows];
+        std::iter::once(enabler)
+            .chain(columns)
+            .map(|mut column| {
+                column.resize(1 << log_size, M31::from(0));
+                let mut column = BaseColumn::from_iter(column);
+                if self.is_first_id.is_some() {
+                    bit_reverse_coset_to_circle_domain_order(column.as_mut_slice());
+                }
+                column
+            })
+            .collect()
+    }
+}
+
+/// The enabler of the current row, returned by [`Enabler::evaluate`].
+#[derive(Clone, Debug)]
+pub struct Enabled<F> {
+    pub enabler: F,
+}
+
+impl<F: Clone> Enabled<F> {
+    /// Adds the entry `multiplicity * enabler / (z - combine(values))` to the relation.
+    pub fn add_to_relation<E, R>(
+        &self,
+        eval: &mut E,
+        relation: &R,
+        multiplicity: E::EF,
+        values: &[F],
+    ) where
+        E: EvalAtRow<F = F>,
+        R: Relation<F, E::EF>,
+    {
+        eval.add_to_relation(RelationEntry::new(
+            relation,
+            multiplicity * E::EF::from(self.enabler.clone()),
+            values,
+        ));
+    }
+}
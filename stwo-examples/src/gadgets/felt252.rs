@@ -0,0 +1,210 @@
+//! Felt252 arithmetic from "ADD Opcode": 252-bit field elements stored as 28 9-bit limbs, and the
+//! limb-wise addition with carries modulo the Cairo prime `P = 2^251 + 17 * 2^192 + 1`.
+//!
+//! The result is shown to be reduced by adding it to its complement `P - 1 - dst`. The limbs of
+//! the result and of its complement are not range-checked here; that must be done with a 9-bit
+//! range-check lookup.
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+use stwo_prover::{
+    constraint_framework::{EvalAtRow, FrameworkEval},
+    core::{backend::simd::column::BaseColumn, fields::m31::M31},
+};
+
+use crate::LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR;
+
+pub const N_LIMBS: usize = 28;
+pub const LIMB_BITS: usize = 9;
+
+/// `2^22`, the inverse of `2^LIMB_BITS` in M31.
+const INV_LIMB_BASE: u32 = 1 << 22;
+
+/// Limbs of `P`, which are non-zero only at limbs 0, 21 and 27.
+pub const P_LIMBS: [u32; N_LIMBS] = {
+    let mut limbs = [0; N_LIMBS];
+    limbs[0] = 1;
+    limbs[21] = 136;
+    limbs[27] = 256;
+    limbs
+};
+
+/// Limbs of `P - 1`.
+pub const P_MINUS_ONE_LIMBS: [u32; N_LIMBS] = {
+    let mut limbs = P_LIMBS;
+    limbs[0] = 0;
+    limbs
+};
+
+/// The Cairo prime.
+pub fn prime() -> BigUint {
+    (BigUint::one() << 251) + (BigUint::from(17u32) << 192) + BigUint::one()
+}
+
+/// A 252-bit integer as 9-bit limbs, least significant first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Felt252Limbs(pub [u32; N_LIMBS]);
+
+impl Felt252Limbs {
+    pub fn from_biguint(value: &BigUint) -> Self {
+        assert!(
+            value.bits() <= (N_LIMBS * LIMB_BITS) as u64,
+            "{value} does not fit in {N_LIMBS} limbs"
+        );
+        let mask = BigUint::from((1u32 << LIMB_BITS) - 1);
+        Self(std::array::from_fn(|i| {
+            ((value >> (i * LIMB_BITS)) & &mask)
+                .to_u32()
+                .expect("limb fits in a u32")
+        }))
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        self.0
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, &limb| (acc << LIMB_BITS) + limb)
+    }
+
+    pub fn to_m31(&self) -> [M31; N_LIMBS] {
+        self.0.map(M31::from)
+    }
+}
+
+/// Reduces `value` modulo `P`.
+pub fn reduce(value: &BigUint) -> BigUint {
+    value % prime()
+}
+
+/// Computes `a + b mod P` for `a, b < P`, and whether `P` was subtracted.
+pub fn add_mod_p(a: &BigUint, b: &BigUint) -> (BigUint, bool) {
+    let p = prime();
+    assert!(a < &p && b < &p, "operands must be reduced");
+    let sum = a + b;
+    if sum >= p {
+        (sum - p, true)
+    } else {
+        (sum, false)
+    }
+}
+
+/// Constrains `op0 + op1 = dst + sub_p_bit * P` and, through [`eval_reduced`], `dst < P`.
+///
+/// The carries are not allocated as columns: each one is inlined in the next, and only the last
+/// limb is checked for equality, as described in the "Optimization" section of the chapter.
+pub fn eval_add_mod_p<E: EvalAtRow>(
+    eval: &mut E,
+    op0: &[E::F; N_LIMBS],
+    op1: &[E::F; N_LIMBS],
+    dst: &[E::F; N_LIMBS],
+    dst_complement: &[E::F; N_LIMBS],
+    sub_p_bit: E::F,
+) {
+    let one = E::F::one();
+    eval.add_constraint(sub_p_bit.clone() * (sub_p_bit.clone() - one.clone()));
+
+    let inv_limb_base = E::F::from(M31::from(INV_LIMB_BASE));
+    let mut carry = E::F::zero();
+    for i in 0..N_LIMBS - 1 {
+        carry = (op0[i].clone() + op1[i].clone() + carry
+            - dst[i].clone()
+            - sub_p_bit.clone() * E::F::from(M31::from(P_LIMBS[i])))
+            * inv_limb_base.clone();
+        // Subtracting P can make a carry of -1
+        eval.add_constraint(
+            carry.clone() * (carry.clone() - one.clone()) * (carry.clone() + one.clone()),
+        );
+    }
+
+    let last = N_LIMBS - 1;
+    eval.add_constraint(
+        op0[last].clone() + op1[last].clone() + carry
+            - dst[last].clone()
+            - sub_p_bit * E::F::from(M31::from(P_LIMBS[last])),
+    );
+
+    eval_reduced(eval, dst, dst_complement);
+}
+
+/// Constrains `value + complement = P - 1`, with carries inlined as in [`eval_add_mod_p`]. Once
+/// the limbs of both are range-checked, `complement` is non-negative, so `value < P`.
+pub fn eval_reduced<E: EvalAtRow>(
+    eval: &mut E,
+    value: &[E::F; N_LIMBS],
+    complement: &[E::F; N_LIMBS],
+) {
+    let one = E::F::one();
+    let inv_limb_base = E::F::from(M31::from(INV_LIMB_BASE));
+    let mut carry = E::F::zero();
+    for i in 0..N_LIMBS - 1 {
+        carry = (value[i].clone() + complement[i].clone() + carry
+            - E::F::from(M31::from(P_MINUS_ONE_LIMBS[i])))
+            * inv_limb_base.clone();
+        eval.add_constraint(carry.clone() * (carry.clone() - one.clone()));
+    }
+
+    let last = N_LIMBS - 1;
+    eval.add_constraint(
+        value[last].clone() + complement[last].clone() + carry
+            - E::F::from(M31::from(P_MINUS_ONE_LIMBS[last])),
+    );
+}
+
+/// A component that adds two Felt252 values per row. Its columns are the limbs of `op0`, `op1`
+/// and `dst`, followed by `sub_p_bit` and the limbs of `P - 1 - dst`.
+pub struct Felt252AddEval {
+    pub log_size: u32,
+}
+
+impl FrameworkEval for Felt252AddEval {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let op0 = std::array::from_fn(|_| eval.next_trace_mask());
+        let op1 = std::array::from_fn(|_| eval.next_trace_mask());
+        let dst = std::array::from_fn(|_| eval.next_trace_mask());
+        let sub_p_bit = eval.next_trace_mask();
+        let dst_complement = std::array::from_fn(|_| eval.next_trace_mask());
+
+        eval_add_mod_p(&mut eval, &op0, &op1, &dst, &dst_complement, sub_p_bit);
+
+        eval
+    }
+}
+
+/// Generates the columns read by [`Felt252AddEval`] for the additions `a + b`, in row order.
+/// The rows after the last pair are padded with `0 + 0`.
+pub fn gen_add_trace(log_size: u32, operands: &[(BigUint, BigUint)]) -> Vec<BaseColumn> {
+    assert!(
+        operands.len() <= 1 << log_size,
+        "{} additions do not fit in 2^{log_size} rows",
+        operands.len()
+    );
+    let p_minus_one = prime() - 1u32;
+    let mut columns = vec![Vec::with_capacity(1 << log_size); 4 * N_LIMBS + 1];
+    let padding = (BigUint::zero(), BigUint::zero());
+    for (a, b) in operands
+        .iter()
+        .chain(std::iter::repeat(&padding))
+        .take(1 << log_size)
+    {
+        let (sum, sub_p) = add_mod_p(a, b);
+        let limbs = [a, b, &sum].map(|value| Felt252Limbs::from_biguint(value).to_m31());
+        for (column, value) in columns.iter_mut().zip(limbs.iter().flatten()) {
+            column.push(*value);
+        }
+        columns[3 * N_LIMBS].push(M31::from(sub_p as u32));
+        let complement = Felt252Limbs::from_biguint(&(&p_minus_one - &sum)).to_m31();
+        for (column, value) in columns[3 * N_LIMBS + 1..].iter_mut().zip(complement) {
+            column.push(value);
+        }
+    }
+
+    columns.into_iter().map(BaseColumn::from_iter).collect()
+}
@@ -0,0 +1,40 @@
+//! The IsZero gadget from "Additional Examples": an inverse witness column `inv` such that
+//! `value * inv` is 0 if `value` is zero and 1 otherwise.
+
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::EvalAtRow,
+    core::{
+        backend::simd::column::BaseColumn,
+        fields::{m31::P, FieldExpOps},
+    },
+};
+
+// ANCHOR: is_zero
+/// Reads the inverse column of `value` from the next trace column and returns an expression that
+/// is 1 if `value` is zero and 0 otherwise.
+pub fn is_zero<E: EvalAtRow>(eval: &mut E, value: E::F) -> E::F {
+    let inv = eval.next_trace_mask();
+    let is_zero = E::F::one() - value.clone() * inv.clone();
+
+    // If `value` is not zero, `inv` must be its inverse
+    eval.add_constraint(value * is_zero.clone());
+    // If `value` is zero, `inv` must be zero, so that the witness is unique
+    eval.add_constraint(inv * is_zero.clone());
+
+    is_zero
+}
+// ANCHOR_END: is_zero
+
+/// Generates the inverse column read by [`is_zero`], with 0 wherever `values` is 0.
+pub fn gen_inverse_column(values: &BaseColumn) -> BaseColumn {
+    BaseColumn {
+        // x^(p-2) is the inverse of x, and maps 0 to 0
+        data: values
+            .data
+            .iter()
+            .map(|value| value.pow((P - 2) as u128))
+            .collect(),
+        length: values.length,
+    }
+}
@@ -0,0 +1,6 @@
+//! Building blocks to be used inside `FrameworkEval::evaluate`, with their trace generators.
+
+pub mod bytes;
+pub mod enabler;
+pub mod felt252;
+pub mod is_zero;
@@ -1 +1,15 @@
+pub mod airs;
+pub mod bench;
+pub mod cli;
+pub mod debug;
+pub mod declared;
+pub mod gadgets;
+pub mod pipeline;
+pub mod preprocessed;
+pub mod proof;
+pub mod public_inputs;
+pub mod spreadsheet;
+pub mod witness;
 
+/// The log blowup factor used when evaluating the constraints of the example AIRs.
+pub const LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR: u32 = 1;
@@ -0,0 +1,319 @@
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX},
+    core::{
+        air::{Component, ComponentProver},
+        backend::{
+            simd::{m31::LOG_N_LANES, SimdBackend},
+            BackendForChannel,
+        },
+        channel::{Channel, MerkleChannel},
+        fields::{m31::M31, qm31::SecureField},
+        pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec},
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation, PolyOps},
+            BitReversedOrder,
+        },
+        prover::{prove, verify, ProvingError, StarkProof, VerificationError},
+        vcs::ops::MerkleHasher,
+        ColumnVec,
+    },
+};
+
+use crate::{preprocessed::registry::PreprocessedRegistry, LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR};
+
+/// The largest log size of a column accepted by [`verify_air`]. It bounds the preprocessed trace
+/// the verifier regenerates, and keeps the blown up domains inside the circle group.
+pub const MAX_COLUMN_LOG_SIZE: u32 = 26;
+
+/// Public data that is mixed into the channel as part of the transcript.
+pub trait Statement {
+    fn mix_into(&self, channel: &mut impl Channel);
+}
+
+impl Statement for () {
+    fn mix_into(&self, _channel: &mut impl Channel) {}
+}
+
+/// The set of components that make up an AIR.
+pub trait AirComponents {
+    fn components(&self) -> Vec<&dyn Component>;
+
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>>;
+}
+
+/// Describes how the prover and the verifier build the transcript of an AIR.
+///
+/// The transcript always has the same shape:
+/// 1. commit to the preprocessed trace,
+/// 2. mix `Statement0` (e.g. the log sizes and the public inputs) and commit to the original trace,
+/// 3. draw the lookup elements,
+/// 4. mix `Statement1` (e.g. the claimed sums) and commit to the interaction trace.
+pub trait Air {
+    /// Name of the AIR, recorded in serialized proofs.
+    const NAME: &'static str;
+    /// Names of the components, in the order returned by [`AirComponents::components`].
+    const COMPONENT_NAMES: &'static [&'static str];
+
+    /// Public data known before the original trace is committed.
+    type Statement0: Statement;
+    /// Public data known only after the lookup elements are drawn.
+    type Statement1: Statement;
+    type LookupElements;
+    type Components: AirComponents;
+
+    /// Checks that the components of `statement0` can be built, e.g. that its log sizes are in
+    /// range. The statement is read from an untrusted proof, so [`verify_air`] calls this before
+    /// anything else and rejects the proof rather than panicking.
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String>;
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements;
+
+    /// The columns of the preprocessed trace. The verifier regenerates them from `statement0` and
+    /// rejects proofs that commit to anything else.
+    fn preprocessed_registry(_statement0: &Self::Statement0) -> PreprocessedRegistry {
+        PreprocessedRegistry::new()
+    }
+
+    /// Log sizes of the columns of each tree. The interaction tree is only committed if it is
+    /// present here.
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>>;
+
+    /// Sum of the LogUp claimed sums of all components.
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField;
+
+    /// Sum of the LogUp terms that the verifier computes itself from the public inputs in
+    /// `statement0`. The proof is only accepted if it cancels out the claimed sums.
+    fn public_sum(
+        _statement0: &Self::Statement0,
+        _lookup_elements: &Self::LookupElements,
+    ) -> SecureField {
+        SecureField::zero()
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components;
+}
+
+/// The prover side of an [`Air`]: the trace generators.
+pub trait AirProver: Air {
+    /// Data passed from the original trace generation to the interaction trace generation.
+    type Witness;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    );
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    );
+}
+
+pub struct AirProof<A: Air, H: MerkleHasher> {
+    pub statement0: A::Statement0,
+    pub statement1: A::Statement1,
+    pub stark_proof: StarkProof<H>,
+}
+
+// ANCHOR: prove_air
+pub fn prove_air<A: AirProver, MC: MerkleChannel>(
+    air: &A,
+    config: PcsConfig,
+) -> Result<AirProof<A, MC::H>, ProvingError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let (statement0, trace, witness) = air.gen_trace();
+    let log_sizes = A::log_sizes(&statement0);
+    let max_log_size = log_sizes
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(LOG_N_LANES);
+
+    // Precompute twiddles for evaluating and interpolating the trace
+    let twiddles = SimdBackend::precompute_twiddles(
+        CanonicCoset::new(
+            max_log_size + LOG_CONSTRAINT_EVAL_BLOWUP_FACTOR + config.fri_config.log_blowup_factor,
+        )
+        .circle_domain()
+        .half_coset,
+    );
+
+    // Create the channel and commitment scheme
+    let channel = &mut MC::C::default();
+    let mut commitment_scheme = CommitmentSchemeProver::<SimdBackend, MC>::new(config, &twiddles);
+
+    // Commit to the preprocessed trace
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(A::preprocessed_registry(&statement0).gen_trace());
+    tree_builder.commit(channel);
+
+    // Commit to statement 0 and the original trace
+    statement0.mix_into(channel);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(trace);
+    tree_builder.commit(channel);
+
+    // Draw lookup elements
+    let lookup_elements = A::draw_lookup_elements(channel);
+
+    // Commit to statement 1 and the interaction trace
+    let (statement1, interaction_trace) = air.gen_interaction_trace(&witness, &lookup_elements);
+    statement1.mix_into(channel);
+    if log_sizes.len() > INTERACTION_TRACE_IDX {
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(interaction_trace);
+        tree_builder.commit(channel);
+    } else {
+        assert!(
+            interaction_trace.is_empty(),
+            "interaction trace generated but no interaction tree in log sizes"
+        );
+    }
+
+    let components = A::components(&statement0, &lookup_elements, &statement1);
+    let stark_proof = prove(&components.component_provers(), channel, commitment_scheme)?;
+
+    Ok(AirProof {
+        statement0,
+        statement1,
+        stark_proof,
+    })
+}
+// ANCHOR_END: prove_air
+
+/// The root of the Merkle tree the prover commits to `preprocessed` with.
+fn preprocessed_root<MC: MerkleChannel>(
+    preprocessed: ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    config: PcsConfig,
+) -> <MC::H as MerkleHasher>::Hash
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let max_log_size = preprocessed
+        .iter()
+        .map(|column| column.domain.log_size())
+        .max()
+        .unwrap_or(LOG_N_LANES);
+    let twiddles = SimdBackend::precompute_twiddles(
+        CanonicCoset::new(max_log_size + config.fri_config.log_blowup_factor)
+            .circle_domain()
+            .half_coset,
+    );
+    let mut commitment_scheme = CommitmentSchemeProver::<SimdBackend, MC>::new(config, &twiddles);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(preprocessed);
+    tree_builder.commit(&mut MC::C::default());
+    commitment_scheme.roots()[PREPROCESSED_TRACE_IDX]
+}
+
+// ANCHOR: verify_air
+pub fn verify_air<A: Air, MC: MerkleChannel>(
+    proof: AirProof<A, MC::H>,
+    config: PcsConfig,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let AirProof {
+        statement0,
+        statement1,
+        stark_proof,
+    } = proof;
+
+    A::check_statement(&statement0)
+        .map_err(|err| VerificationError::InvalidStructure(format!("invalid statement: {err}")))?;
+    let log_sizes = A::log_sizes(&statement0);
+    if let Some(log_size) = log_sizes
+        .iter()
+        .flatten()
+        .find(|log_size| !(LOG_N_LANES..=MAX_COLUMN_LOG_SIZE).contains(log_size))
+    {
+        return Err(VerificationError::InvalidStructure(format!(
+            "column log size {log_size} is not between {LOG_N_LANES} and {MAX_COLUMN_LOG_SIZE}"
+        )));
+    }
+    // One commitment per tree plus the composition polynomial.
+    if stark_proof.commitments.len() != log_sizes.len() + 1 {
+        return Err(VerificationError::InvalidStructure(format!(
+            "expected {} commitments, got {}",
+            log_sizes.len() + 1,
+            stark_proof.commitments.len()
+        )));
+    }
+
+    // The preprocessed trace is fixed by the statement, not chosen by the prover
+    let preprocessed = A::preprocessed_registry(&statement0).gen_trace();
+    if stark_proof.commitments[PREPROCESSED_TRACE_IDX]
+        != preprocessed_root::<MC>(preprocessed, config)
+    {
+        return Err(VerificationError::InvalidStructure(
+            "the preprocessed trace commitment does not match the statement".to_string(),
+        ));
+    }
+
+    // Create channel and commitment scheme
+    let channel = &mut MC::C::default();
+    let commitment_scheme = &mut CommitmentSchemeVerifier::<MC>::new(config);
+
+    // Preprocessed columns
+    commitment_scheme.commit(
+        stark_proof.commitments[PREPROCESSED_TRACE_IDX],
+        &log_sizes[PREPROCESSED_TRACE_IDX],
+        channel,
+    );
+
+    // Statement 0 and trace columns
+    statement0.mix_into(channel);
+    commitment_scheme.commit(
+        stark_proof.commitments[ORIGINAL_TRACE_IDX],
+        &log_sizes[ORIGINAL_TRACE_IDX],
+        channel,
+    );
+
+    // Draw lookup elements
+    let lookup_elements = A::draw_lookup_elements(channel);
+
+    // ANCHOR: check_logup_sum
+    // Verify that the claimed sums cancel out the public inputs
+    let logup_sum = A::claimed_sum(&statement1) + A::public_sum(&statement0, &lookup_elements);
+    if logup_sum != SecureField::zero() {
+        return Err(VerificationError::InvalidStructure(format!(
+            "LogUp sum is {logup_sum:?}, expected zero"
+        )));
+    }
+    // ANCHOR_END: check_logup_sum
+
+    // Statement 1 and interaction columns
+    statement1.mix_into(channel);
+    if log_sizes.len() > INTERACTION_TRACE_IDX {
+        commitment_scheme.commit(
+            stark_proof.commitments[INTERACTION_TRACE_IDX],
+            &log_sizes[INTERACTION_TRACE_IDX],
+            channel,
+        );
+    }
+
+    let components = A::components(&statement0, &lookup_elements, &statement1);
+    verify(
+        &components.components(),
+        channel,
+        commitment_scheme,
+        stark_proof,
+    )
+}
+// ANCHOR_END: verify_air
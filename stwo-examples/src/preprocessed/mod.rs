@@ -0,0 +1,384 @@
+//! Reusable preprocessed columns.
+//!
+//! Every column is described by its values in row order, and [`PreprocessedColumn::gen_column`]
+//! converts them to bit-reversed circle domain order before building the evaluation, so that masks
+//! like `[-1, 0]` see the rows in the intended order.
+
+pub mod registry;
+
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::preprocessed_columns::PreProcessedColumnId,
+    core::{
+        backend::simd::{column::BaseColumn, m31::LOG_N_LANES, SimdBackend},
+        fields::m31::M31,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation},
+            BitReversedOrder,
+        },
+        utils::bit_reverse_coset_to_circle_domain_order,
+    },
+};
+
+// ANCHOR: preprocessed_column
+/// A column of the preprocessed trace, identified by its id.
+pub trait PreprocessedColumn {
+    fn log_size(&self) -> u32;
+
+    fn id(&self) -> PreProcessedColumnId;
+
+    /// The value of the column at `row`, in row order.
+    fn value_at_row(&self, row: usize) -> M31;
+
+    /// Generates the column in bit-reversed circle domain order, ready to be committed. Panics if
+    /// the column is smaller than a SIMD vector, which the SIMD backend cannot commit to.
+    fn gen_column(&self) -> CircleEvaluation<SimdBackend, M31, BitReversedOrder> {
+        assert!(
+            self.log_size() >= LOG_N_LANES,
+            "preprocessed column `{}` has log size {}, below the {LOG_N_LANES} of a SIMD vector",
+            self.id().id,
+            self.log_size()
+        );
+        let mut col =
+            BaseColumn::from_iter((0..1 << self.log_size()).map(|row| self.value_at_row(row)));
+
+        // Convert the column to bit-reversed circle domain order
+        bit_reverse_coset_to_circle_domain_order(col.as_mut_slice());
+
+        CircleEvaluation::new(CanonicCoset::new(self.log_size()).circle_domain(), col)
+    }
+}
+// ANCHOR_END: preprocessed_column
+
+// ANCHOR: is_first
+/// 1 on the first row, 0 elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IsFirst {
+    pub log_size: u32,
+}
+
+impl IsFirst {
+    pub fn new(log_size: u32) -> Self {
+        Self { log_size }
+    }
+}
+
+impl PreprocessedColumn for IsFirst {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!("is_first_{}", self.log_size),
+        }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        M31::from((row == 0) as u32)
+    }
+}
+// ANCHOR_END: is_first
+
+/// 1 on the last row, 0 elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IsLast {
+    pub log_size: u32,
+}
+
+impl IsLast {
+    pub fn new(log_size: u32) -> Self {
+        Self { log_size }
+    }
+}
+
+impl PreprocessedColumn for IsLast {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!("is_last_{}", self.log_size),
+        }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        M31::from((row == (1 << self.log_size) - 1) as u32)
+    }
+}
+
+/// 1 on every `step`-th row starting from the first, 0 elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IsStep {
+    pub log_size: u32,
+    pub step: usize,
+}
+
+impl IsStep {
+    pub fn new(log_size: u32, step: usize) -> Self {
+        assert!(step > 0, "step must be positive");
+        Self { log_size, step }
+    }
+}
+
+impl PreprocessedColumn for IsStep {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!("is_step_{}_{}", self.step, self.log_size),
+        }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        M31::from((row % self.step == 0) as u32)
+    }
+}
+
+/// The sequence `0, 1, ..., 2^log_size - 1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Seq {
+    pub log_size: u32,
+}
+
+impl Seq {
+    pub fn new(log_size: u32) -> Self {
+        Self { log_size }
+    }
+}
+
+impl PreprocessedColumn for Seq {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!("seq_{}", self.log_size),
+        }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        M31::from(row)
+    }
+}
+
+// ANCHOR: range_check
+/// All the values that fit in `n_bits` bits, one per row, repeated to fill `2^log_size` rows.
+///
+/// The table is repeated when it is smaller than the component using it, or than a SIMD vector: the
+/// repeated rows are neutral as long as their multiplicity is 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeCheck {
+    pub n_bits: u32,
+    pub log_size: u32,
+}
+
+impl RangeCheck {
+    /// The table on its own, repeated to `2^LOG_N_LANES` rows if it has fewer.
+    pub fn new(n_bits: u32) -> Self {
+        Self::repeated(n_bits, n_bits.max(LOG_N_LANES))
+    }
+
+    pub fn repeated(n_bits: u32, log_size: u32) -> Self {
+        assert!(
+            log_size >= n_bits,
+            "a {n_bits}-bit table does not fit in 2^{log_size} rows"
+        );
+        Self { n_bits, log_size }
+    }
+}
+
+impl PreprocessedColumn for RangeCheck {
+    fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        let id = if self.log_size == self.n_bits {
+            format!("range_check_{}_bits", self.n_bits)
+        } else {
+            format!("range_check_{}_bits_log_{}", self.n_bits, self.log_size)
+        };
+        PreProcessedColumnId { id }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        M31::from(row & ((1 << self.n_bits) - 1))
+    }
+}
+// ANCHOR_END: range_check
+
+/// Column `index` of the table of all the tuples whose `i`-th element fits in `n_bits[i]` bits.
+/// Row `r` holds the tuple packed in `r`, the first element in the most significant bits.
+///
+/// The table has at least `2^LOG_N_LANES` rows: when there are fewer tuples, it repeats itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeCheckTuple {
+    pub n_bits: Vec<u32>,
+    pub index: usize,
+}
+
+impl RangeCheckTuple {
+    /// All the columns of the table.
+    pub fn columns(n_bits: &[u32]) -> Vec<Self> {
+        (0..n_bits.len())
+            .map(|index| Self {
+                n_bits: n_bits.to_vec(),
+                index,
+            })
+            .collect()
+    }
+}
+
+impl PreprocessedColumn for RangeCheckTuple {
+    fn log_size(&self) -> u32 {
+        self.n_bits.iter().sum::<u32>().max(LOG_N_LANES)
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!(
+                "range_check_{}_bits_{}",
+                self.n_bits.iter().join("_"),
+                self.index
+            ),
+        }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        let shift = self.n_bits[self.index + 1..].iter().sum::<u32>();
+        let mask = (1 << self.n_bits[self.index]) - 1;
+        M31::from((row >> shift) & mask)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BitwiseOp {
+    Xor,
+    And,
+    Or,
+}
+
+impl BitwiseOp {
+    pub fn apply(&self, a: u32, b: u32) -> u32 {
+        match self {
+            Self::Xor => a ^ b,
+            Self::And => a & b,
+            Self::Or => a | b,
+        }
+    }
+}
+
+impl fmt::Display for BitwiseOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xor => write!(f, "xor"),
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+        }
+    }
+}
+
+/// A column of the truth table of a bitwise operation on `n_bits`-bit operands. The table has
+/// `2^(2 * n_bits)` rows: row `r` holds `a = r >> n_bits`, `b = r & (2^n_bits - 1)` and `a op b`.
+///
+/// The operand columns do not depend on the operation, so they are shared between the XOR, AND
+/// and OR tables of the same width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseTable {
+    Lhs { n_bits: u32 },
+    Rhs { n_bits: u32 },
+    Result { op: BitwiseOp, n_bits: u32 },
+}
+
+impl BitwiseTable {
+    /// The three columns `a`, `b` and `a op b` of the table.
+    pub fn columns(op: BitwiseOp, n_bits: u32) -> [Self; 3] {
+        [
+            Self::Lhs { n_bits },
+            Self::Rhs { n_bits },
+            Self::Result { op, n_bits },
+        ]
+    }
+
+    fn n_bits(&self) -> u32 {
+        match *self {
+            Self::Lhs { n_bits } | Self::Rhs { n_bits } | Self::Result { n_bits, .. } => n_bits,
+        }
+    }
+}
+
+impl PreprocessedColumn for BitwiseTable {
+    fn log_size(&self) -> u32 {
+        2 * self.n_bits()
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        let id = match self {
+            Self::Lhs { n_bits } => format!("bitwise_{n_bits}_lhs"),
+            Self::Rhs { n_bits } => format!("bitwise_{n_bits}_rhs"),
+            Self::Result { op, n_bits } => format!("{op}_{n_bits}_result"),
+        };
+        PreProcessedColumnId { id }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        let n_bits = self.n_bits();
+        let a = (row >> n_bits) as u32;
+        let b = (row & ((1 << n_bits) - 1)) as u32;
+        M31::from(match self {
+            Self::Lhs { .. } => a,
+            Self::Rhs { .. } => b,
+            Self::Result { op, .. } => op.apply(a, b),
+        })
+    }
+}
+
+/// A column of the table of every byte split at every bit: row `256 * shift + byte` holds `shift`,
+/// `byte` and the `shift` low bits of `byte`, for `shift` in `0..8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteSplit {
+    Shift,
+    Byte,
+    Low,
+}
+
+impl ByteSplit {
+    pub const LOG_SIZE: u32 = 11;
+
+    /// The three columns `shift`, `byte` and `low` of the table.
+    pub fn columns() -> [Self; 3] {
+        [Self::Shift, Self::Byte, Self::Low]
+    }
+}
+
+impl PreprocessedColumn for ByteSplit {
+    fn log_size(&self) -> u32 {
+        Self::LOG_SIZE
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        let id = match self {
+            Self::Shift => "byte_split_shift",
+            Self::Byte => "byte_split_byte",
+            Self::Low => "byte_split_low",
+        };
+        PreProcessedColumnId { id: id.to_string() }
+    }
+
+    fn value_at_row(&self, row: usize) -> M31 {
+        let (shift, byte) = (row >> 8, row & 0xff);
+        M31::from(match self {
+            Self::Shift => shift,
+            Self::Byte => byte,
+            Self::Low => byte & ((1 << shift) - 1),
+        })
+    }
+}
@@ -0,0 +1,137 @@
+//! A registry that owns the preprocessed columns of an AIR, so that the committed preprocessed
+//! tree and the [`TraceLocationAllocator`] given to the components always agree on the column
+//! order.
+//!
+//! Components are created through [`ComponentBuilder`], which checks in debug builds that they
+//! only read registered columns: the allocator would otherwise silently append a missing column,
+//! and the component would read a column that is not in the committed tree.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, FrameworkComponent, FrameworkEval,
+        InfoEvaluator, TraceLocationAllocator,
+    },
+    core::{
+        backend::simd::SimdBackend,
+        fields::{m31::M31, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        ColumnVec,
+    },
+};
+use thiserror::Error;
+
+use super::PreprocessedColumn;
+
+#[derive(Debug, Error)]
+#[error("preprocessed columns [{}] are referenced but not registered", .0.join(", "))]
+pub struct UnregisteredColumns(pub Vec<String>);
+
+/// The preprocessed columns of an AIR, deduplicated by id and kept in a canonical order (sorted
+/// by id).
+#[derive(Default)]
+pub struct PreprocessedRegistry {
+    columns: BTreeMap<String, Box<dyn PreprocessedColumn>>,
+}
+
+impl PreprocessedRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `column` and returns its id. Registering a column whose id is already present is
+    /// a no-op, so components can register the columns they use independently.
+    pub fn add(&mut self, column: impl PreprocessedColumn + 'static) -> PreProcessedColumnId {
+        let id = column.id();
+        self.columns
+            .entry(id.id.clone())
+            .or_insert_with(|| Box::new(column));
+        id
+    }
+
+    /// The ids of the registered columns, in commitment order.
+    pub fn ids(&self) -> Vec<PreProcessedColumnId> {
+        self.columns
+            .keys()
+            .map(|id| PreProcessedColumnId { id: id.clone() })
+            .collect()
+    }
+
+    /// The log sizes of the registered columns, in commitment order.
+    pub fn log_sizes(&self) -> Vec<u32> {
+        self.columns
+            .values()
+            .map(|column| column.log_size())
+            .collect()
+    }
+
+    /// Generates the preprocessed trace, in commitment order.
+    pub fn gen_trace(&self) -> ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+        self.columns
+            .values()
+            .map(|column| column.gen_column())
+            .collect()
+    }
+
+    /// Builds the allocator the components must be created with.
+    pub fn trace_location_allocator(&self) -> TraceLocationAllocator {
+        TraceLocationAllocator::new_with_preproccessed_columns(&self.ids())
+    }
+
+    /// Consumes the registry into a builder of the components that read its columns.
+    pub fn component_builder(self) -> ComponentBuilder {
+        ComponentBuilder {
+            allocator: self.trace_location_allocator(),
+            registry: self,
+        }
+    }
+
+    /// Checks that every preprocessed column read by `eval` is registered.
+    pub fn check_references<E: FrameworkEval>(&self, eval: &E) -> Result<(), UnregisteredColumns> {
+        let missing = referenced_columns(eval)
+            .into_iter()
+            .map(|column| column.id)
+            .filter(|id| !self.columns.contains_key(id))
+            .unique()
+            .collect_vec();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(UnregisteredColumns(missing))
+        }
+    }
+}
+
+/// Creates the components of an AIR with the allocator of a [`PreprocessedRegistry`].
+pub struct ComponentBuilder {
+    registry: PreprocessedRegistry,
+    allocator: TraceLocationAllocator,
+}
+
+impl ComponentBuilder {
+    /// Creates the next component. In debug builds, panics if `eval` reads a preprocessed column
+    /// that is not registered.
+    pub fn build<E: FrameworkEval>(
+        &mut self,
+        eval: E,
+        claimed_sum: SecureField,
+    ) -> FrameworkComponent<E> {
+        if cfg!(debug_assertions) {
+            if let Err(err) = self.registry.check_references(&eval) {
+                panic!("{err}");
+            }
+        }
+        FrameworkComponent::new(&mut self.allocator, eval, claimed_sum)
+    }
+}
+
+/// The preprocessed columns read by `eval`, in the order it first reads them.
+pub fn referenced_columns<E: FrameworkEval>(eval: &E) -> Vec<PreProcessedColumnId> {
+    eval.evaluate(InfoEvaluator::empty())
+        .preprocessed_columns
+        .into_iter()
+        .unique_by(|column| column.id.clone())
+        .collect()
+}
@@ -0,0 +1,335 @@
+use std::{fs, path::Path};
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use stwo_prover::core::{
+    channel::MerkleChannel,
+    fri::FriConfig,
+    pcs::PcsConfig,
+    prover::StarkProof,
+    vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+};
+use thiserror::Error;
+
+use crate::pipeline::{Air, AirProof};
+
+/// Version of the serialized proof format. Bump it whenever the layout of [`ProofEnvelope`]
+/// changes.
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+
+/// The largest serialized proof that is decoded, in bytes. Proofs come from untrusted sources, and
+/// this keeps a crafted length prefix from allocating more than that.
+pub const MAX_PROOF_SIZE: u64 = 1 << 28;
+
+/// The options of `bincode::serialize`, with the size limit of [`MAX_PROOF_SIZE`].
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(MAX_PROOF_SIZE)
+}
+
+/// Stable name of a Merkle channel, recorded in the header of serialized proofs.
+pub trait ChannelName {
+    const NAME: &'static str;
+}
+
+impl ChannelName for Blake2sMerkleChannel {
+    const NAME: &'static str = "blake2s";
+}
+
+impl ChannelName for Poseidon252MerkleChannel {
+    const NAME: &'static str = "poseidon252";
+}
+
+/// Serializable copy of [`PcsConfig`], so that the proof format does not depend on the layout of
+/// the prover's config type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigHeader {
+    pub pow_bits: u32,
+    pub log_blowup_factor: u32,
+    pub log_last_layer_degree_bound: u32,
+    pub n_queries: usize,
+}
+
+impl From<PcsConfig> for ConfigHeader {
+    fn from(config: PcsConfig) -> Self {
+        Self {
+            pow_bits: config.pow_bits,
+            log_blowup_factor: config.fri_config.log_blowup_factor,
+            log_last_layer_degree_bound: config.fri_config.log_last_layer_degree_bound,
+            n_queries: config.fri_config.n_queries,
+        }
+    }
+}
+
+impl From<ConfigHeader> for PcsConfig {
+    fn from(header: ConfigHeader) -> Self {
+        Self {
+            pow_bits: header.pow_bits,
+            fri_config: FriConfig::new(
+                header.log_last_layer_degree_bound,
+                header.log_blowup_factor,
+                header.n_queries,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofHeader {
+    pub version: u32,
+    pub air: String,
+    pub channel: String,
+    pub config: ConfigHeader,
+    pub components: Vec<String>,
+}
+
+impl ProofHeader {
+    pub fn new<A: Air, MC: ChannelName>(config: PcsConfig) -> Self {
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            air: A::NAME.to_string(),
+            channel: MC::NAME.to_string(),
+            config: config.into(),
+            components: A::COMPONENT_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+
+    /// Checks that a proof with this header can be verified as a proof of `A` over `MC`, made with
+    /// the config the verifier expects rather than one of the prover's choosing.
+    pub fn check<A: Air, MC: ChannelName>(
+        &self,
+        config: PcsConfig,
+    ) -> Result<(), ProofFormatError> {
+        if self.version != PROOF_FORMAT_VERSION {
+            return Err(ProofFormatError::UnsupportedVersion(self.version));
+        }
+        let expected = Self::new::<A, MC>(config);
+        for (field, expected, found) in [
+            ("air", &expected.air, &self.air),
+            ("channel", &expected.channel, &self.channel),
+        ] {
+            if expected != found {
+                return Err(ProofFormatError::HeaderMismatch {
+                    field,
+                    expected: expected.clone(),
+                    found: found.clone(),
+                });
+            }
+        }
+        if expected.config != self.config {
+            return Err(ProofFormatError::HeaderMismatch {
+                field: "config",
+                expected: format!("{:?}", expected.config),
+                found: format!("{:?}", self.config),
+            });
+        }
+        if expected.components != self.components {
+            return Err(ProofFormatError::HeaderMismatch {
+                field: "components",
+                expected: expected.components.join(","),
+                found: self.components.join(","),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProofFormatError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("binary encoding error: {0}")]
+    Binary(#[from] bincode::Error),
+    #[error("JSON encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(
+        "unsupported proof format version {0}, expected {}",
+        PROOF_FORMAT_VERSION
+    )]
+    UnsupportedVersion(u32),
+    #[error("the proof is {0} bytes, at most {} are accepted", MAX_PROOF_SIZE)]
+    TooLarge(u64),
+    #[error("proof header mismatch in `{field}`: expected `{expected}`, found `{found}`")]
+    HeaderMismatch {
+        field: &'static str,
+        expected: String,
+        found: String,
+    },
+}
+
+/// The encodings a [`ProofEnvelope`] can be stored in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofFormat {
+    Binary,
+    Json,
+}
+
+impl ProofFormat {
+    /// Picks the format from the file extension: `.json` is JSON, anything else is binary.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            _ => Self::Binary,
+        }
+    }
+}
+
+/// A proof of an [`Air`] together with its statements and a header describing how it was made.
+///
+/// The binary encoding writes the header first, so that the version and AIR can be checked before
+/// the rest of the proof is decoded.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "A::Statement0: Serialize, A::Statement1: Serialize, StarkProof<MC::H>: Serialize",
+    deserialize = "A::Statement0: DeserializeOwned, A::Statement1: DeserializeOwned, \
+                   StarkProof<MC::H>: DeserializeOwned"
+))]
+pub struct ProofEnvelope<A: Air, MC: MerkleChannel> {
+    pub header: ProofHeader,
+    pub statement0: A::Statement0,
+    pub statement1: A::Statement1,
+    pub stark_proof: StarkProof<MC::H>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "A::Statement0: Serialize, A::Statement1: Serialize, StarkProof<MC::H>: Serialize",
+    deserialize = "A::Statement0: DeserializeOwned, A::Statement1: DeserializeOwned, \
+                   StarkProof<MC::H>: DeserializeOwned"
+))]
+struct ProofBody<A: Air, MC: MerkleChannel> {
+    statement0: A::Statement0,
+    statement1: A::Statement1,
+    stark_proof: StarkProof<MC::H>,
+}
+
+impl<A: Air, MC: MerkleChannel + ChannelName> ProofEnvelope<A, MC>
+where
+    A::Statement0: Serialize + DeserializeOwned,
+    A::Statement1: Serialize + DeserializeOwned,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    pub fn new(proof: AirProof<A, MC::H>, config: PcsConfig) -> Self {
+        Self {
+            header: ProofHeader::new::<A, MC>(config),
+            statement0: proof.statement0,
+            statement1: proof.statement1,
+            stark_proof: proof.stark_proof,
+        }
+    }
+
+    pub fn config(&self) -> PcsConfig {
+        self.header.config.into()
+    }
+
+    pub fn into_proof(self) -> AirProof<A, MC::H> {
+        AirProof {
+            statement0: self.statement0,
+            statement1: self.statement1,
+            stark_proof: self.stark_proof,
+        }
+    }
+
+    /// The size of the STARK proof alone in the binary format, without the header and the
+    /// statements.
+    pub fn stark_proof_size(&self) -> Result<usize, ProofFormatError> {
+        Ok(bincode_options().serialized_size(&self.stark_proof)? as usize)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = bincode_options().serialize(&self.header)?;
+        bincode_options().serialize_into(
+            &mut bytes,
+            &(&self.statement0, &self.statement1, &self.stark_proof),
+        )?;
+        Ok(bytes)
+    }
+
+    /// Decodes a proof of `A` over `MC`, checking its header against the expected `config`.
+    pub fn from_bytes(bytes: &[u8], config: PcsConfig) -> Result<Self, ProofFormatError> {
+        check_size(bytes.len() as u64)?;
+        let mut reader = bytes;
+        let header: ProofHeader = bincode_options().deserialize_from(&mut reader)?;
+        header.check::<A, MC>(config)?;
+        let body: ProofBody<A, MC> = bincode_options().deserialize_from(&mut reader)?;
+        Ok(Self {
+            header,
+            statement0: body.statement0,
+            statement1: body.statement1,
+            stark_proof: body.stark_proof,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String, ProofFormatError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Decodes a proof of `A` over `MC`, checking its header against the expected `config`.
+    pub fn from_json(json: &str, config: PcsConfig) -> Result<Self, ProofFormatError> {
+        check_size(json.len() as u64)?;
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        let header: ProofHeader = serde_json::from_value(
+            value
+                .get_mut("header")
+                .map(serde_json::Value::take)
+                .unwrap_or_default(),
+        )?;
+        header.check::<A, MC>(config)?;
+        let body: ProofBody<A, MC> = serde_json::from_value(value)?;
+        Ok(Self {
+            header,
+            statement0: body.statement0,
+            statement1: body.statement1,
+            stark_proof: body.stark_proof,
+        })
+    }
+
+    pub fn write_to_file(&self, path: &Path, format: ProofFormat) -> Result<(), ProofFormatError> {
+        match format {
+            ProofFormat::Binary => fs::write(path, self.to_bytes()?)?,
+            ProofFormat::Json => fs::write(path, self.to_json()?)?,
+        }
+        Ok(())
+    }
+
+    pub fn read_from_file(
+        path: &Path,
+        format: ProofFormat,
+        config: PcsConfig,
+    ) -> Result<Self, ProofFormatError> {
+        check_size(fs::metadata(path)?.len())?;
+        match format {
+            ProofFormat::Binary => Self::from_bytes(&fs::read(path)?, config),
+            ProofFormat::Json => Self::from_json(&fs::read_to_string(path)?, config),
+        }
+    }
+}
+
+fn check_size(size: u64) -> Result<(), ProofFormatError> {
+    if size > MAX_PROOF_SIZE {
+        return Err(ProofFormatError::TooLarge(size));
+    }
+    Ok(())
+}
+
+/// Reads only the header of a proof file, e.g. to find out which AIR it belongs to.
+pub fn read_header(path: &Path, format: ProofFormat) -> Result<ProofHeader, ProofFormatError> {
+    check_size(fs::metadata(path)?.len())?;
+    match format {
+        ProofFormat::Binary => Ok(bincode_options().deserialize_from(fs::File::open(path)?)?),
+        ProofFormat::Json => {
+            let mut value: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+            Ok(serde_json::from_value(
+                value
+                    .get_mut("header")
+                    .map(serde_json::Value::take)
+                    .unwrap_or_default(),
+            )?)
+        }
+    }
+}
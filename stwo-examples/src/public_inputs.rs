@@ -0,0 +1,79 @@
+//! Public inputs exposed through LogUp, as described in "Additional Examples".
+//!
+//! A component adds its side of the public tuples to a relation like any other entry, while the
+//! other side is left out of the trace: the verifier computes it from the drawn lookup elements
+//! (see [`Air::public_sum`](crate::pipeline::Air::public_sum)) and checks that it cancels out the
+//! claimed sums.
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::Relation,
+    core::{
+        channel::Channel,
+        fields::{m31::M31, qm31::SecureField},
+    },
+};
+
+use crate::pipeline::Statement;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicEntry {
+    /// The multiplicity the verifier adds the tuple with.
+    pub multiplicity: M31,
+    pub values: Vec<M31>,
+}
+
+/// Public tuples of a single relation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicEntries {
+    pub entries: Vec<PublicEntry>,
+}
+
+impl PublicEntries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, multiplicity: M31, values: Vec<M31>) {
+        self.entries.push(PublicEntry {
+            multiplicity,
+            values,
+        });
+    }
+
+    /// Checks that every tuple has the `arity` of the relation it is added to.
+    pub fn check_arity(&self, arity: usize) -> Result<(), String> {
+        match self
+            .entries
+            .iter()
+            .find(|entry| entry.values.len() != arity)
+        {
+            Some(entry) => Err(format!(
+                "public tuples have {arity} elements, got {}",
+                entry.values.len()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Computes `sum(multiplicity / combine(values))` over the public tuples.
+    pub fn logup_sum<R: Relation<M31, SecureField>>(&self, relation: &R) -> SecureField {
+        self.entries.iter().fold(SecureField::zero(), |sum, entry| {
+            sum + SecureField::from(entry.multiplicity) / relation.combine(&entry.values)
+        })
+    }
+}
+
+impl Statement for PublicEntries {
+    fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.entries.len() as u64);
+        for entry in &self.entries {
+            channel.mix_u64(entry.multiplicity.0 as u64);
+            channel.mix_u64(entry.values.len() as u64);
+            for value in &entry.values {
+                channel.mix_u64(value.0 as u64);
+            }
+        }
+    }
+}
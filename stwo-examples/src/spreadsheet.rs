@@ -0,0 +1,203 @@
+//! Imports a trace from a spreadsheet saved as CSV or TSV, as framed in the "Writing a
+//! Spreadsheet" chapter: the first row names the columns and every other row is a row of the
+//! trace, holding an M31 value in every cell.
+//!
+//! Cells are separated by the delimiter alone; quoted cells are not supported.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use stwo_prover::core::{
+    backend::simd::{column::BaseColumn, SimdBackend},
+    fields::m31::{M31, P},
+    poly::{
+        circle::{CanonicCoset, CircleEvaluation},
+        BitReversedOrder,
+    },
+    utils::bit_reverse_coset_to_circle_domain_order,
+    ColumnVec,
+};
+use thiserror::Error;
+
+use crate::gadgets::enabler::padded_log_size;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    /// Picks the delimiter from the file extension: `.tsv` is tab-separated, anything else is
+    /// comma-separated.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("tsv") => Self::Tab,
+            _ => Self::Comma,
+        }
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            Self::Comma => ',',
+            Self::Tab => '\t',
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImportOptions {
+    pub delimiter: Delimiter,
+    /// The smallest log size of the trace, e.g. from `--log-size`. The trace is padded with zero
+    /// rows to the larger of this and the smallest power of two holding the rows.
+    pub min_log_size: u32,
+    /// Whether to reorder the rows with `bit_reverse_coset_to_circle_domain_order`, as needed by
+    /// constraints that compare a row with its neighbours.
+    pub bit_reverse: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::Comma,
+            min_log_size: 0,
+            bit_reverse: false,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SpreadsheetError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the spreadsheet has no header row")]
+    MissingHeader,
+    #[error("column {0} has no name")]
+    UnnamedColumn(usize),
+    #[error("column `{0}` appears twice")]
+    DuplicateColumn(String),
+    #[error("line {line} has {found} cells, expected {expected}")]
+    RowLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("line {line}, column `{column}`: `{cell}` is not an M31 value in [0, {P})")]
+    InvalidCell {
+        line: usize,
+        column: String,
+        cell: String,
+    },
+}
+
+/// A trace imported from a spreadsheet, with its columns in the order of the header.
+pub struct ImportedTrace {
+    pub log_size: u32,
+    /// The number of rows of the spreadsheet, before padding.
+    pub n_rows: usize,
+    pub columns: ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    /// The index of every column by name, which is also the order in which a `FrameworkEval`
+    /// reads them with `next_trace_mask`.
+    pub indices: HashMap<String, usize>,
+}
+
+impl ImportedTrace {
+    pub fn column(
+        &self,
+        name: &str,
+    ) -> Option<&CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+        self.indices.get(name).map(|&index| &self.columns[index])
+    }
+}
+
+/// Parses a canonical M31 value: a decimal integer below `P`, with no sign and no leading zeros.
+fn parse_m31(cell: &str) -> Option<M31> {
+    // `u32::from_str` also accepts a `+` sign and leading zeros
+    if cell.is_empty() || !cell.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if cell.len() > 1 && cell.starts_with('0') {
+        return None;
+    }
+    let value: u32 = cell.parse().ok()?;
+    (value < P).then(|| M31::from(value))
+}
+
+/// Imports the trace held in `text`. Empty lines are skipped.
+pub fn parse_spreadsheet(
+    text: &str,
+    options: ImportOptions,
+) -> Result<ImportedTrace, SpreadsheetError> {
+    let delimiter = options.delimiter.as_char();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines.next().ok_or(SpreadsheetError::MissingHeader)?;
+    let names = header
+        .split(delimiter)
+        .map(|name| name.trim().to_string())
+        .collect::<Vec<_>>();
+    let mut indices = HashMap::new();
+    for (index, name) in names.iter().enumerate() {
+        if name.is_empty() {
+            return Err(SpreadsheetError::UnnamedColumn(index));
+        }
+        if indices.insert(name.clone(), index).is_some() {
+            return Err(SpreadsheetError::DuplicateColumn(name.clone()));
+        }
+    }
+
+    let mut values = vec![vec![]; names.len()];
+    for (line, row) in lines {
+        let cells = row.split(delimiter).collect::<Vec<_>>();
+        if cells.len() != names.len() {
+            return Err(SpreadsheetError::RowLength {
+                line,
+                expected: names.len(),
+                found: cells.len(),
+            });
+        }
+        for ((column, name), cell) in values.iter_mut().zip(&names).zip(cells) {
+            let value = parse_m31(cell.trim()).ok_or_else(|| SpreadsheetError::InvalidCell {
+                line,
+                column: name.clone(),
+                cell: cell.to_string(),
+            })?;
+            column.push(value);
+        }
+    }
+
+    let n_rows = values.first().map_or(0, Vec::len);
+    let log_size = padded_log_size(n_rows).max(options.min_log_size);
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let columns = values
+        .into_iter()
+        .map(|mut column| {
+            column.resize(1 << log_size, M31::from(0));
+            if options.bit_reverse {
+                bit_reverse_coset_to_circle_domain_order(&mut column);
+            }
+            CircleEvaluation::new(domain, BaseColumn::from_iter(column))
+        })
+        .collect();
+    Ok(ImportedTrace {
+        log_size,
+        n_rows,
+        columns,
+        indices,
+    })
+}
+
+/// Imports the trace of the spreadsheet at `path`, whose delimiter is picked from its extension.
+pub fn read_spreadsheet(
+    path: &Path,
+    options: ImportOptions,
+) -> Result<ImportedTrace, SpreadsheetError> {
+    let options = ImportOptions {
+        delimiter: Delimiter::from_path(path),
+        ..options
+    };
+    parse_spreadsheet(&fs::read_to_string(path)?, options)
+}
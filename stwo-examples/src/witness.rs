@@ -0,0 +1,88 @@
+//! Generates traces row by row from a closure, instead of setting the cells of each column.
+//!
+//! The closure is called once for every row of the trace, padding rows included, and the rows are
+//! put in circle domain order, so that row `i + 1` is the one a mask with offset `1` sees from row
+//! `i`, and rows line up with the columns of [`crate::preprocessed`].
+
+use std::{num::NonZeroUsize, thread};
+
+use itertools::Itertools;
+use stwo_prover::core::{
+    backend::simd::{column::BaseColumn, SimdBackend},
+    fields::m31::M31,
+    poly::{
+        circle::{CanonicCoset, CircleEvaluation},
+        BitReversedOrder,
+    },
+    utils::bit_reverse_coset_to_circle_domain_order,
+    ColumnVec,
+};
+
+/// Maps `f` over `0..n` on all the available threads, in order.
+fn par_map<U: Send>(n: usize, f: impl Fn(usize) -> U + Sync) -> Vec<U> {
+    let n_threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = n.div_ceil(n_threads).max(1);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles = (0..n)
+            .step_by(chunk_size)
+            .map(|start| {
+                scope.spawn(move || (start..n.min(start + chunk_size)).map(f).collect_vec())
+            })
+            .collect_vec();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect::<Vec<U>>()
+    })
+}
+
+/// Generates the `N` columns of a trace from its rows, in row order.
+pub fn gen_trace_from_rows<const N: usize>(
+    log_size: u32,
+    rows: &[[M31; N]],
+) -> ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+    assert_eq!(
+        rows.len(),
+        1 << log_size,
+        "a trace of log size {log_size} has {} rows",
+        1 << log_size
+    );
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    par_map(N, |i| {
+        let mut column = rows.iter().map(|row| row[i]).collect_vec();
+        bit_reverse_coset_to_circle_domain_order(&mut column);
+        CircleEvaluation::new(domain, BaseColumn::from_iter(column))
+    })
+}
+
+/// Generates a trace of `2^log_size` rows, where row `i` is `row_fn(i, &previous_row)`. The row
+/// before the first one is all zeros.
+///
+/// The rows are computed one after the other; use [`gen_parallel_trace`] when they do not depend
+/// on the previous one.
+pub fn gen_sequential_trace<const N: usize>(
+    log_size: u32,
+    mut row_fn: impl FnMut(usize, &[M31; N]) -> [M31; N],
+) -> ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+    let mut rows = Vec::with_capacity(1 << log_size);
+    let mut previous_row = [M31::from(0); N];
+    for i in 0..1 << log_size {
+        previous_row = row_fn(i, &previous_row);
+        rows.push(previous_row);
+    }
+    gen_trace_from_rows(log_size, &rows)
+}
+
+/// Generates a trace of `2^log_size` rows, where row `i` is `row_fn(i)`, computing the rows in
+/// parallel.
+pub fn gen_parallel_trace<const N: usize>(
+    log_size: u32,
+    row_fn: impl Fn(usize) -> [M31; N] + Sync,
+) -> ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> {
+    gen_trace_from_rows(log_size, &par_map(1 << log_size, row_fn))
+}
@@ -0,0 +1,51 @@
+//! The metrics of the Benchmarks Report and their CSV output.
+
+use stwo_examples::{
+    airs::simple::SimpleAir,
+    bench::{measure, to_csv, BenchRecord, CSV_COLUMNS},
+    pipeline::prove_air,
+    proof::{ConfigHeader, ProofEnvelope},
+};
+use stwo_prover::core::{
+    backend::simd::m31::LOG_N_LANES, pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel,
+};
+
+const LOG_SIZE: u32 = LOG_N_LANES;
+
+#[test]
+fn test_proof_size_excludes_header_and_statements() {
+    let config = PcsConfig::default();
+    let air = SimpleAir { log_size: LOG_SIZE };
+    let metrics = measure::<_, Blake2sMerkleChannel>(&air, 1 << LOG_SIZE, config).unwrap();
+
+    let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+    let envelope = ProofEnvelope::<SimpleAir, Blake2sMerkleChannel>::new(proof, config);
+    let proof_size = envelope.stark_proof_size().unwrap();
+    let file_size = envelope.to_bytes().unwrap().len();
+    assert!(proof_size < file_size);
+
+    // The query positions vary between proofs, and so does the size of the STARK proof, but not
+    // that of the header and statements
+    assert!(metrics.proof_size_kb < metrics.file_size_kb);
+    assert_eq!(
+        (metrics.file_size_kb - metrics.proof_size_kb) * 1024.,
+        (file_size - proof_size) as f64
+    );
+}
+
+#[test]
+fn test_csv_has_a_field_per_column() {
+    let config = PcsConfig::default();
+    let air = SimpleAir { log_size: LOG_SIZE };
+    let record = BenchRecord {
+        metrics: measure::<_, Blake2sMerkleChannel>(&air, 1 << LOG_SIZE, config).unwrap(),
+        log_size: LOG_SIZE,
+        channel: "blake2s".to_string(),
+        config: ConfigHeader::from(config),
+    };
+    let csv = to_csv(&[record]);
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], CSV_COLUMNS.join(","));
+    assert_eq!(lines[1].split(',').count(), CSV_COLUMNS.len());
+}
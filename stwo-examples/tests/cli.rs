@@ -0,0 +1,41 @@
+//! Parsing of the flags shared by the examples and the `stwo-prove` and `stwo-verify` tools.
+
+use stwo_examples::cli::{parse_log_size, positional_args, MAX_LOG_SIZE};
+use stwo_prover::core::backend::simd::m31::LOG_N_LANES;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_log_size_defaults_to_a_simd_vector() {
+    assert_eq!(
+        parse_log_size(args(&["simple", "proof.bin"])),
+        Ok(LOG_N_LANES)
+    );
+}
+
+#[test]
+fn test_log_size_is_bounded() {
+    for log_size in [LOG_N_LANES, MAX_LOG_SIZE] {
+        let flag = args(&["--log-size", &log_size.to_string()]);
+        assert_eq!(parse_log_size(flag), Ok(log_size));
+    }
+    for log_size in ["0", "3", "25", "4294967295", "-1", "ten"] {
+        assert!(parse_log_size(args(&["--log-size", log_size])).is_err());
+    }
+    assert!(parse_log_size(args(&["--log-size"])).is_err());
+}
+
+#[test]
+fn test_flag_values_are_not_positional() {
+    let parsed = positional_args(&args(&[
+        "simple",
+        "--log-size",
+        "5",
+        "proof.bin",
+        "--n-queries",
+        "3",
+    ]));
+    assert_eq!(parsed, ["simple", "proof.bin"]);
+}
@@ -0,0 +1,299 @@
+//! Checks that the constraints of the example AIRs vanish on the traces of their witness
+//! generators, over many seeds.
+//!
+//! Only the constraints added with `add_constraint` are evaluated, so the components whose only
+//! constraints are LogUp relations have no case here: those of the static and dynamic lookups,
+//! the public permutation, the bitwise and range check examples, the reads of the memory example,
+//! the memory and range check components of mini Cairo, and the bitwise, byte split and round
+//! constant tables of SHA-256 and SHA3-256. A case would pass on any trace; their relations only
+//! balance if the proofs of `prove_and_verify.rs` verify.
+
+use rand::{rngs::StdRng, Rng};
+use stwo_examples::{
+    airs::{
+        components::{
+            gen_computing_trace, gen_scheduling_trace, ComputationLookupElements, ComputingEval,
+        },
+        fibonacci::{self, gen_fibonacci_trace, FibonacciEval},
+        keccak::{self, sponge::SpongeEval, Sha3Air, Sha3Elements, Sha3Row, Sha3Witness},
+        memory::{self, MemoryElements, MemoryEval, MemoryTable},
+        mini_cairo::{
+            fibonacci_program, opcode::OpcodeEval, verify_instruction::VerifyInstructionEval,
+            vm::Opcode, MiniCairoAir, MiniCairoElements, MiniCairoWitness, MAX_FIBONACCI_STEPS,
+        },
+        sha256::{
+            self, chain::ChainEval, compression::CompressionEval, schedule::ScheduleEval,
+            Sha256Air, Sha256Elements, Sha256Row, Sha256Witness,
+        },
+    },
+    debug::{
+        consistency::{assert_consistent, DEFAULT_N_SEEDS},
+        constraints::DebugTrace,
+    },
+    pipeline::AirProver,
+    preprocessed::{registry::PreprocessedRegistry, IsFirst, PreprocessedColumn},
+};
+use stwo_prover::core::{
+    backend::simd::{column::BaseColumn, m31::LOG_N_LANES, SimdBackend},
+    fields::m31::{M31, P},
+    poly::{
+        circle::{CanonicCoset, CircleEvaluation},
+        BitReversedOrder,
+    },
+};
+
+/// The trace of a component whose columns are committed as generated, on the circle domain of
+/// `log_size`, next to the preprocessed columns of `registry`.
+fn committed_trace(
+    registry: &PreprocessedRegistry,
+    log_size: u32,
+    columns: &[BaseColumn],
+) -> DebugTrace {
+    let preprocessed = registry.gen_trace();
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let trace: Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>> = columns
+        .iter()
+        .map(|column| CircleEvaluation::new(domain, column.clone()))
+        .collect();
+    DebugTrace::new(
+        &registry
+            .ids()
+            .into_iter()
+            .zip(&preprocessed)
+            .collect::<Vec<_>>(),
+        &trace,
+    )
+}
+
+#[test]
+fn test_computing_matches_scheduling_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let log_size = rng.gen_range(LOG_N_LANES..LOG_N_LANES + 4);
+        let scheduling_trace = gen_scheduling_trace(log_size, rng);
+        let computing_trace =
+            gen_computing_trace(log_size, &scheduling_trace[0], &scheduling_trace[1]);
+        let eval = ComputingEval {
+            log_size,
+            lookup_elements: ComputationLookupElements::dummy(),
+        };
+        (eval, DebugTrace::new(&[], &computing_trace))
+    });
+}
+
+#[test]
+fn test_fibonacci_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let log_n_steps = rng.gen_range(LOG_N_LANES..LOG_N_LANES + 4);
+        let n_instances = rng.gen_range(1..4);
+        let registry = fibonacci::preprocessed_registry(log_n_steps);
+        let preprocessed = registry.gen_trace();
+        let eval = FibonacciEval {
+            log_n_steps,
+            n_instances,
+            result: fibonacci::fibonacci(1 << log_n_steps),
+        };
+        (
+            eval,
+            DebugTrace::new(
+                &registry
+                    .ids()
+                    .into_iter()
+                    .zip(&preprocessed)
+                    .collect::<Vec<_>>(),
+                &gen_fibonacci_trace(log_n_steps, n_instances),
+            ),
+        )
+    });
+}
+
+#[test]
+fn test_memory_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        // Sparse addresses, so that the generator fills holes, each read a random number of times
+        let mut memory = MemoryTable::new();
+        for address in 0..1 << (LOG_N_LANES + 2) {
+            if rng.gen_bool(0.5) {
+                memory.insert(address, M31::from(rng.gen_range(0..P)));
+                for _ in 0..rng.gen_range(0..3) {
+                    memory.read(address);
+                }
+            }
+        }
+        // Padded beyond the addresses half of the time
+        let log_size = memory.log_size() + rng.gen_range(0..2);
+        let eval = MemoryEval {
+            is_first_id: IsFirst::new(log_size).id(),
+            log_size,
+            memory_elements: MemoryElements::dummy(),
+        };
+        let trace = committed_trace(
+            &memory::preprocessed_registry(log_size),
+            log_size,
+            &memory.gen_trace(log_size),
+        );
+        (eval, trace)
+    });
+}
+
+/// The witness of the mini Cairo AIR for the Fibonacci program with a random number of steps.
+fn mini_cairo_witness(rng: &mut StdRng) -> MiniCairoWitness {
+    let program = fibonacci_program(rng.gen_range(1..=MAX_FIBONACCI_STEPS));
+    let (_, _, witness) = MiniCairoAir::new(&program).unwrap().gen_trace();
+    witness
+}
+
+#[test]
+fn test_mini_cairo_verify_instruction_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let witness = mini_cairo_witness(rng);
+        let log_size = witness.statement0.verify_instruction_log_size;
+        let elements = MiniCairoElements::dummy();
+        let eval = VerifyInstructionEval {
+            log_size,
+            memory_elements: elements.memory,
+            instruction_elements: elements.instruction,
+            range_check_elements: elements.range_check,
+        };
+        let trace = committed_trace(
+            &PreprocessedRegistry::new(),
+            log_size,
+            &witness.verify_instruction,
+        );
+        (eval, trace)
+    });
+}
+
+#[test]
+fn test_mini_cairo_opcode_traces() {
+    for opcode in Opcode::ALL {
+        assert_consistent(DEFAULT_N_SEEDS, |rng| {
+            let witness = mini_cairo_witness(rng);
+            let log_size = witness.statement0.opcode_log_sizes[opcode as usize];
+            let elements = MiniCairoElements::dummy();
+            let eval = OpcodeEval {
+                opcode,
+                log_size,
+                memory_elements: elements.memory,
+                instruction_elements: elements.instruction,
+                registers_elements: elements.registers,
+            };
+            let trace = committed_trace(
+                &PreprocessedRegistry::new(),
+                log_size,
+                &witness.opcodes[opcode as usize],
+            );
+            (eval, trace)
+        });
+    }
+}
+
+/// One or two random messages of at most `max_len` bytes.
+fn random_messages(rng: &mut StdRng, max_len: usize) -> Vec<Vec<u8>> {
+    (0..rng.gen_range(1..3))
+        .map(|_| {
+            let len = rng.gen_range(0..=max_len);
+            (0..len).map(|_| rng.gen()).collect()
+        })
+        .collect()
+}
+
+/// Hashes random messages in chains of one or two hashes, and returns the log size of the
+/// component whose rows are picked by `rows`, with its trace.
+fn sha256_trace(
+    rng: &mut StdRng,
+    log_size: fn(usize) -> u32,
+    rows: fn(&Sha256Witness) -> &[Sha256Row],
+) -> (u32, DebugTrace) {
+    let air = Sha256Air {
+        messages: random_messages(rng, sha256::reference::MAX_SINGLE_BLOCK_LEN),
+        chain_length: rng.gen_range(1..3),
+    };
+    let (_, witness) = air.gen_witness();
+    let log_size = log_size(air.n_instances());
+    let trace = committed_trace(
+        &sha256::preprocessed_registry(air.n_instances()),
+        log_size,
+        &Sha256Row::gen_trace(log_size, rows(&witness)),
+    );
+    (log_size, trace)
+}
+
+#[test]
+fn test_sha256_schedule_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let (log_size, trace) =
+            sha256_trace(rng, sha256::rounds_log_size, |witness| &witness.schedule);
+        let elements = Sha256Elements::dummy();
+        (ScheduleEval { log_size, elements }, trace)
+    });
+}
+
+#[test]
+fn test_sha256_round_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let (log_size, trace) =
+            sha256_trace(rng, sha256::rounds_log_size, |witness| &witness.round);
+        let elements = Sha256Elements::dummy();
+        (sha256::round::RoundEval { log_size, elements }, trace)
+    });
+}
+
+#[test]
+fn test_sha256_compression_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let (log_size, trace) = sha256_trace(rng, sha256::instances_log_size, |witness| {
+            &witness.compression
+        });
+        let elements = Sha256Elements::dummy();
+        (CompressionEval { log_size, elements }, trace)
+    });
+}
+
+#[test]
+fn test_sha256_chain_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let (log_size, trace) =
+            sha256_trace(rng, sha256::instances_log_size, |witness| &witness.chain);
+        let elements = Sha256Elements::dummy();
+        (ChainEval { log_size, elements }, trace)
+    });
+}
+
+/// Like [`sha256_trace`], for SHA3-256.
+fn sha3_trace(
+    rng: &mut StdRng,
+    log_size: fn(usize) -> u32,
+    rows: fn(&Sha3Witness) -> &[Sha3Row],
+) -> (u32, DebugTrace) {
+    let air = Sha3Air {
+        messages: random_messages(rng, keccak::reference::MAX_SINGLE_BLOCK_LEN),
+        chain_length: rng.gen_range(1..3),
+    };
+    let (_, witness) = air.gen_witness();
+    let log_size = log_size(air.n_instances());
+    let trace = committed_trace(
+        &keccak::preprocessed_registry(air.n_instances()),
+        log_size,
+        &Sha3Row::gen_trace(log_size, rows(&witness)),
+    );
+    (log_size, trace)
+}
+
+#[test]
+fn test_sha3_round_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let (log_size, trace) = sha3_trace(rng, keccak::rounds_log_size, |witness| &witness.round);
+        let elements = Sha3Elements::dummy();
+        (keccak::round::RoundEval { log_size, elements }, trace)
+    });
+}
+
+#[test]
+fn test_sha3_sponge_trace() {
+    assert_consistent(DEFAULT_N_SEEDS, |rng| {
+        let (log_size, trace) =
+            sha3_trace(rng, keccak::instances_log_size, |witness| &witness.sponge);
+        let elements = Sha3Elements::dummy();
+        (SpongeEval { log_size, elements }, trace)
+    });
+}
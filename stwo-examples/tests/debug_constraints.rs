@@ -0,0 +1,113 @@
+//! Row-by-row constraint evaluation, and the rejection of traces whose columns do not all have the
+//! size of the component.
+
+use num_traits::One;
+use stwo_examples::{
+    airs::fibonacci::{fibonacci, FibonacciEval},
+    debug::constraints::{find_constraint_failures, DebugTrace},
+    preprocessed::{IsFirst, IsLast, PreprocessedColumn},
+};
+use stwo_prover::core::{backend::simd::m31::LOG_N_LANES, fields::m31::M31};
+
+const LOG_N_STEPS: u32 = LOG_N_LANES + 1;
+
+/// The values of `column` in row order.
+fn values(column: &impl PreprocessedColumn) -> Vec<M31> {
+    (0..1 << column.log_size())
+        .map(|row| column.value_at_row(row))
+        .collect()
+}
+
+/// The columns `a, b` of a single Fibonacci instance, in row order.
+fn fibonacci_columns() -> Vec<Vec<M31>> {
+    let (mut a, mut b) = (vec![M31::one()], vec![M31::one()]);
+    for row in 1..1 << LOG_N_STEPS {
+        a.push(b[row - 1]);
+        b.push(a[row - 1] + b[row - 1]);
+    }
+    vec![a, b]
+}
+
+fn eval() -> FibonacciEval {
+    FibonacciEval {
+        log_n_steps: LOG_N_STEPS,
+        n_instances: 1,
+        result: fibonacci(1 << LOG_N_STEPS),
+    }
+}
+
+#[test]
+fn test_fibonacci_trace_holds() {
+    let trace = DebugTrace::from_row_order(
+        vec![
+            (
+                IsFirst::new(LOG_N_STEPS).id(),
+                values(&IsFirst::new(LOG_N_STEPS)),
+            ),
+            (
+                IsLast::new(LOG_N_STEPS).id(),
+                values(&IsLast::new(LOG_N_STEPS)),
+            ),
+        ],
+        fibonacci_columns(),
+    );
+    assert!(find_constraint_failures(&eval(), &trace).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "preprocessed `is_last_5` has 64 rows, but the component has 32")]
+fn test_long_preprocessed_column_is_rejected() {
+    // Only the first half of `IsLast` of twice the size would be read, and it is all zeros, so the
+    // last row would go unchecked
+    let is_last = IsLast::new(LOG_N_STEPS + 1);
+    let trace = DebugTrace::from_row_order(
+        vec![
+            (
+                IsFirst::new(LOG_N_STEPS).id(),
+                values(&IsFirst::new(LOG_N_STEPS)),
+            ),
+            (IsLast::new(LOG_N_STEPS).id(), values(&is_last)),
+        ],
+        fibonacci_columns(),
+    );
+    find_constraint_failures(&eval(), &trace);
+}
+
+#[test]
+#[should_panic(expected = "preprocessed `is_first_5` has 16 rows, but the component has 32")]
+fn test_short_preprocessed_column_is_rejected() {
+    // Wrapping `IsFirst` of half the size around would also set it in the middle of the trace
+    let is_first = IsFirst::new(LOG_N_STEPS - 1);
+    let trace = DebugTrace::from_row_order(
+        vec![
+            (IsFirst::new(LOG_N_STEPS).id(), values(&is_first)),
+            (
+                IsLast::new(LOG_N_STEPS).id(),
+                values(&IsLast::new(LOG_N_STEPS)),
+            ),
+        ],
+        fibonacci_columns(),
+    );
+    find_constraint_failures(&eval(), &trace);
+}
+
+#[test]
+#[should_panic(expected = "tree 1 column 1 has 16 rows, but the component has 32")]
+fn test_short_trace_column_is_rejected() {
+    let mut columns = fibonacci_columns();
+    columns[1].truncate(1 << (LOG_N_STEPS - 1));
+    let trace = DebugTrace::from_row_order(
+        vec![
+            (
+                IsFirst::new(LOG_N_STEPS).id(),
+                values(&IsFirst::new(LOG_N_STEPS)),
+            ),
+            (
+                IsLast::new(LOG_N_STEPS).id(),
+                values(&IsLast::new(LOG_N_STEPS)),
+            ),
+        ],
+        columns,
+    );
+    find_constraint_failures(&eval(), &trace);
+}
@@ -0,0 +1,200 @@
+//! The gadgets on traces from their own generators, and on tampered copies of those traces.
+
+use num_bigint::BigUint;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use stwo_examples::{
+    airs::{
+        is_zero::{gen_is_zero_trace, IsZeroEval},
+        mini_cairo::{
+            fibonacci_program,
+            opcode::{gen_opcode_trace, OpcodeEval},
+            vm::{run, Opcode},
+            InstructionElements, MemoryElements, RegistersElements,
+        },
+    },
+    debug::constraints::{find_constraint_failures, DebugTrace},
+    gadgets::felt252::{gen_add_trace, prime, Felt252AddEval, Felt252Limbs, N_LIMBS},
+};
+use stwo_prover::core::{
+    backend::{simd::m31::LOG_N_LANES, Column},
+    fields::m31::M31,
+};
+
+const LOG_SIZE: u32 = LOG_N_LANES;
+
+/// The columns `value, inv, flag` of [`IsZeroEval`], in row order, where every even row holds a
+/// zero value.
+fn is_zero_columns() -> Vec<Vec<M31>> {
+    let values = (0..1u32 << LOG_SIZE)
+        .map(|row| M31::from(if row % 2 == 0 { 0 } else { row * 1000 + 7 }))
+        .collect::<Vec<_>>();
+    gen_is_zero_trace(&values)
+        .iter()
+        .map(|column| column.to_cpu())
+        .collect()
+}
+
+/// The rows and constraint indices at which the IsZero constraints fail on `columns`.
+fn is_zero_failures(columns: Vec<Vec<M31>>) -> Vec<(usize, usize)> {
+    let eval = IsZeroEval { log_size: LOG_SIZE };
+    find_constraint_failures(&eval, &DebugTrace::from_row_order(vec![], columns))
+        .into_iter()
+        .map(|failure| (failure.row, failure.constraint_index))
+        .collect()
+}
+
+#[test]
+fn test_is_zero_holds_on_zero_and_non_zero_values() {
+    let columns = is_zero_columns();
+    assert_eq!(columns[2][0], M31::from(1));
+    assert_eq!(columns[2][1], M31::from(0));
+    assert_eq!(is_zero_failures(columns), []);
+}
+
+#[test]
+fn test_is_zero_rejects_a_wrong_inverse() {
+    // Not the inverse of a non-zero value
+    let mut columns = is_zero_columns();
+    columns[1][1] += M31::from(1);
+    assert!(is_zero_failures(columns).contains(&(1, 0)));
+
+    // A non-zero inverse of zero
+    let mut columns = is_zero_columns();
+    columns[1][2] = M31::from(5);
+    assert_eq!(is_zero_failures(columns), [(2, 1)]);
+}
+
+#[test]
+fn test_is_zero_rejects_a_wrong_flag() {
+    for row in [0, 1] {
+        let mut columns = is_zero_columns();
+        columns[2][row] = M31::from(1) - columns[2][row];
+        assert_eq!(is_zero_failures(columns), [(row, 2)]);
+    }
+}
+
+/// The constraints of [`Felt252AddEval`] that come before those showing that `dst` is reduced:
+/// `sub_p_bit` is a bit, 27 carries and the last limb.
+const N_ADD_CONSTRAINTS: usize = N_LIMBS + 1;
+
+/// The edge cases `0 + 0`, `(P - 1) + 1` and `(P - 1) + (P - 1)`, followed by random additions.
+fn felt252_operands() -> Vec<(BigUint, BigUint)> {
+    let p = prime();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut operands = vec![
+        (BigUint::from(0u32), BigUint::from(0u32)),
+        (&p - 1u32, BigUint::from(1u32)),
+        (&p - 1u32, &p - 1u32),
+    ];
+    while operands.len() < 1 << LOG_SIZE {
+        let [a, b] = [(); 2].map(|_| BigUint::from_slice(&rng.gen::<[u32; 8]>()) % &p);
+        operands.push((a, b));
+    }
+    operands
+}
+
+/// The columns of [`Felt252AddEval`] for [`felt252_operands`], in row order.
+fn felt252_add_columns() -> Vec<Vec<M31>> {
+    gen_add_trace(LOG_SIZE, &felt252_operands())
+        .iter()
+        .map(|column| column.to_cpu())
+        .collect()
+}
+
+fn felt252_add_failures(columns: Vec<Vec<M31>>) -> Vec<(usize, usize)> {
+    let eval = Felt252AddEval { log_size: LOG_SIZE };
+    find_constraint_failures(&eval, &DebugTrace::from_row_order(vec![], columns))
+        .into_iter()
+        .map(|failure| (failure.row, failure.constraint_index))
+        .collect()
+}
+
+/// Overwrites the limbs of `value` starting at column `first` of `row`.
+fn set_limbs(columns: &mut [Vec<M31>], first: usize, row: usize, value: &BigUint) {
+    let limbs = Felt252Limbs::from_biguint(value).to_m31();
+    for (column, limb) in columns[first..first + N_LIMBS].iter_mut().zip(limbs) {
+        column[row] = limb;
+    }
+}
+
+#[test]
+fn test_felt252_add_matches_reference() {
+    let p = prime();
+    let columns = felt252_add_columns();
+    for (row, (a, b)) in felt252_operands().iter().enumerate() {
+        let dst = Felt252Limbs(std::array::from_fn(|i| columns[2 * N_LIMBS + i][row].0));
+        assert_eq!(dst.to_biguint(), (a + b) % &p, "wrong sum at row {row}");
+    }
+    assert_eq!(felt252_add_failures(columns), []);
+}
+
+#[test]
+fn test_felt252_add_rejects_a_wrong_sub_p_bit() {
+    // Claim that P was not subtracted from `(P - 1) + 1`, keeping the reduced result
+    let mut columns = felt252_add_columns();
+    columns[3 * N_LIMBS][1] = M31::from(0);
+    let failures = felt252_add_failures(columns);
+    assert!(!failures.is_empty());
+    assert!(failures.iter().all(|&(row, _)| row == 1));
+}
+
+#[test]
+fn test_felt252_add_rejects_an_unreduced_dst() {
+    // `(P - 1) + 1 = P` without subtracting P satisfies the addition, but `P` is not reduced
+    let mut columns = felt252_add_columns();
+    columns[3 * N_LIMBS][1] = M31::from(0);
+    set_limbs(&mut columns, 2 * N_LIMBS, 1, &prime());
+    let failures = felt252_add_failures(columns.clone());
+    assert!(!failures.is_empty());
+    assert!(failures
+        .iter()
+        .all(|&(row, constraint)| row == 1 && constraint >= N_ADD_CONSTRAINTS));
+
+    // No complement makes `P` reduced: the one of `P - 1` is 0, and that of `P` would be -1
+    set_limbs(&mut columns, 3 * N_LIMBS + 1, 1, &BigUint::from(0u32));
+    assert!(!felt252_add_failures(columns).is_empty());
+}
+
+/// The columns `enabler, pc, ap, fp, dst_off, op0_off, op1_off, dst, op0, op1` of the `Mul`
+/// component for the Fibonacci program, in row order, and its evaluator.
+fn mul_opcode_trace() -> (OpcodeEval, Vec<Vec<M31>>) {
+    let execution = run(&fibonacci_program(3)).unwrap();
+    let steps = execution
+        .steps
+        .into_iter()
+        .filter(|step| step.instruction.opcode == Opcode::Mul)
+        .collect::<Vec<_>>();
+    assert_eq!(steps.len(), 1);
+    let eval = OpcodeEval {
+        opcode: Opcode::Mul,
+        log_size: LOG_SIZE,
+        memory_elements: MemoryElements::dummy(),
+        instruction_elements: InstructionElements::dummy(),
+        registers_elements: RegistersElements::dummy(),
+    };
+    let columns = gen_opcode_trace(LOG_SIZE, &steps)
+        .iter()
+        .map(|column| column.to_cpu())
+        .collect();
+    (eval, columns)
+}
+
+#[test]
+fn test_enabler_frees_the_padding_rows() {
+    let (eval, mut columns) = mul_opcode_trace();
+    // `op0` on a padding row, which is not a product
+    columns[8][1] = M31::from(3);
+    let trace = DebugTrace::from_row_order(vec![], columns);
+    assert!(find_constraint_failures(&eval, &trace).is_empty());
+}
+
+#[test]
+fn test_enabler_keeps_the_real_rows_constrained() {
+    let (eval, mut columns) = mul_opcode_trace();
+    // `dst` on the executed row
+    columns[7][0] += M31::from(1);
+    let trace = DebugTrace::from_row_order(vec![], columns);
+    let failures = find_constraint_failures(&eval, &trace);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].row, 0);
+}
@@ -0,0 +1,23 @@
+//! The sizes of the preprocessed columns the SIMD backend is able to commit to.
+
+use stwo_examples::preprocessed::{IsFirst, PreprocessedColumn, RangeCheck};
+use stwo_prover::core::backend::{simd::m31::LOG_N_LANES, Column};
+
+#[test]
+fn test_small_range_check_is_repeated_to_a_simd_vector() {
+    let range_check = RangeCheck::new(2);
+    assert_eq!(range_check, RangeCheck::repeated(2, LOG_N_LANES));
+    assert_eq!(range_check.gen_column().values.len(), 1 << LOG_N_LANES);
+    for row in 0..1 << LOG_N_LANES {
+        assert_eq!(range_check.value_at_row(row).0, row as u32 % 4);
+    }
+
+    // Tables of a SIMD vector or more are not repeated
+    assert_eq!(RangeCheck::new(8).log_size(), 8);
+}
+
+#[test]
+#[should_panic(expected = "preprocessed column `is_first_3` has log size 3")]
+fn test_column_smaller_than_a_simd_vector_panics() {
+    IsFirst::new(LOG_N_LANES - 1).gen_column();
+}
@@ -0,0 +1,70 @@
+//! Checks that components are only built on registries holding the preprocessed columns they read.
+
+use num_traits::Zero;
+use stwo_examples::{
+    airs::fibonacci::{self, fibonacci, FibonacciEval},
+    preprocessed::{
+        registry::{referenced_columns, PreprocessedRegistry},
+        IsFirst, IsLast, PreprocessedColumn,
+    },
+};
+use stwo_prover::core::{backend::simd::m31::LOG_N_LANES, fields::qm31::SecureField};
+
+const LOG_N_STEPS: u32 = LOG_N_LANES;
+
+fn eval() -> FibonacciEval {
+    FibonacciEval {
+        log_n_steps: LOG_N_STEPS,
+        n_instances: 1,
+        result: fibonacci(1 << LOG_N_STEPS),
+    }
+}
+
+#[test]
+fn test_referenced_columns() {
+    let ids = referenced_columns(&eval())
+        .into_iter()
+        .map(|column| column.id)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            IsFirst::new(LOG_N_STEPS).id().id,
+            IsLast::new(LOG_N_STEPS).id().id
+        ]
+    );
+}
+
+#[test]
+fn test_registered_columns_pass() {
+    let registry = fibonacci::preprocessed_registry(LOG_N_STEPS);
+    registry.check_references(&eval()).unwrap();
+    registry
+        .component_builder()
+        .build(eval(), SecureField::zero());
+}
+
+#[test]
+fn test_unregistered_columns_are_reported() {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(LOG_N_STEPS));
+    let err = registry.check_references(&eval()).unwrap_err();
+    assert_eq!(err.0, [IsLast::new(LOG_N_STEPS).id().id]);
+
+    // A column of the right kind but another size is a different column
+    let registry = fibonacci::preprocessed_registry(LOG_N_STEPS + 1);
+    assert_eq!(registry.check_references(&eval()).unwrap_err().0.len(), 2);
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "referenced but not registered")
+)]
+fn test_building_on_a_missing_column_panics_in_debug_builds() {
+    let mut registry = PreprocessedRegistry::new();
+    registry.add(IsFirst::new(LOG_N_STEPS));
+    registry
+        .component_builder()
+        .build(eval(), SecureField::zero());
+}
@@ -0,0 +1,138 @@
+//! Round trips of the serialized proof format, and rejection of proofs that do not match the AIR,
+//! channel, config or version expected by the verifier.
+
+use stwo_examples::{
+    airs::{fibonacci::FibonacciAir, simple::SimpleAir},
+    pipeline::{prove_air, verify_air},
+    proof::{ConfigHeader, ProofEnvelope, ProofFormatError, PROOF_FORMAT_VERSION},
+};
+use stwo_prover::core::{
+    backend::simd::m31::LOG_N_LANES,
+    pcs::PcsConfig,
+    vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+};
+
+type Envelope = ProofEnvelope<SimpleAir, Blake2sMerkleChannel>;
+
+fn envelope() -> Envelope {
+    let config = PcsConfig::default();
+    let air = SimpleAir {
+        log_size: LOG_N_LANES,
+    };
+    ProofEnvelope::new(
+        prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap(),
+        config,
+    )
+}
+
+fn other_config() -> PcsConfig {
+    ConfigHeader {
+        n_queries: 1,
+        ..ConfigHeader::from(PcsConfig::default())
+    }
+    .into()
+}
+
+#[test]
+fn test_binary_round_trip() {
+    let config = PcsConfig::default();
+    let bytes = envelope().to_bytes().unwrap();
+    let decoded = Envelope::from_bytes(&bytes, config).unwrap();
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+    verify_air::<SimpleAir, Blake2sMerkleChannel>(decoded.into_proof(), config).unwrap();
+}
+
+#[test]
+fn test_json_round_trip() {
+    let config = PcsConfig::default();
+    let json = envelope().to_json().unwrap();
+    let decoded = Envelope::from_json(&json, config).unwrap();
+    assert_eq!(decoded.to_json().unwrap(), json);
+    verify_air::<SimpleAir, Blake2sMerkleChannel>(decoded.into_proof(), config).unwrap();
+}
+
+#[test]
+fn test_wrong_air_is_rejected() {
+    let config = PcsConfig::default();
+    let bytes = envelope().to_bytes().unwrap();
+    assert!(matches!(
+        ProofEnvelope::<FibonacciAir, Blake2sMerkleChannel>::from_bytes(&bytes, config),
+        Err(ProofFormatError::HeaderMismatch { field: "air", .. })
+    ));
+}
+
+#[test]
+fn test_wrong_channel_is_rejected() {
+    let config = PcsConfig::default();
+    let json = envelope().to_json().unwrap();
+    assert!(matches!(
+        ProofEnvelope::<SimpleAir, Poseidon252MerkleChannel>::from_json(&json, config),
+        Err(ProofFormatError::HeaderMismatch {
+            field: "channel",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_wrong_config_is_rejected() {
+    let bytes = envelope().to_bytes().unwrap();
+    assert!(matches!(
+        Envelope::from_bytes(&bytes, other_config()),
+        Err(ProofFormatError::HeaderMismatch {
+            field: "config",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_wrong_version_is_rejected() {
+    let config = PcsConfig::default();
+    let version = PROOF_FORMAT_VERSION + 1;
+
+    // The version is the first field of the header
+    let mut bytes = envelope().to_bytes().unwrap();
+    bytes[..4].copy_from_slice(&version.to_le_bytes());
+    assert!(matches!(
+        Envelope::from_bytes(&bytes, config),
+        Err(ProofFormatError::UnsupportedVersion(found)) if found == version
+    ));
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(&envelope().to_json().unwrap()).unwrap();
+    value["header"]["version"] = version.into();
+    assert!(matches!(
+        Envelope::from_json(&value.to_string(), config),
+        Err(ProofFormatError::UnsupportedVersion(found)) if found == version
+    ));
+}
+
+#[test]
+fn test_truncated_input_is_rejected() {
+    let config = PcsConfig::default();
+    let bytes = envelope().to_bytes().unwrap();
+    for len in [0, 3, bytes.len() / 2, bytes.len() - 1] {
+        assert!(matches!(
+            Envelope::from_bytes(&bytes[..len], config),
+            Err(ProofFormatError::Binary(_))
+        ));
+    }
+
+    let json = envelope().to_json().unwrap();
+    assert!(matches!(
+        Envelope::from_json(&json[..json.len() / 2], config),
+        Err(ProofFormatError::Json(_))
+    ));
+}
+
+#[test]
+fn test_oversized_length_prefix_is_rejected() {
+    // The name of the AIR follows the version, prefixed with its length
+    let mut bytes = envelope().to_bytes().unwrap();
+    bytes[4..12].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+    assert!(matches!(
+        Envelope::from_bytes(&bytes, PcsConfig::default()),
+        Err(ProofFormatError::Binary(_))
+    ));
+}
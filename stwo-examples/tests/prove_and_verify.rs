@@ -0,0 +1,485 @@
+//! Proves and verifies every example AIR under several PCS configs, going through the serialized
+//! proof format as `stwo-prove` and `stwo-verify` do, and checks that the failing examples and
+//! tampered proofs are rejected at the expected stage.
+
+use std::marker::PhantomData;
+
+use num_traits::{One, Zero};
+use serde::{de::DeserializeOwned, Serialize};
+use stwo_examples::{
+    airs::{
+        bitwise::{check_n_bits, BitwiseAir},
+        catalog::{visit_air, AirVisitor, AIR_NAMES},
+        components::ComponentsAir,
+        dynamic_lookups::DynamicLookupsAir,
+        fibonacci::FibonacciAir,
+        is_zero::IsZeroAir,
+        keccak::Sha3Air,
+        local_row_constraints::LocalRowConstraintsAir,
+        local_row_constraints_fails::{LocalRowConstraintsFails1Air, LocalRowConstraintsFails2Air},
+        memory::MemoryAir,
+        mini_cairo::MiniCairoAir,
+        preprocessed_trace::PreprocessedTraceAir,
+        public_permutation::PublicPermutationAir,
+        range_check::{RangeCheckAir, RangeCheckWidths},
+        sha256::{self, Sha256Air},
+        simple::SimpleAir,
+        static_lookups::StaticLookupsAir,
+    },
+    declared::{parse_spec, verify_declared, DeclaredAir, Expr},
+    pipeline::{prove_air, verify_air, Air, AirProof, AirProver},
+    preprocessed::{
+        registry::PreprocessedRegistry, BitwiseOp, IsFirst, IsLast, PreprocessedColumn,
+    },
+    proof::{ChannelName, ConfigHeader, ProofEnvelope},
+    spreadsheet::ImportOptions,
+};
+use stwo_prover::{
+    constraint_framework::preprocessed_columns::PreProcessedColumnId,
+    core::{
+        backend::{
+            simd::{m31::LOG_N_LANES, SimdBackend},
+            BackendForChannel,
+        },
+        channel::{Channel, MerkleChannel},
+        fields::{m31::M31, qm31::SecureField},
+        pcs::{PcsConfig, TreeVec},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        prover::{ProvingError, StarkProof, VerificationError},
+        vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+        ColumnVec,
+    },
+};
+
+const LOG_SIZE: u32 = LOG_N_LANES + 1;
+
+fn configs() -> Vec<PcsConfig> {
+    let default = ConfigHeader::from(PcsConfig::default());
+    vec![
+        PcsConfig::default(),
+        ConfigHeader {
+            log_blowup_factor: 2,
+            n_queries: 10,
+            pow_bits: 0,
+            ..default
+        }
+        .into(),
+        ConfigHeader {
+            log_blowup_factor: 3,
+            n_queries: 5,
+            pow_bits: 8,
+            ..default
+        }
+        .into(),
+    ]
+}
+
+struct ProveAndVerify<MC> {
+    config: PcsConfig,
+    channel: PhantomData<MC>,
+}
+
+impl<MC: MerkleChannel + ChannelName> AirVisitor for ProveAndVerify<MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    type Output = Result<(), String>;
+
+    fn visit<A: AirProver>(self, air: &A, _n: usize) -> Self::Output
+    where
+        A::Statement0: Serialize + DeserializeOwned,
+        A::Statement1: Serialize + DeserializeOwned,
+    {
+        let proof =
+            prove_air::<A, MC>(air, self.config).map_err(|err| format!("proving failed: {err}"))?;
+        let bytes = ProofEnvelope::<A, MC>::new(proof, self.config)
+            .to_bytes()
+            .map_err(|err| err.to_string())?;
+        let envelope = ProofEnvelope::<A, MC>::from_bytes(&bytes, self.config)
+            .map_err(|err| err.to_string())?;
+        verify_air::<A, MC>(envelope.into_proof(), self.config)
+            .map_err(|err| format!("verification failed: {err}"))
+    }
+}
+
+fn assert_proves_and_verifies<MC: MerkleChannel + ChannelName>(name: &str)
+where
+    SimdBackend: BackendForChannel<MC>,
+    StarkProof<MC::H>: Serialize + DeserializeOwned,
+{
+    for config in configs() {
+        let visitor = ProveAndVerify::<MC> {
+            config,
+            channel: PhantomData,
+        };
+        visit_air(name, LOG_SIZE, visitor)
+            .unwrap()
+            .unwrap_or_else(|err| panic!("{name} with {config:?}: {err}"));
+    }
+}
+
+macro_rules! prove_and_verify_tests {
+    ($($test:ident: $air:ty,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                assert_proves_and_verifies::<Blake2sMerkleChannel>(
+                    <$air>::NAME,
+                );
+            }
+        )*
+
+        #[test]
+        fn test_every_air_is_tested() {
+            let tested = [$(<$air>::NAME),*];
+            assert_eq!(tested.len(), AIR_NAMES.len());
+            for name in AIR_NAMES {
+                assert!(tested.contains(&name), "{name} has no test");
+            }
+        }
+    };
+}
+
+prove_and_verify_tests! {
+    test_simple: SimpleAir,
+    test_preprocessed_trace: PreprocessedTraceAir,
+    test_static_lookups: StaticLookupsAir,
+    test_dynamic_lookups: DynamicLookupsAir,
+    test_components: ComponentsAir,
+    test_local_row_constraints: LocalRowConstraintsAir,
+    test_public_permutation: PublicPermutationAir,
+    test_is_zero: IsZeroAir,
+    test_bitwise: BitwiseAir,
+    test_range_check: RangeCheckAir,
+    test_memory: MemoryAir,
+    test_fibonacci: FibonacciAir,
+    test_mini_cairo: MiniCairoAir,
+    test_sha256: Sha256Air,
+    test_sha3: Sha3Air,
+}
+
+#[test]
+fn test_every_air_with_poseidon252() {
+    for name in AIR_NAMES {
+        assert_proves_and_verifies::<Poseidon252MerkleChannel>(name);
+    }
+}
+
+const DECLARED_SPEC: &str = "\
+column col_1
+column col_2
+column col_3
+preprocessed is_first = is_first
+relation values 1
+
+constraint col_1 * col_2 + col_1 - col_3
+constraint is_first * (col_2[1] - col_1)
+lookup values 1 : col_1
+lookup values -1 : col_2
+";
+
+const DECLARED_TRACE: &str = "\
+col_1,col_2,col_3
+5,7,40
+7,5,42
+";
+
+#[test]
+fn test_declared() {
+    let spec = parse_spec(DECLARED_SPEC).unwrap();
+    let options = ImportOptions {
+        min_log_size: LOG_SIZE,
+        ..ImportOptions::default()
+    };
+    let air = DeclaredAir::from_spreadsheet(spec.clone(), DECLARED_TRACE, options).unwrap();
+    for config in configs() {
+        let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+        verify_declared::<Blake2sMerkleChannel>(proof, &spec, config).unwrap();
+    }
+}
+
+#[test]
+fn test_declared_rejects_another_spec() {
+    let spec = parse_spec(DECLARED_SPEC).unwrap();
+    let air =
+        DeclaredAir::from_spreadsheet(spec, DECLARED_TRACE, ImportOptions::default()).unwrap();
+    let other_spec = parse_spec(&DECLARED_SPEC.replace("col_1 * col_2", "col_2 * col_1")).unwrap();
+
+    let config = PcsConfig::default();
+    let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+    assert!(matches!(
+        verify_declared::<Blake2sMerkleChannel>(proof, &other_spec, config),
+        Err(VerificationError::InvalidStructure(_))
+    ));
+}
+
+#[test]
+fn test_local_row_constraints_fails_1_is_rejected_by_the_prover() {
+    for config in configs() {
+        let air = LocalRowConstraintsFails1Air { log_size: LOG_SIZE };
+        assert!(matches!(
+            prove_air::<_, Blake2sMerkleChannel>(&air, config),
+            Err(ProvingError::ConstraintsNotSatisfied)
+        ));
+    }
+}
+
+#[test]
+fn test_local_row_constraints_fails_2_is_rejected_by_the_prover() {
+    for config in configs() {
+        let air = LocalRowConstraintsFails2Air { log_size: LOG_SIZE };
+        assert!(matches!(
+            prove_air::<_, Blake2sMerkleChannel>(&air, config),
+            Err(ProvingError::ConstraintsNotSatisfied)
+        ));
+    }
+}
+
+#[test]
+fn test_unbalanced_claimed_sum_is_rejected_by_the_verifier() {
+    for config in configs() {
+        let air = LocalRowConstraintsAir { log_size: LOG_SIZE };
+        let mut proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+        proof.statement1.claimed_sum += SecureField::one();
+        assert!(matches!(
+            verify_air::<LocalRowConstraintsAir, Blake2sMerkleChannel>(proof, config),
+            Err(VerificationError::InvalidStructure(_))
+        ));
+    }
+}
+
+#[test]
+fn test_swapped_claimed_sums_are_rejected_by_the_verifier() {
+    for config in configs() {
+        let air = ComponentsAir { log_size: LOG_SIZE };
+        let mut proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+        let statement1 = &mut proof.statement1;
+        std::mem::swap(
+            &mut statement1.scheduling_claimed_sum,
+            &mut statement1.computing_claimed_sum,
+        );
+        // The LogUp sum still cancels out, but the claimed sums no longer match the components
+        assert!(matches!(
+            verify_air::<ComponentsAir, Blake2sMerkleChannel>(proof, config),
+            Err(VerificationError::OodsNotMatching)
+        ));
+    }
+}
+
+/// An `IsLast` column that is never set, which frees the last row of a Fibonacci trace from
+/// holding the result.
+struct NeverLast(IsLast);
+
+impl PreprocessedColumn for NeverLast {
+    fn log_size(&self) -> u32 {
+        self.0.log_size()
+    }
+
+    fn id(&self) -> PreProcessedColumnId {
+        self.0.id()
+    }
+
+    fn value_at_row(&self, _row: usize) -> M31 {
+        M31::zero()
+    }
+}
+
+/// A Fibonacci prover that claims a wrong result and commits to [`NeverLast`] instead of
+/// `IsLast`, so that every constraint holds.
+struct ForgedFibonacciAir(FibonacciAir);
+
+impl Air for ForgedFibonacciAir {
+    const NAME: &'static str = FibonacciAir::NAME;
+    const COMPONENT_NAMES: &'static [&'static str] = FibonacciAir::COMPONENT_NAMES;
+
+    type Statement0 = <FibonacciAir as Air>::Statement0;
+    type Statement1 = <FibonacciAir as Air>::Statement1;
+    type LookupElements = <FibonacciAir as Air>::LookupElements;
+    type Components = <FibonacciAir as Air>::Components;
+
+    fn check_statement(statement0: &Self::Statement0) -> Result<(), String> {
+        FibonacciAir::check_statement(statement0)
+    }
+
+    fn draw_lookup_elements(channel: &mut impl Channel) -> Self::LookupElements {
+        FibonacciAir::draw_lookup_elements(channel)
+    }
+
+    fn preprocessed_registry(statement0: &Self::Statement0) -> PreprocessedRegistry {
+        let mut registry = PreprocessedRegistry::new();
+        registry.add(IsFirst::new(statement0.log_n_steps));
+        registry.add(NeverLast(IsLast::new(statement0.log_n_steps)));
+        registry
+    }
+
+    fn log_sizes(statement0: &Self::Statement0) -> TreeVec<Vec<u32>> {
+        FibonacciAir::log_sizes(statement0)
+    }
+
+    fn claimed_sum(statement1: &Self::Statement1) -> SecureField {
+        FibonacciAir::claimed_sum(statement1)
+    }
+
+    fn components(
+        statement0: &Self::Statement0,
+        lookup_elements: &Self::LookupElements,
+        statement1: &Self::Statement1,
+    ) -> Self::Components {
+        FibonacciAir::components(statement0, lookup_elements, statement1)
+    }
+}
+
+impl AirProver for ForgedFibonacciAir {
+    type Witness = <FibonacciAir as AirProver>::Witness;
+
+    fn gen_trace(
+        &self,
+    ) -> (
+        Self::Statement0,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+        Self::Witness,
+    ) {
+        let (mut statement0, trace, witness) = self.0.gen_trace();
+        statement0.result += 1;
+        (statement0, trace, witness)
+    }
+
+    fn gen_interaction_trace(
+        &self,
+        witness: &Self::Witness,
+        lookup_elements: &Self::LookupElements,
+    ) -> (
+        Self::Statement1,
+        ColumnVec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) {
+        self.0.gen_interaction_trace(witness, lookup_elements)
+    }
+}
+
+#[test]
+fn test_forged_preprocessed_trace_is_rejected_by_the_verifier() {
+    for config in configs() {
+        let air = ForgedFibonacciAir(FibonacciAir {
+            log_n_steps: LOG_SIZE,
+            n_instances: 1,
+        });
+        // The forged proof is valid for the preprocessed trace it commits to
+        let forged = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+        verify_air::<ForgedFibonacciAir, Blake2sMerkleChannel>(forged, config).unwrap();
+
+        let forged = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+        let proof = AirProof::<FibonacciAir, _> {
+            statement0: forged.statement0,
+            statement1: forged.statement1,
+            stark_proof: forged.stark_proof,
+        };
+        assert!(matches!(
+            verify_air::<FibonacciAir, Blake2sMerkleChannel>(proof, config),
+            Err(VerificationError::InvalidStructure(_))
+        ));
+    }
+}
+
+/// Proves `air` and checks that the verifier rejects the proof once `tamper` makes its statement
+/// malformed, instead of panicking while building the components.
+fn assert_malformed_statement_is_rejected<A: AirProver>(
+    air: &A,
+    tamper: impl Fn(&mut A::Statement0),
+) {
+    let config = PcsConfig::default();
+    let mut proof = prove_air::<_, Blake2sMerkleChannel>(air, config).unwrap();
+    tamper(&mut proof.statement0);
+    assert!(matches!(
+        verify_air::<A, Blake2sMerkleChannel>(proof, config),
+        Err(VerificationError::InvalidStructure(_))
+    ));
+}
+
+#[test]
+fn test_supported_limb_sizes() {
+    let supported = (0..=32)
+        .filter(|&n_bits| check_n_bits(n_bits).is_ok())
+        .collect::<Vec<_>>();
+    assert_eq!(supported, [2, 4, 8]);
+}
+
+#[test]
+#[should_panic(expected = "limb size 1 is not supported")]
+fn test_proving_with_one_bit_limbs_panics() {
+    let air = BitwiseAir {
+        op: BitwiseOp::Xor,
+        n_bits: 1,
+        log_size: LOG_SIZE,
+    };
+    let _ = prove_air::<_, Blake2sMerkleChannel>(&air, PcsConfig::default());
+}
+
+#[test]
+fn test_malformed_statements_are_rejected_by_the_verifier() {
+    let simple = SimpleAir { log_size: LOG_SIZE };
+    for log_size in [0, LOG_N_LANES - 1, 32, u32::MAX] {
+        assert_malformed_statement_is_rejected(&simple, |statement0| {
+            statement0.log_size = log_size
+        });
+    }
+
+    let bitwise = BitwiseAir {
+        op: BitwiseOp::Xor,
+        n_bits: 4,
+        log_size: LOG_SIZE,
+    };
+    for n_bits in [0, 1, 3, 16, 32] {
+        assert_malformed_statement_is_rejected(&bitwise, |statement0| statement0.n_bits = n_bits);
+    }
+
+    let range_check = RangeCheckAir {
+        widths: RangeCheckWidths::new(&[4, 4]),
+        log_size: LOG_SIZE,
+    };
+    for widths in [vec![], vec![0, 4], vec![u32::MAX, 2], vec![1; 32]] {
+        assert_malformed_statement_is_rejected(&range_check, |statement0| {
+            statement0.widths = RangeCheckWidths(widths.clone())
+        });
+    }
+    assert_malformed_statement_is_rejected(&range_check, |statement0| {
+        statement0.consumer_log_sizes[1] = 40
+    });
+
+    let memory = MemoryAir { log_size: LOG_SIZE };
+    assert_malformed_statement_is_rejected(&memory, |statement0| statement0.memory_log_size = 1);
+
+    let fibonacci = FibonacciAir {
+        log_n_steps: LOG_SIZE,
+        n_instances: 1,
+    };
+    assert_malformed_statement_is_rejected(&fibonacci, |statement0| statement0.n_instances = 0);
+    assert_malformed_statement_is_rejected(&fibonacci, |statement0| {
+        statement0.n_instances = u32::MAX
+    });
+
+    let declared = DeclaredAir::from_spreadsheet(
+        parse_spec(DECLARED_SPEC).unwrap(),
+        DECLARED_TRACE,
+        ImportOptions::default(),
+    )
+    .unwrap();
+    assert_malformed_statement_is_rejected(&declared, |statement0| {
+        statement0.spec.constraints.push(Expr::Column {
+            column: 99,
+            offset: 0,
+        })
+    });
+
+    let sha256 = Sha256Air {
+        messages: sha256::sample_messages(2),
+        chain_length: 1,
+    };
+    assert_malformed_statement_is_rejected(&sha256, |statement0| statement0.blocks.clear());
+    assert_malformed_statement_is_rejected(&sha256, |statement0| {
+        statement0.digests.pop();
+    });
+    assert_malformed_statement_is_rejected(&sha256, |statement0| {
+        statement0.chain_length = u32::MAX
+    });
+}
@@ -0,0 +1,78 @@
+//! The reference SHA-256 against the FIPS 180-4 test vectors, and the digests proven by the AIR
+//! against the reference.
+
+use stwo_examples::{
+    airs::sha256::{
+        reference::{
+            chain, digest_block, pad_single_block, sha256_single_block, to_hex, Digest,
+            TEST_VECTORS,
+        },
+        Sha256Air,
+    },
+    pipeline::{prove_air, verify_air},
+};
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
+
+fn to_bytes(digest: &Digest) -> Vec<u8> {
+    digest.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
+
+fn test_messages() -> Vec<Vec<u8>> {
+    TEST_VECTORS
+        .iter()
+        .map(|(message, _)| message.as_bytes().to_vec())
+        .collect()
+}
+
+/// Proves `air` and checks that its digests are those of the reference.
+fn assert_proven_digests_match(air: Sha256Air) -> Vec<Digest> {
+    let config = PcsConfig::default();
+    let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+    let digests = proof.statement0.digests.clone();
+    assert_eq!(digests.len(), air.messages.len());
+    for (message, digest) in air.messages.iter().zip(&digests) {
+        assert_eq!(*digest, chain(message, air.chain_length));
+    }
+    verify_air::<Sha256Air, Blake2sMerkleChannel>(proof, config).unwrap();
+    digests
+}
+
+#[test]
+fn test_reference_matches_test_vectors() {
+    for (message, expected) in TEST_VECTORS {
+        assert_eq!(to_hex(&sha256_single_block(message.as_bytes())), expected);
+    }
+}
+
+#[test]
+fn test_digest_block_pads_the_digest_as_a_message() {
+    for (message, _) in TEST_VECTORS {
+        let digest = sha256_single_block(message.as_bytes());
+        assert_eq!(digest_block(&digest), pad_single_block(&to_bytes(&digest)));
+    }
+
+    // SHA-256(SHA-256(""))
+    assert_eq!(
+        to_hex(&chain(b"", 2)),
+        "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456"
+    );
+}
+
+#[test]
+fn test_proven_digests_match_test_vectors() {
+    let digests = assert_proven_digests_match(Sha256Air {
+        messages: test_messages(),
+        chain_length: 1,
+    });
+    for (digest, (_, expected)) in digests.iter().zip(TEST_VECTORS) {
+        assert_eq!(to_hex(digest), expected);
+    }
+}
+
+#[test]
+fn test_proven_chain_matches_reference() {
+    assert_proven_digests_match(Sha256Air {
+        messages: test_messages()[..1].to_vec(),
+        chain_length: 3,
+    });
+}
@@ -0,0 +1,72 @@
+//! The reference SHA3-256 against the NIST test vectors, and the digests proven by the AIR against
+//! the reference.
+
+use stwo_examples::{
+    airs::keccak::{
+        reference::{chain, sha3_256_single_block, to_hex, Digest, TEST_VECTORS},
+        Sha3Air,
+    },
+    pipeline::{prove_air, verify_air},
+};
+use stwo_prover::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
+
+fn to_bytes(digest: &Digest) -> Vec<u8> {
+    digest.iter().flat_map(|lane| lane.to_le_bytes()).collect()
+}
+
+fn test_messages() -> Vec<Vec<u8>> {
+    TEST_VECTORS
+        .iter()
+        .map(|(message, _)| message.as_bytes().to_vec())
+        .collect()
+}
+
+/// Proves `air` and checks that its digests are those of the reference.
+fn assert_proven_digests_match(air: Sha3Air) -> Vec<Digest> {
+    let config = PcsConfig::default();
+    let proof = prove_air::<_, Blake2sMerkleChannel>(&air, config).unwrap();
+    let digests = proof.statement0.digests.clone();
+    assert_eq!(digests.len(), air.messages.len());
+    for (message, digest) in air.messages.iter().zip(&digests) {
+        assert_eq!(*digest, chain(message, air.chain_length));
+    }
+    verify_air::<Sha3Air, Blake2sMerkleChannel>(proof, config).unwrap();
+    digests
+}
+
+#[test]
+fn test_reference_matches_test_vectors() {
+    for (message, expected) in TEST_VECTORS {
+        assert_eq!(to_hex(&sha3_256_single_block(message.as_bytes())), expected);
+    }
+}
+
+#[test]
+fn test_chain_hashes_the_previous_digest() {
+    for (message, _) in TEST_VECTORS {
+        let digest = sha3_256_single_block(message.as_bytes());
+        assert_eq!(
+            chain(message.as_bytes(), 2),
+            sha3_256_single_block(&to_bytes(&digest))
+        );
+    }
+}
+
+#[test]
+fn test_proven_digests_match_test_vectors() {
+    let digests = assert_proven_digests_match(Sha3Air {
+        messages: test_messages(),
+        chain_length: 1,
+    });
+    for (digest, (_, expected)) in digests.iter().zip(TEST_VECTORS) {
+        assert_eq!(to_hex(digest), expected);
+    }
+}
+
+#[test]
+fn test_proven_chain_matches_reference() {
+    assert_proven_digests_match(Sha3Air {
+        messages: test_messages()[..1].to_vec(),
+        chain_length: 3,
+    });
+}
@@ -0,0 +1,160 @@
+//! Importing traces from CSV and TSV spreadsheets: the cells accepted, the layout errors reported,
+//! and the padding and order of the imported columns.
+
+use std::path::Path;
+
+use stwo_examples::spreadsheet::{
+    parse_spreadsheet, Delimiter, ImportOptions, ImportedTrace, SpreadsheetError,
+};
+use stwo_prover::core::{
+    backend::{simd::m31::LOG_N_LANES, Column},
+    fields::m31::M31,
+    utils::bit_reverse_coset_to_circle_domain_order,
+};
+
+fn parse(text: &str) -> Result<ImportedTrace, SpreadsheetError> {
+    parse_spreadsheet(text, ImportOptions::default())
+}
+
+/// The values of the column `name`, in the order they are committed.
+fn values(trace: &ImportedTrace, name: &str) -> Vec<M31> {
+    trace.column(name).unwrap().values.to_cpu()
+}
+
+#[test]
+fn test_cells_must_be_canonical() {
+    for cell in ["0", "7", "2147483646"] {
+        let trace = parse(&format!("a\n{cell}\n")).unwrap();
+        assert_eq!(
+            values(&trace, "a")[0],
+            M31::from(cell.parse::<u32>().unwrap())
+        );
+    }
+    for cell in [
+        "2147483647",
+        "4294967296",
+        "+5",
+        "007",
+        "-1",
+        "1.0",
+        "0x10",
+        "1e3",
+    ] {
+        let err = parse(&format!("a\n1\n{cell}\n")).err().unwrap();
+        assert!(
+            matches!(&err, SpreadsheetError::InvalidCell { line: 3, column, cell: found }
+                if column == "a" && found == cell),
+            "`{cell}` gave {err:?}"
+        );
+    }
+}
+
+#[test]
+fn test_rows_must_have_a_cell_per_column() {
+    let err = parse("a,b\n1,2\n3\n").err().unwrap();
+    assert!(matches!(
+        err,
+        SpreadsheetError::RowLength {
+            line: 3,
+            expected: 2,
+            found: 1
+        }
+    ));
+    let err = parse("a,b\n1,2,3\n").err().unwrap();
+    assert!(matches!(
+        err,
+        SpreadsheetError::RowLength {
+            line: 2,
+            expected: 2,
+            found: 3
+        }
+    ));
+}
+
+#[test]
+fn test_columns_must_have_distinct_names() {
+    assert!(matches!(
+        parse("a,b,a\n1,2,3\n").err().unwrap(),
+        SpreadsheetError::DuplicateColumn(name) if name == "a"
+    ));
+    assert!(matches!(
+        parse("a,,c\n1,2,3\n").err().unwrap(),
+        SpreadsheetError::UnnamedColumn(1)
+    ));
+    assert!(matches!(
+        parse("\n\n").err().unwrap(),
+        SpreadsheetError::MissingHeader
+    ));
+}
+
+#[test]
+fn test_rows_are_padded_to_a_simd_vector() {
+    let trace = parse("a,b\n1,2\n3,4\n5,6\n").unwrap();
+    assert_eq!(trace.n_rows, 3);
+    assert_eq!(trace.log_size, LOG_N_LANES);
+    assert_eq!(trace.indices["a"], 0);
+    assert_eq!(trace.indices["b"], 1);
+
+    let mut expected = [1u32, 3, 5].map(M31::from).to_vec();
+    expected.resize(1 << LOG_N_LANES, M31::from(0));
+    assert_eq!(values(&trace, "a"), expected);
+}
+
+#[test]
+fn test_min_log_size() {
+    let text = "a\n1\n2\n3\n";
+    for (min_log_size, log_size) in [(0, LOG_N_LANES), (LOG_N_LANES + 2, LOG_N_LANES + 2)] {
+        let options = ImportOptions {
+            min_log_size,
+            ..ImportOptions::default()
+        };
+        let trace = parse_spreadsheet(text, options).unwrap();
+        assert_eq!(trace.log_size, log_size);
+        assert_eq!(trace.columns[0].values.len(), 1 << log_size);
+    }
+
+    // More rows than the minimum size
+    let text = format!("a\n{}", "1\n".repeat((1 << LOG_N_LANES) + 1));
+    let options = ImportOptions {
+        min_log_size: LOG_N_LANES,
+        ..ImportOptions::default()
+    };
+    let trace = parse_spreadsheet(&text, options).unwrap();
+    assert_eq!(trace.log_size, LOG_N_LANES + 1);
+}
+
+#[test]
+fn test_bit_reverse() {
+    let text = (0..10).fold("a\n".to_string(), |text, row| format!("{text}{row}\n"));
+    let in_row_order = parse(&text).unwrap();
+    let options = ImportOptions {
+        bit_reverse: true,
+        ..ImportOptions::default()
+    };
+    let bit_reversed = parse_spreadsheet(&text, options).unwrap();
+
+    let mut expected = values(&in_row_order, "a");
+    bit_reverse_coset_to_circle_domain_order(&mut expected);
+    assert_eq!(values(&bit_reversed, "a"), expected);
+}
+
+#[test]
+fn test_tsv() {
+    assert_eq!(Delimiter::from_path(Path::new("trace.tsv")), Delimiter::Tab);
+    assert_eq!(
+        Delimiter::from_path(Path::new("trace.csv")),
+        Delimiter::Comma
+    );
+    assert_eq!(Delimiter::from_path(Path::new("trace")), Delimiter::Comma);
+
+    let options = ImportOptions {
+        delimiter: Delimiter::Tab,
+        ..ImportOptions::default()
+    };
+    let trace = parse_spreadsheet("a\tb\n1\t2\n", options).unwrap();
+    assert_eq!(values(&trace, "b")[0], M31::from(2));
+
+    // Commas are not delimiters in a TSV
+    let err = parse_spreadsheet("a\tb\n1,2\t3\n", options).err().unwrap();
+    assert!(matches!(err, SpreadsheetError::InvalidCell { cell, .. } if cell == "1,2"));
+}